@@ -8,10 +8,15 @@ int main()
     CECEngine *engine = cec_engine_new();
 
     // 执行一个命令
-    CECEngineResult *result = cec_engine_execute(engine, "CREATE GAME");
+    CECEngineResult *result = nullptr;
+    if (cec_engine_execute(engine, "CREATE GAME", &result) != CEC_OK)
+    {
+        std::cerr << cec_last_error() << std::endl;
+        return 1;
+    }
 
     // 将结果转换为字符串并打印
-    char buffer[256];
+    char buffer[2048];
     cec_result_to_string(result, buffer, sizeof(buffer));
     std::cout << buffer << std::endl;
 
@@ -19,7 +24,11 @@ int main()
     cec_result_free(result);
 
     // 执行另一个命令
-    result = cec_engine_execute(engine, "GAME 1 RED MOVE (0,0) to (0,1)");
+    if (cec_engine_execute(engine, "GAME 1 RED MOVE (0,0) to (0,1)", &result) != CEC_OK)
+    {
+        std::cerr << cec_last_error() << std::endl;
+        return 1;
+    }
 
     // 将结果转换为字符串并打印
     cec_result_to_string(result, buffer, sizeof(buffer));
@@ -34,10 +34,16 @@ int main()
         std::cout << "Line " << line_number << ": " << command << std::endl;
 
         // 执行命令
-        CECEngineResult *result = cec_engine_execute(engine, command.c_str());
+        CECEngineResult *result = nullptr;
+        if (cec_engine_execute(engine, command.c_str(), &result) != CEC_OK)
+        {
+            std::cerr << "Error: " << cec_last_error() << std::endl;
+            line_number++;
+            continue;
+        }
 
         // 打印结果
-        char buffer[256];
+        char buffer[2048];
         cec_result_to_string(result, buffer, sizeof(buffer));
         std::cout << "Result: " << buffer << std::endl;
         std::cout << "----------------------------------------" << std::endl;
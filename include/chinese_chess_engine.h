@@ -2,6 +2,8 @@
 // This header file is for C and C++ programs to use the Chinese Chess Engine
 
 #include <stddef.h>
+#include <stdint.h>
+#include <stdbool.h>
 #ifndef CHINESE_CHESS_ENGINE_H
 #define CHINESE_CHESS_ENGINE_H
 
@@ -14,23 +16,298 @@ extern "C"
     typedef struct CECEngine CECEngine;
     typedef struct CECEngineResult CECEngineResult;
 
+    // Piece
+    // piece_type: 0 King, 1 Advisor, 2 Elephant, 3 Horse, 4 Chariot, 5 Cannon, 6 Pawn, 7 None
+    // color: 0 None, 1 Red, 2 Black
+    typedef struct CECPiece
+    {
+        uint8_t piece_type;
+        uint8_t color;
+    } CECPiece;
+
+    // Board coordinate
+    typedef struct CECPosition
+    {
+        uint8_t x;
+        uint8_t y;
+    } CECPosition;
+
+    // Game status
+    typedef enum CECGameStatus
+    {
+        CEC_STATUS_PLAYING = 0,
+        CEC_STATUS_RED_WON = 1,
+        CEC_STATUS_BLACK_WON = 2,
+        // The side to move has no legal move and loses
+        CEC_STATUS_STALEMATE = 3,
+        CEC_STATUS_DRAW = 4,
+    } CECGameStatus;
+
+    // Result kind
+    typedef enum CECResultKind
+    {
+        CEC_RESULT_GAME_CREATED = 0,
+        CEC_RESULT_MOVE_SUCCESS = 1,
+        CEC_RESULT_JOIN_SUCCESS = 2,
+        CEC_RESULT_UNDO_SUCCESS = 3,
+        CEC_RESULT_GAME_STATUS = 4,
+        CEC_RESULT_GAME_NOT_FOUND = 5,
+        CEC_RESULT_MOVE_FAILED = 6,
+        CEC_RESULT_JOIN_FAILED = 7,
+        CEC_RESULT_UNDO_FAILED = 8,
+        CEC_RESULT_INVALID_COMMAND = 9,
+        CEC_RESULT_GAME_LIST = 10,
+        CEC_RESULT_GAME_DELETED = 11,
+        CEC_RESULT_GAME_HISTORY = 12,
+        CEC_RESULT_GAME_BOARD = 13,
+        CEC_RESULT_LEGAL_MOVES = 14,
+        CEC_RESULT_GAME_WATCHED = 15,
+        CEC_RESULT_GAME_EVENTS = 16,
+        CEC_RESULT_DRAW_OFFERED = 17,
+        CEC_RESULT_DRAW_OFFER_FAILED = 18,
+    } CECResultKind;
+
+    // Error reason of a failed result
+    typedef enum CECErrorReason
+    {
+        CEC_ERROR_NONE = 0,
+        CEC_ERROR_GAME_NOT_FOUND = 1,
+        CEC_ERROR_MOVE_REJECTED = 2,
+        CEC_ERROR_JOIN_REJECTED = 3,
+        CEC_ERROR_UNDO_REJECTED = 4,
+        CEC_ERROR_INVALID_COMMAND = 5,
+        CEC_ERROR_DRAW_OFFER_REJECTED = 6,
+    } CECErrorReason;
+
+    // Detailed reason of a CEC_RESULT_MOVE_FAILED result
+    typedef enum CECMoveError
+    {
+        CEC_MOVE_ERROR_NONE = 0,
+        CEC_MOVE_ERROR_GAME_OVER = 1,
+        CEC_MOVE_ERROR_OUT_OF_BOUNDS = 2,
+        CEC_MOVE_ERROR_NO_PIECE = 3,
+        CEC_MOVE_ERROR_WRONG_COLOR = 4,
+        CEC_MOVE_ERROR_NOT_YOUR_TURN = 5,
+        CEC_MOVE_ERROR_OWN_PIECE_ON_TARGET = 6,
+        CEC_MOVE_ERROR_INVALID_MOVE_PATTERN = 7,
+        CEC_MOVE_ERROR_HORSE_LEG_BLOCKED = 8,
+        CEC_MOVE_ERROR_ELEPHANT_EYE_BLOCKED = 9,
+        CEC_MOVE_ERROR_ELEPHANT_CROSS_RIVER = 10,
+        CEC_MOVE_ERROR_OUT_OF_PALACE = 11,
+        CEC_MOVE_ERROR_CANNON_NEEDS_SCREEN = 12,
+        CEC_MOVE_ERROR_PATH_BLOCKED = 13,
+        CEC_MOVE_ERROR_PAWN_BACKWARD = 14,
+        CEC_MOVE_ERROR_LEAVES_KING_IN_CHECK = 15,
+        CEC_MOVE_ERROR_KINGS_FACING = 16,
+    } CECMoveError;
+
+    // Event kind
+    typedef enum CECEventKind
+    {
+        CEC_EVENT_GAME_CREATED = 0,
+        CEC_EVENT_PLAYER_JOINED = 1,
+        CEC_EVENT_MOVE_MADE = 2,
+        CEC_EVENT_UNDO = 3,
+        CEC_EVENT_GAME_FINISHED = 4,
+    } CECEventKind;
+
+    // Event passed to listeners
+    // The event and player_name are only valid during the callback
+    typedef struct CECEvent
+    {
+        CECEventKind kind;
+        uint32_t game_id;
+        // CEC_EVENT_PLAYER_JOINED
+        const char *player_name;
+        uint8_t player_color;
+        // CEC_EVENT_MOVE_MADE and CEC_EVENT_UNDO
+        uint8_t from_x;
+        uint8_t from_y;
+        uint8_t to_x;
+        uint8_t to_y;
+        CECPiece moved_piece;
+        CECPiece captured_piece;
+        bool check;
+        // CEC_EVENT_GAME_FINISHED
+        CECGameStatus status;
+    } CECEvent;
+
+    // Event callback
+    // Declared "C-unwind" on the Rust side: a callback written in a language
+    // that unwinds with Rust panics is reported as CEC_ERR_PANIC. C++
+    // exceptions must not escape a callback.
+    typedef void (*CECEventCallback)(const CECEvent *event, void *user_data);
+
+    // Status code returned by most functions
+    // On failure, cec_last_error() describes what went wrong
+    typedef enum CECStatus
+    {
+        CEC_OK = 0,
+        CEC_ERR_NULL_POINTER = 1,
+        CEC_ERR_INVALID_ARGUMENT = 2,
+        CEC_ERR_INVALID_UTF8 = 3,
+        CEC_ERR_GAME_NOT_FOUND = 4,
+        CEC_ERR_NOT_AVAILABLE = 5,
+        CEC_ERR_BUFFER_TOO_SMALL = 6,
+        CEC_ERR_REENTRANT = 7,
+        CEC_ERR_PANIC = 8,
+        CEC_ERR_ENGINE_POISONED = 9,
+    } CECStatus;
+
+    // Output format used by cec_result_to_string
+    typedef enum CECOutputFormat
+    {
+        CEC_OUTPUT_TEXT = 0,
+        CEC_OUTPUT_JSON = 1,
+    } CECOutputFormat;
+
+    // Language of the messages in results; kinds and error codes are not translated
+    typedef enum CECLanguage
+    {
+        CEC_LANGUAGE_ENGLISH = 0,
+        CEC_LANGUAGE_CHINESE = 1,
+    } CECLanguage;
+
+    // Thread safety:
+    // An engine handle may be shared between threads; every call locks the
+    // engine internally. Event callbacks run on the thread that executed the
+    // command while the engine is locked, so a callback must not call back
+    // into the same engine (such calls fail with CEC_ERR_REENTRANT).
+    // Panics inside the engine never cross the C boundary; they are reported
+    // as CEC_ERR_PANIC. After a panic the engine may be left half-updated, so
+    // every later call on it fails with CEC_ERR_ENGINE_POISONED; free it and
+    // create a new one.
+
+    // Get the error message of the most recent failed call on this thread
+    // Returns NULL if no call has failed. The string stays valid until the
+    // next failed call on the same thread. If reading the message panics, a
+    // static fallback message is returned instead
+    const char *cec_last_error(void);
+
     // Create a new engine instance
-    CECEngine *cec_engine_new();
+    // Returns NULL on failure
+    CECEngine *cec_engine_new(void);
 
     // Destroy an engine instance
+    // No other thread may use the engine while it is being destroyed
     void cec_engine_free(CECEngine *engine);
 
     // Execute a command
-    // Returns a pointer to a CECEngineResult, which must be freed with cec_result_free()
-    CECEngineResult *cec_engine_execute(CECEngine *engine, const char *command);
+    // On success *result receives a result which must be freed with cec_result_free()
+    CECStatus cec_engine_execute(CECEngine *engine, const char *command, CECEngineResult **result);
+
+    // Set the output format of an engine (CEC_OUTPUT_TEXT by default)
+    // Results of commands executed afterwards are rendered in this format
+    // by cec_result_to_string
+    CECStatus cec_engine_set_output_format(CECEngine *engine, CECOutputFormat format);
+
+    // Get the output format of an engine
+    CECStatus cec_engine_get_output_format(const CECEngine *engine, CECOutputFormat *format);
+
+    // Set the message language of an engine (CEC_LANGUAGE_ENGLISH by default)
+    // Applies to results of commands executed afterwards, in both text and JSON;
+    // Chinese text results append a Chinese message to the first line of failures
+    CECStatus cec_engine_set_language(CECEngine *engine, CECLanguage language);
+
+    // Get the message language of an engine
+    CECStatus cec_engine_get_language(const CECEngine *engine, CECLanguage *language);
 
     // Convert result to string
-    // Writes the result to the provided buffer, up to buffer_size bytes
-    void cec_result_to_string(CECEngineResult *result, char *buffer, size_t buffer_size);
+    // Uses the engine's output format and language at the time the command was executed.
+    // Writes the result to the provided buffer, truncated to buffer_size - 1 bytes
+    CECStatus cec_result_to_string(const CECEngineResult *result, char *buffer, size_t buffer_size);
+
+    // Get the kind of a result
+    CECStatus cec_result_kind(const CECEngineResult *result, CECResultKind *kind);
+
+    // Get the game id of a result
+    // Returns CEC_ERR_NOT_AVAILABLE if the result does not refer to a game
+    CECStatus cec_result_game_id(const CECEngineResult *result, uint32_t *game_id);
+
+    // Get the game status of a CEC_RESULT_GAME_STATUS or CEC_RESULT_GAME_WATCHED result
+    CECStatus cec_result_status(const CECEngineResult *result, CECGameStatus *status);
+
+    // Get the error reason of a result, CEC_ERROR_NONE for successful results
+    CECStatus cec_result_error_reason(const CECEngineResult *result, CECErrorReason *reason);
+
+    // Get the captured piece of a CEC_RESULT_MOVE_SUCCESS result
+    // piece_type is 7 (None) if nothing was captured
+    CECStatus cec_result_captured_piece(const CECEngineResult *result, CECPiece *piece);
+
+    // Get the detailed reason of a CEC_RESULT_MOVE_FAILED result
+    // Returns CEC_ERR_NOT_AVAILABLE for other results
+    CECStatus cec_result_move_error(const CECEngineResult *result, CECMoveError *error);
+
+    // Get the byte range [start, end) of the syntax error of a CEC_RESULT_INVALID_COMMAND result
+    // start == end == strlen(command) when the command ends too early
+    // Returns CEC_ERR_NOT_AVAILABLE for other results
+    CECStatus cec_result_parse_error_span(const CECEngineResult *result, size_t *start, size_t *end);
+
+    // Human readable (UTF-8) description of a move error in the given language
+    // The string is statically allocated; returns NULL for unknown values and
+    // a static fallback message if the lookup panics
+    const char *cec_move_error_description(CECMoveError error, CECLanguage language);
+
+    // Render the result as JSON, regardless of the engine's output format
+    // "message" uses the engine's language at the time the command was executed
+    // Schema (version 1):
+    //   {"version":1,"ok":bool,"kind":"MOVE_SUCCESS","game_id":1 or null,
+    //    "error":null or {"code":"MOVE_FAILED","message":"..."},"payload":object or null}
+    //   MOVE_FAILED errors also carry "reason":"HORSE_LEG_BLOCKED" (see CECMoveError)
+    //   INVALID_COMMAND errors also carry "span":[start,end] and "expected":["TO",...]
+    // *length receives the length of the JSON text, excluding the terminating '\0'.
+    // Pass a NULL buffer to query the length only. Nothing is written and
+    // CEC_ERR_BUFFER_TOO_SMALL is returned if buffer_size <= length
+    CECStatus cec_result_to_json(const CECEngineResult *result, char *buffer, size_t buffer_size, size_t *length);
 
     // Free a result instance
     void cec_result_free(CECEngineResult *result);
 
+    // Get the piece at (x, y)
+    CECStatus cec_game_get_piece(const CECEngine *engine, uint32_t game_id, uint8_t x, uint8_t y, CECPiece *piece);
+
+    // Get the whole board
+    // pieces must hold 90 elements, indexed by y * 9 + x
+    CECStatus cec_game_get_board(const CECEngine *engine, uint32_t game_id, CECPiece *pieces);
+
+    // Get the side to move, 1 for Red and 2 for Black
+    CECStatus cec_game_side_to_move(const CECEngine *engine, uint32_t game_id, uint8_t *color);
+
+    // Get whether the side to move is in check
+    CECStatus cec_game_is_in_check(const CECEngine *engine, uint32_t game_id, bool *in_check);
+
+    // Get the number of moves played
+    CECStatus cec_game_history_length(const CECEngine *engine, uint32_t game_id, size_t *length);
+
+    // Get the legal destinations of the piece at (x, y)
+    // Only pieces of the side to move in a game still in progress have any
+    // *count receives the total number of destinations. At most capacity
+    // positions are written; CEC_ERR_BUFFER_TOO_SMALL is returned if there are more
+    CECStatus cec_game_legal_moves_from(const CECEngine *engine, uint32_t game_id, uint8_t x, uint8_t y, CECPosition *targets, size_t capacity, size_t *count);
+
+    // Start watching a game as a spectator
+    // Writes {"last_seq":3,"game":{...}}, the same as the payload of WATCH GAME.
+    // Poll new events with cec_game_events_since() starting from last_seq.
+    // The length and buffer rules are the same as for cec_result_to_json()
+    CECStatus cec_game_watch(const CECEngine *engine, uint32_t game_id, char *buffer, size_t buffer_size, size_t *length);
+
+    // Get the events with a sequence number greater than seq as a JSON array
+    // Each element is the same as an event of GET GAME <id> EVENTS SINCE <seq>.
+    // Use it to poll, or to resume after a disconnect.
+    // The length and buffer rules are the same as for cec_result_to_json()
+    CECStatus cec_game_events_since(const CECEngine *engine, uint32_t game_id, uint64_t seq, char *buffer, size_t buffer_size, size_t *length);
+
+    // Offer a draw as color (1 for Red, 2 for Black)
+    // Returns CEC_ERR_NOT_AVAILABLE if the game is over
+    CECStatus cec_game_offer_draw(CECEngine *engine, uint32_t game_id, uint8_t color);
+
+    // Register an event callback
+    // user_data is passed back unchanged on every call
+    CECStatus cec_engine_add_listener(CECEngine *engine, CECEventCallback callback, void *user_data, uint64_t *listener_id);
+
+    // Remove an event callback registered with cec_engine_add_listener()
+    CECStatus cec_engine_remove_listener(CECEngine *engine, uint64_t listener_id);
+
 #ifdef __cplusplus
 }
 #endif
@@ -0,0 +1,222 @@
+use crate::core::moves::{Move, MoveList};
+use crate::core::event::{EventRecord, Subscription};
+use crate::core::ruler::Game;
+use crate::{Board, Color, Engine, GameFilter, GameStatus, GameSummary, GameView, MakeMoveError, MoveError, Notation, Piece, Position, Ruler};
+
+/// 游戏ID
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct GameId(pub u32);
+
+impl std::fmt::Display for GameId {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+/// 类型化接口的错误
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EngineError {
+    GameNotFound(GameId),
+    /// 走法被拒绝，附带原因
+    IllegalMove(MoveError),
+    /// 对局已满或该颜色已有玩家
+    JoinRejected(Color),
+    /// 没有可以撤销的走法
+    NothingToUndo,
+    /// 对局已经结束，或提和的一方不是红方或黑方
+    DrawOfferRejected,
+}
+
+impl std::fmt::Display for EngineError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            EngineError::GameNotFound(game_id) => write!(f, "game {} not found", game_id),
+            EngineError::IllegalMove(error) => write!(f, "illegal move: {}", error.code()),
+            EngineError::JoinRejected(color) => write!(f, "cannot join as {:?}", color),
+            EngineError::NothingToUndo => write!(f, "no move to undo"),
+            EngineError::DrawOfferRejected => write!(f, "cannot offer a draw"),
+        }
+    }
+}
+
+impl std::error::Error for EngineError {}
+
+/// 对一个游戏的操作，由 [`Engine::game`] 获得
+#[derive(Debug)]
+pub struct GameHandle<'a> {
+    ruler: &'a mut Ruler,
+    id: GameId,
+}
+
+impl GameHandle<'_> {
+    pub fn id(&self) -> GameId {
+        self.id
+    }
+
+    /// 对局数据
+    pub fn game(&self) -> &Game {
+        // 句柄存在期间游戏不会被删除
+        self.ruler.get_game(self.id.0).expect("game exists while its handle is alive")
+    }
+
+    // 生成合法走法时在对局的棋盘上试走并撤销，不复制棋盘
+    fn game_mut(&mut self) -> &mut Game {
+        self.ruler.get_game_mut(self.id.0).expect("game exists while its handle is alive")
+    }
+
+    /// 当前棋盘
+    pub fn board(&self) -> &Board {
+        &self.game().board
+    }
+
+    /// 当前局面的 FEN
+    pub fn fen(&self) -> String {
+        self.game().to_fen()
+    }
+
+    /// 对局的完整状态
+    pub fn state(&self) -> GameView {
+        self.game().view()
+    }
+
+    /// 对局摘要
+    pub fn summary(&self) -> GameSummary {
+        self.game().summary()
+    }
+
+    /// 按指定记谱方式列出已走的每一步
+    pub fn history(&self, notation: Notation) -> Vec<String> {
+        self.game().history(notation)
+    }
+
+    /// 以指定颜色加入对局
+    pub fn join(&mut self, name: &str, color: Color) -> Result<(), EngineError> {
+        if self.ruler.add_player_to_game(self.id.0, name.to_string(), color) {
+            Ok(())
+        } else {
+            Err(EngineError::JoinRejected(color))
+        }
+    }
+
+    /// 当前走棋方走一步，返回被吃掉的棋子
+    pub fn play(&mut self, mv: Move) -> Result<Option<Piece>, EngineError> {
+        self.ruler.make_move(self.id.0, mv)
+            .map_err(|error| self.move_error(error))?;
+        Ok(self.last_capture())
+    }
+
+    /// 以指定一方的身份走一步，不是该方走棋时返回 [`MoveError::NotYourTurn`]
+    pub fn play_as(&mut self, color: Color, mv: Move) -> Result<Option<Piece>, EngineError> {
+        self.ruler.make_move_as(self.id.0, color, mv)
+            .map_err(|error| self.move_error(error))?;
+        Ok(self.last_capture())
+    }
+
+    /// 撤销上一步
+    pub fn undo(&mut self) -> Result<(), EngineError> {
+        if self.ruler.undo_move(self.id.0) {
+            Ok(())
+        } else {
+            Err(EngineError::NothingToUndo)
+        }
+    }
+
+    /// 当前走棋方的合法走法，对局结束后为空
+    pub fn legal_moves(&mut self) -> MoveList {
+        let game = self.game_mut();
+        if game.status != GameStatus::Playing {
+            return MoveList::new();
+        }
+        game.board.legal_moves(game.current_turn)
+    }
+
+    /// 指定位置棋子的合法走法
+    pub fn legal_moves_from(&mut self, from: Position) -> MoveList {
+        let mut moves = self.legal_moves();
+        moves.retain(|mv| mv.from() == from);
+        moves
+    }
+
+    /// 以指定一方的身份提和，事件推送给观众
+    pub fn offer_draw(&mut self, color: Color) -> Result<(), EngineError> {
+        if self.ruler.offer_draw(self.id.0, color) {
+            Ok(())
+        } else {
+            Err(EngineError::DrawOfferRejected)
+        }
+    }
+
+    /// 以观众身份订阅对局事件，见 [`Ruler::subscribe`]
+    ///
+    /// `resume_from` 为已收到的最后一个事件序号，断线续传时只补发错过的事件
+    pub fn watch(&mut self, name: &str, resume_from: Option<u64>) -> Subscription {
+        self.ruler.subscribe(self.id.0, name.to_string(), resume_from)
+            .expect("game exists while its handle is alive")
+    }
+
+    /// 取消订阅
+    pub fn unwatch(&mut self, subscription_id: u64) -> bool {
+        self.ruler.unsubscribe(self.id.0, subscription_id)
+    }
+
+    /// 序号大于 `seq` 的事件
+    pub fn events_since(&self, seq: u64) -> Vec<EventRecord> {
+        self.game().events.since(seq)
+    }
+
+    /// 最后一个事件的序号，没有事件时为0
+    pub fn last_event_seq(&self) -> u64 {
+        self.game().events.last_seq()
+    }
+
+    fn move_error(&self, error: MakeMoveError) -> EngineError {
+        match error {
+            MakeMoveError::GameNotFound => EngineError::GameNotFound(self.id),
+            MakeMoveError::Rejected(error) => EngineError::IllegalMove(error),
+        }
+    }
+
+    fn last_capture(&self) -> Option<Piece> {
+        self.game().move_history.last()
+            .map(|record| record.captured_piece)
+            .filter(|piece| !piece.is_empty())
+    }
+}
+
+impl Engine {
+    /// 创建新游戏
+    pub fn create_game(&mut self) -> GameId {
+        GameId(self.game_manager.create_game())
+    }
+
+    /// 获取游戏的操作句柄
+    pub fn game(&mut self, id: GameId) -> Result<GameHandle<'_>, EngineError> {
+        if self.game_manager.get_game(id.0).is_none() {
+            return Err(EngineError::GameNotFound(id));
+        }
+        Ok(GameHandle {
+            ruler: &mut self.game_manager,
+            id,
+        })
+    }
+
+    /// 符合条件的游戏，按游戏ID排序
+    pub fn list_games(&self, filter: GameFilter) -> Vec<GameSummary> {
+        let mut games: Vec<GameSummary> = self.game_manager.get_all_games()
+            .into_iter()
+            .filter(|game| filter.matches(&game.status))
+            .map(|game| game.summary())
+            .collect();
+        games.sort_by_key(|game| game.game_id);
+        games
+    }
+
+    /// 删除游戏
+    pub fn delete_game(&mut self, id: GameId) -> Result<(), EngineError> {
+        if self.game_manager.remove_game(id.0) {
+            Ok(())
+        } else {
+            Err(EngineError::GameNotFound(id))
+        }
+    }
+}
@@ -0,0 +1,125 @@
+use std::path::Path;
+
+use chinese_chess_engine::core::record::GameRecord;
+use chinese_chess_engine::{Color, Engine, SvgOptions};
+
+const USAGE: &str = "\
+用法: chinese_chess_engine html <棋谱文件> [-o 输出文件] [--game N] [--black] [--no-coordinates]
+
+  棋谱文件          .pgn 棋谱，或每行一条指令的命令脚本（play 中 save 保存的格式）
+                    命令脚本中以 # 开头的行作为上一步的注释
+  -o                输出文件，默认与棋谱文件同名、扩展名为 .html
+  --game            命令脚本中要导出的游戏ID，默认 1
+  --black           黑方在下方
+  --no-coordinates  不显示坐标
+";
+
+#[derive(Debug, Clone)]
+struct HtmlOptions {
+    input: String,
+    output: Option<String>,
+    game_id: u32,
+    svg: SvgOptions,
+}
+
+fn parse_options(args: &[String]) -> Result<HtmlOptions, String> {
+    let mut input = None;
+    let mut options = HtmlOptions {
+        input: String::new(),
+        output: None,
+        game_id: 1,
+        svg: SvgOptions::default(),
+    };
+
+    let mut iter = args.iter();
+    while let Some(arg) = iter.next() {
+        match arg.as_str() {
+            "-o" | "--output" => options.output = Some(iter.next().ok_or("-o 需要一个文件名")?.clone()),
+            "--game" => {
+                options.game_id = iter.next()
+                    .and_then(|v| v.parse().ok())
+                    .ok_or("--game 需要一个游戏ID")?;
+            }
+            "--black" => options.svg.perspective = Color::Black,
+            "--no-coordinates" => options.svg.coordinates = false,
+            other if other.starts_with('-') => return Err(format!("未知参数: {}", other)),
+            other if input.is_none() => input = Some(other.to_string()),
+            other => return Err(format!("多余的参数: {}", other)),
+        }
+    }
+
+    options.input = input.ok_or("缺少棋谱文件")?;
+    Ok(options)
+}
+
+// 执行命令脚本并取出指定的游戏，# 开头的行作为注释
+fn load_script(text: &str, game_id: u32) -> Result<GameRecord, String> {
+    let mut engine = Engine::new();
+    let mut comments: Vec<(usize, String)> = Vec::new();
+
+    for line in text.lines() {
+        let line = line.trim();
+        if let Some(comment) = line.strip_prefix('#') {
+            let ply = engine.get_game_manager().get_game(game_id).map_or(0, |game| game.move_history.len());
+            comments.push((ply, comment.to_string()));
+        } else if !line.is_empty() {
+            engine.execute(line);
+        }
+    }
+
+    let game = engine.get_game_manager().get_game(game_id)
+        .ok_or(format!("脚本中没有游戏 {}", game_id))?;
+    let mut record = GameRecord::from_game(game);
+    for (ply, comment) in comments {
+        record.set_comment(ply, &comment);
+    }
+    Ok(record)
+}
+
+pub fn run(args: &[String]) -> i32 {
+    let options = match parse_options(args) {
+        Ok(options) => options,
+        Err(message) => {
+            eprintln!("{}\n", message);
+            eprint!("{}", USAGE);
+            return 2;
+        }
+    };
+
+    let text = match std::fs::read_to_string(&options.input) {
+        Ok(text) => text,
+        Err(error) => {
+            eprintln!("无法读取 {}: {}", options.input, error);
+            return 1;
+        }
+    };
+
+    let input = Path::new(&options.input);
+    let is_pgn = input.extension().is_some_and(|ext| ext.eq_ignore_ascii_case("pgn"))
+        || text.trim_start().starts_with('[');
+    let record = if is_pgn {
+        GameRecord::from_pgn(&text).map_err(|error| error.to_string())
+    } else {
+        load_script(&text, options.game_id)
+    };
+    let record = match record {
+        Ok(record) => record,
+        Err(message) => {
+            eprintln!("无法导入棋谱: {}", message);
+            return 1;
+        }
+    };
+
+    let output = options.output.clone()
+        .unwrap_or_else(|| input.with_extension("html").to_string_lossy().into_owned());
+    match std::fs::write(&output, record.to_html(&options.svg)) {
+        Ok(()) => {
+            println!("已导出 {} 步到 {}", record.moves.len(), output);
+            0
+        }
+        Err(error) => {
+            eprintln!("无法写入 {}: {}", output, error);
+            1
+        }
+    }
+}
@@ -0,0 +1,34 @@
+pub mod html;
+pub mod perft;
+pub mod play;
+pub mod run;
+
+const USAGE: &str = "\
+用法: chinese_chess_engine <子命令> [参数]
+
+子命令:
+  play    在终端中对弈
+  html    把棋谱导出为网页查看器
+  run     执行命令脚本并检查 EXPECT 断言
+  perft   统计走法生成的局面数，检查规则实现
+  help    显示本帮助
+";
+
+// 命令行入口，返回进程退出码
+pub fn run(args: &[String]) -> i32 {
+    match args.first().map(String::as_str) {
+        Some("play") => play::run(&args[1..]),
+        Some("html") => html::run(&args[1..]),
+        Some("run") => run::run(&args[1..]),
+        Some("perft") => perft::run(&args[1..]),
+        Some("help") | Some("--help") | Some("-h") | None => {
+            print!("{}", USAGE);
+            0
+        }
+        Some(other) => {
+            eprintln!("未知的子命令: {}\n", other);
+            eprint!("{}", USAGE);
+            2
+        }
+    }
+}
@@ -0,0 +1,136 @@
+use std::time::Instant;
+
+use chinese_chess_engine::Board;
+use chinese_chess_engine::core::perft::PerftPosition;
+
+const USAGE: &str = "\
+用法: chinese_chess_engine perft <深度> [--fen <fen>] [--divide]
+      chinese_chess_engine perft --epd <文件> [--max-depth N]
+
+  深度         统计走多少步之后的局面数
+  --fen        起始局面，默认为初始局面
+  --divide     分别列出每个走法之后的局面数，便于与其他引擎对比
+  --epd        检查文件中每个局面的局面数，每行格式为 <fen> ;D1 44 ;D2 1920
+  --max-depth  只检查不超过该深度的结果
+
+--epd 全部正确时退出码为 0，有错误时为 1
+";
+
+const INITIAL_FEN: &str = "rnbakabnr/9/1c5c1/p1p1p1p1p/9/9/P1P1P1P1P/1C5C1/9/RNBAKABNR w";
+
+#[derive(Debug, Clone, Default)]
+struct PerftOptions {
+    depth: Option<u32>,
+    fen: Option<String>,
+    divide: bool,
+    epd: Option<String>,
+    max_depth: Option<u32>,
+}
+
+fn parse_options(args: &[String]) -> Result<PerftOptions, String> {
+    let mut options = PerftOptions::default();
+
+    let mut iter = args.iter();
+    while let Some(arg) = iter.next() {
+        match arg.as_str() {
+            "--fen" => options.fen = Some(iter.next().ok_or("--fen 需要一个局面")?.clone()),
+            "--divide" => options.divide = true,
+            "--epd" => options.epd = Some(iter.next().ok_or("--epd 需要一个文件名")?.clone()),
+            "--max-depth" => {
+                options.max_depth = Some(iter.next()
+                    .and_then(|v| v.parse().ok())
+                    .ok_or("--max-depth 需要一个深度")?);
+            }
+            other if other.starts_with('-') => return Err(format!("未知参数: {}", other)),
+            other if options.depth.is_none() => {
+                options.depth = Some(other.parse().map_err(|_| format!("无效的深度: {}", other))?);
+            }
+            other => return Err(format!("多余的参数: {}", other)),
+        }
+    }
+
+    if options.epd.is_some() {
+        if options.depth.is_some() || options.fen.is_some() || options.divide {
+            return Err("--epd 不能与深度、--fen 或 --divide 同时使用".to_string());
+        }
+    } else if options.depth.is_none() {
+        return Err("缺少深度".to_string());
+    }
+    Ok(options)
+}
+
+// 计算一个局面的 perft，--divide 时先列出每个走法
+fn run_position(fen: &str, depth: u32, divide: bool) -> Result<(), String> {
+    let (mut board, color) = Board::from_fen(fen).map_err(|error| format!("无效的局面: {}", error))?;
+    let start = Instant::now();
+
+    let nodes = if divide {
+        let moves = board.divide(color, depth);
+        for (mv, nodes) in &moves {
+            println!("{}: {}", mv, nodes);
+        }
+        println!();
+        moves.iter().map(|(_, nodes)| nodes).sum()
+    } else {
+        board.perft(color, depth)
+    };
+
+    let elapsed = start.elapsed();
+    println!("depth {}: {} nodes ({:.3}s)", depth, nodes, elapsed.as_secs_f64());
+    Ok(())
+}
+
+// 检查 EPD 文件中的局面数，返回是否全部正确
+fn run_epd(path: &str, max_depth: Option<u32>) -> Result<bool, String> {
+    let text = std::fs::read_to_string(path).map_err(|error| format!("无法读取 {}: {}", path, error))?;
+    let positions = PerftPosition::parse_epd(&text).map_err(|error| format!("{}: {}", path, error))?;
+
+    let (mut passed, mut failed) = (0, 0);
+    for position in &positions {
+        for &(depth, expected) in &position.counts {
+            if max_depth.is_some_and(|max| depth > max) {
+                continue;
+            }
+
+            let nodes = position.perft(depth);
+            if nodes == expected {
+                println!("PASS {}: D{} {}", position.line, depth, nodes);
+                passed += 1;
+            } else {
+                println!("FAIL {}: D{} expected {}, found {}\n    {}", position.line, depth, expected, nodes, position.fen);
+                failed += 1;
+            }
+        }
+    }
+
+    println!("{} passed, {} failed", passed, failed);
+    Ok(failed == 0)
+}
+
+pub fn run(args: &[String]) -> i32 {
+    let options = match parse_options(args) {
+        Ok(options) => options,
+        Err(message) => {
+            eprintln!("{}\n", message);
+            eprint!("{}", USAGE);
+            return 2;
+        }
+    };
+
+    let result = match (&options.epd, options.depth) {
+        (Some(path), _) => run_epd(path, options.max_depth).map(|passed| if passed { 0 } else { 1 }),
+        (None, Some(depth)) => {
+            let fen = options.fen.as_deref().unwrap_or(INITIAL_FEN);
+            run_position(fen, depth, options.divide).map(|_| 0)
+        }
+        (None, None) => Ok(2),
+    };
+
+    match result {
+        Ok(code) => code,
+        Err(message) => {
+            eprintln!("{}", message);
+            1
+        }
+    }
+}
@@ -0,0 +1,295 @@
+use std::io::{self, BufRead, Write};
+
+use chinese_chess_engine::core::notation;
+use chinese_chess_engine::core::ruler::Game;
+use chinese_chess_engine::core::search::Searcher;
+use chinese_chess_engine::script;
+use chinese_chess_engine::{Color, Engine, GameStatus, MakeMoveError, Move, Notation, Piece, Position};
+
+const USAGE: &str = "\
+用法: chinese_chess_engine play [--red human|engine] [--black human|engine] [--level N] [--max-plies N]
+
+  --red, --black  指定双方由人还是引擎执棋，默认均为 human
+  --level         引擎的搜索深度，默认 3
+  --max-plies     双方都由引擎执棋时的最大步数，默认 300；同一局面重复三次时也会结束
+";
+
+const HELP: &str = "\
+走法可以用以下任意一种写法输入:
+  坐标      (1,2) (4,2)   或   1,2 to 4,2
+  ICCS      b2e2          或   b2-e2
+  WXF       C2.5          或   H8+7
+  中文记谱  炮八平五      或   马8进7
+其他命令:
+  undo         悔棋（对手是引擎时同时撤销引擎的一步）
+  save <文件>  将对局保存为命令脚本
+  help         显示本帮助
+  quit         退出
+";
+
+// ANSI 颜色
+const RED: &str = "\x1b[1;31m";
+const BLACK: &str = "\x1b[1;37m";
+const HIGHLIGHT: &str = "\x1b[43m";
+const RESET: &str = "\x1b[0m";
+
+// 执棋者
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Controller {
+    Human,
+    Engine,
+}
+
+#[derive(Debug, Clone)]
+struct PlayOptions {
+    red: Controller,
+    black: Controller,
+    level: u32,
+    max_plies: usize,
+}
+
+impl PlayOptions {
+    fn controller(&self, color: Color) -> Controller {
+        match color {
+            Color::Red => self.red,
+            _ => self.black,
+        }
+    }
+
+    fn engine_only(&self) -> bool {
+        self.red == Controller::Engine && self.black == Controller::Engine
+    }
+}
+
+fn parse_controller(value: Option<&String>) -> Result<Controller, String> {
+    match value.map(|v| v.to_lowercase()).as_deref() {
+        Some("human") => Ok(Controller::Human),
+        Some("engine") => Ok(Controller::Engine),
+        _ => Err("执棋者只能是 human 或 engine".to_string()),
+    }
+}
+
+fn parse_options(args: &[String]) -> Result<PlayOptions, String> {
+    let mut options = PlayOptions {
+        red: Controller::Human,
+        black: Controller::Human,
+        level: 3,
+        max_plies: 300,
+    };
+
+    let mut iter = args.iter();
+    while let Some(arg) = iter.next() {
+        match arg.as_str() {
+            "--red" => options.red = parse_controller(iter.next())?,
+            "--black" => options.black = parse_controller(iter.next())?,
+            "--level" => {
+                options.level = iter.next()
+                    .and_then(|v| v.parse().ok())
+                    .filter(|&level| level > 0)
+                    .ok_or("--level 需要一个正整数")?;
+            }
+            "--max-plies" => {
+                options.max_plies = iter.next()
+                    .and_then(|v| v.parse().ok())
+                    .filter(|&plies| plies > 0)
+                    .ok_or("--max-plies 需要一个正整数")?;
+            }
+            other => return Err(format!("未知参数: {}", other)),
+        }
+    }
+
+    Ok(options)
+}
+
+fn color_name(color: Color) -> &'static str {
+    match color {
+        Color::Red => "红方",
+        _ => "黑方",
+    }
+}
+
+// 绘制带颜色的棋盘
+fn render(game: &Game) -> String {
+    let last_move = game.move_history.last()
+        .map(|record| (record.mv.from(), record.mv.to()));
+    let separator = format!("  +{}\n", "--+".repeat(9));
+
+    let mut out = String::from("   0  1  2  3  4  5  6  7  8\n");
+    out.push_str(&separator);
+    for y in (0..10).rev() {
+        out.push_str(&format!("{} |", y));
+        for pos in (0..9).filter_map(|x| Position::try_new(x, y)) {
+            let piece = game.board.get_piece(pos);
+            let highlighted = last_move.is_some_and(|(from, to)| pos == from || pos == to);
+
+            if highlighted {
+                out.push_str(HIGHLIGHT);
+            }
+            if piece.is_empty() {
+                out.push_str("  ");
+            } else {
+                let color = if piece.color == Color::Red { RED } else { BLACK };
+                out.push_str(&format!("{}{}", color, piece.chinese_name()));
+            }
+            out.push_str(RESET);
+            out.push('|');
+        }
+        out.push('\n');
+        out.push_str(&separator);
+    }
+
+    out
+}
+
+// 被吃掉的棋子
+fn captured_pieces(game: &Game, color: Color) -> String {
+    let pieces: Vec<String> = game.move_history.iter()
+        .map(|record| record.captured_piece)
+        .filter(|piece: &Piece| !piece.is_empty() && piece.color == color)
+        .map(|piece| piece.chinese_name().to_string())
+        .collect();
+
+    if pieces.is_empty() {
+        "无".to_string()
+    } else {
+        pieces.join(" ")
+    }
+}
+
+fn print_game(game: &Game) {
+    print!("{}", render(game));
+    println!("红方被吃: {}", captured_pieces(game, Color::Red));
+    println!("黑方被吃: {}", captured_pieces(game, Color::Black));
+
+    if game.status == GameStatus::Playing && game.board.is_in_check(game.current_turn) {
+        println!("{}被将军！", color_name(game.current_turn));
+    }
+}
+
+// 当前局面（同一方走棋）在对局中出现的次数，包括当前这一次
+fn repetitions(game: &Game) -> usize {
+    let hash = game.board.get_hash();
+    let plies = game.move_history.len();
+    1 + game.move_history.iter()
+        .enumerate()
+        .filter(|(i, record)| (plies - i).is_multiple_of(2) && record.hash_before == hash)
+        .count()
+}
+
+// 走子并打印中文记谱
+fn play_move(engine: &mut Engine, game_id: u32, mv: Move) -> Result<(), MakeMoveError> {
+    let (color, text) = match engine.get_game_manager().get_game(game_id) {
+        Some(game) => (game.current_turn, Notation::Chinese.format(&game.board, mv)),
+        None => return Err(MakeMoveError::GameNotFound),
+    };
+
+    engine.get_game_manager_mut().make_move(game_id, mv)?;
+    println!("{}: {}  ({})", color_name(color), text, mv);
+    Ok(())
+}
+
+pub fn run(args: &[String]) -> i32 {
+    let options = match parse_options(args) {
+        Ok(options) => options,
+        Err(message) => {
+            eprintln!("{}\n", message);
+            eprint!("{}", USAGE);
+            return 2;
+        }
+    };
+
+    let mut engine = Engine::new();
+    let game_id = engine.get_game_manager_mut().create_game();
+    let searcher = Searcher::new(options.level);
+    let mut lines = io::stdin().lock().lines();
+
+    println!("输入 help 查看帮助");
+
+    loop {
+        let game = match engine.get_game_manager().get_game(game_id) {
+            Some(game) => game,
+            None => return 1,
+        };
+        print_game(game);
+
+        if game.status != GameStatus::Playing {
+            match game.status {
+                GameStatus::RedWon => println!("红方胜"),
+                GameStatus::BlackWon => println!("黑方胜"),
+                // 困毙的一方判负
+                GameStatus::Stalemate => println!("{}无子可走，{}胜", color_name(game.current_turn), color_name(game.current_turn.opponent())),
+                _ => println!("和棋"),
+            }
+            return 0;
+        }
+
+        // 引擎的搜索是确定的，双方都由引擎执棋时可能无限循环
+        if options.engine_only() {
+            if game.move_history.len() >= options.max_plies {
+                println!("已走 {} 步，判和", options.max_plies);
+                return 0;
+            }
+            if repetitions(game) >= 3 {
+                println!("同一局面重复三次，判和");
+                return 0;
+            }
+        }
+
+        let turn = game.current_turn;
+        if options.controller(turn) == Controller::Engine {
+            match searcher.best_move(&game.board, turn) {
+                Some(mv) => {
+                    let _ = play_move(&mut engine, game_id, mv);
+                }
+                None => return 0,
+            }
+            continue;
+        }
+
+        print!("{}> ", color_name(turn));
+        let _ = io::stdout().flush();
+
+        let line = match lines.next() {
+            Some(Ok(line)) => line,
+            _ => return 0,
+        };
+        let input = line.trim();
+
+        match input.split_whitespace().next().unwrap_or("") {
+            "" => {}
+            "quit" | "exit" => return 0,
+            "help" => print!("{}", HELP),
+            "undo" => {
+                let ruler = engine.get_game_manager_mut();
+                if !ruler.undo_move(game_id) {
+                    println!("没有可以悔的棋");
+                } else if options.controller(turn.opponent()) == Controller::Engine {
+                    // 撤销引擎的应着，轮回到人
+                    ruler.undo_move(game_id);
+                }
+            }
+            "save" => {
+                let path = input["save".len()..].trim();
+                if path.is_empty() {
+                    println!("用法: save <文件>");
+                } else {
+                    match std::fs::write(path, script::save(game)) {
+                        Ok(()) => println!("已保存到 {}", path),
+                        Err(error) => println!("保存失败: {}", error),
+                    }
+                }
+            }
+            // 对局只能只读访问，与 Searcher::best_move 一样在副本上解析，每次输入复制一次
+            _ => match notation::parse_move(&mut game.board.clone(), turn, input) {
+                Some(mv) => {
+                    match play_move(&mut engine, game_id, mv) {
+                        Ok(()) => {}
+                        Err(MakeMoveError::Rejected(error)) => println!("不合法的走法: {}", error.description()),
+                        Err(MakeMoveError::GameNotFound) => return 1,
+                    }
+                }
+                None => println!("无法识别的走法，输入 help 查看帮助"),
+            },
+        }
+    }
+}
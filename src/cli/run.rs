@@ -0,0 +1,73 @@
+use chinese_chess_engine::Engine;
+
+const USAGE: &str = "\
+用法: chinese_chess_engine run <脚本文件>... [--failures]
+
+  脚本文件    每行一条指令的命令脚本，每个文件使用新的引擎执行
+              支持 # 注释、EXPECT <结果>、EXPECT BOARD <fen> 和 LET g = CREATE GAME
+  --failures  只显示失败的语句
+
+全部通过时退出码为 0，有失败的语句时为 1
+";
+
+#[derive(Debug, Clone, Default)]
+struct RunOptions {
+    scripts: Vec<String>,
+    failures_only: bool,
+}
+
+fn parse_options(args: &[String]) -> Result<RunOptions, String> {
+    let mut options = RunOptions::default();
+    for arg in args {
+        match arg.as_str() {
+            "--failures" => options.failures_only = true,
+            other if other.starts_with('-') => return Err(format!("未知参数: {}", other)),
+            other => options.scripts.push(other.to_string()),
+        }
+    }
+
+    if options.scripts.is_empty() {
+        return Err("缺少脚本文件".to_string());
+    }
+    Ok(options)
+}
+
+pub fn run(args: &[String]) -> i32 {
+    let options = match parse_options(args) {
+        Ok(options) => options,
+        Err(message) => {
+            eprintln!("{}\n", message);
+            eprint!("{}", USAGE);
+            return 2;
+        }
+    };
+
+    let mut code = 0;
+    for path in &options.scripts {
+        let text = match std::fs::read_to_string(path) {
+            Ok(text) => text,
+            Err(error) => {
+                eprintln!("无法读取 {}: {}", path, error);
+                code = 1;
+                continue;
+            }
+        };
+
+        let report = Engine::new().run_script(&text);
+        println!("== {}", path);
+        if options.failures_only {
+            for step in report.failures() {
+                println!("FAIL {}:{}: {}\n    {}", path, step.line, step.text, step.message);
+            }
+            let (passed, failed) = report.counts();
+            println!("{} passed, {} failed", passed, failed);
+        } else {
+            println!("{}", report);
+        }
+
+        if !report.passed() {
+            code = 1;
+        }
+    }
+    code
+}
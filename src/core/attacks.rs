@@ -0,0 +1,339 @@
+use std::sync::OnceLock;
+use super::bitboard::Bitboard;
+use super::piece::Color;
+
+// 预计算的攻击表
+// 位棋盘下标为 x + y * 9，旋转位棋盘（按列存放）下标为 y + x * 10
+
+// 第 0 列的所有位置
+const FILE_0: u128 = {
+    let mut mask = 0u128;
+    let mut y = 0;
+    while y < 10{
+        mask |= 1u128 << (y * 9);
+        y += 1;
+    }
+    mask
+};
+
+// 被阻挡位置和它挡住的目标：马腿或象眼为空时才能到达目标
+type Blocked = [(u128, u128); 4];
+
+struct AttackTables{
+    // [红, 黑][位置]
+    king: [[u128; 90]; 2],
+    advisor: [[u128; 90]; 2],
+    // 可以走到该位置的将和士所在的位置，该位置须在九宫内
+    king_attackers: [[u128; 90]; 2],
+    advisor_attackers: [[u128; 90]; 2],
+    pawn: [[u128; 90]; 2],
+    // 可以走到该位置的兵所在的位置
+    pawn_attackers: [[u128; 90]; 2],
+    // 按象眼分组，只包含己方一侧的目标
+    elephant: [[Blocked; 90]; 2],
+    elephant_attackers: [[Blocked; 90]; 2],
+    // 按马腿分组
+    horse: [Blocked; 90],
+    horse_attackers: [Blocked; 90],
+
+    // 直线攻击，下标为 [线上的位置][线上的占用]，结果为线上被攻击的位置
+    rank_chariot: [[u16; 512]; 9],
+    rank_cannon: [[u16; 512]; 9],
+    file_chariot: [[u16; 1024]; 10],
+    file_cannon: [[u16; 1024]; 10],
+    // 把一列的 10 位展开为第 0 列的位棋盘
+    file_spread: [u128; 1024],
+}
+
+fn tables() -> &'static AttackTables{
+    static TABLES: OnceLock<Box<AttackTables>> = OnceLock::new();
+    TABLES.get_or_init(AttackTables::build)
+}
+
+fn bit(x: i8, y: i8) -> u128{
+    1u128 << (x as u32 + y as u32 * 9)
+}
+
+fn on_board(x: i8, y: i8) -> bool{
+    (0..9).contains(&x) && (0..10).contains(&y)
+}
+
+fn in_palace(color: Color, x: i8, y: i8) -> bool{
+    let ranks = match color{
+        Color::Red => 0..=2,
+        _ => 7..=9,
+    };
+    (3..=5).contains(&x) && ranks.contains(&y)
+}
+
+fn own_side(color: Color, y: i8) -> bool{
+    match color{
+        Color::Red => y < 5,
+        _ => y >= 5,
+    }
+}
+
+fn side_index(color: Color) -> usize{
+    match color{
+        Color::Red => 0,
+        _ => 1,
+    }
+}
+
+// 一条线上从 pos 出发的车和炮的攻击，occupied 为线上的占用
+fn line_attacks(len: i8, pos: i8, occupied: u16) -> (u16, u16){
+    let (mut chariot, mut cannon) = (0u16, 0u16);
+    for step in [-1i8, 1]{
+        let mut screened = false;
+        let mut i = pos + step;
+        while (0..len).contains(&i){
+            let square = 1u16 << i;
+            if !screened{
+                chariot |= square;
+                if occupied & square != 0{
+                    screened = true;
+                }
+            }else if occupied & square != 0{
+                // 炮架之后的第一个棋子
+                cannon |= square;
+                break;
+            }
+            i += step;
+        }
+    }
+    (chariot, cannon)
+}
+
+impl AttackTables{
+    fn build() -> Box<AttackTables>{
+        let mut t = Box::new(AttackTables{
+            king: [[0; 90]; 2],
+            advisor: [[0; 90]; 2],
+            king_attackers: [[0; 90]; 2],
+            advisor_attackers: [[0; 90]; 2],
+            pawn: [[0; 90]; 2],
+            pawn_attackers: [[0; 90]; 2],
+            elephant: [[[(0, 0); 4]; 90]; 2],
+            elephant_attackers: [[[(0, 0); 4]; 90]; 2],
+            horse: [[(0, 0); 4]; 90],
+            horse_attackers: [[(0, 0); 4]; 90],
+            rank_chariot: [[0; 512]; 9],
+            rank_cannon: [[0; 512]; 9],
+            file_chariot: [[0; 1024]; 10],
+            file_cannon: [[0; 1024]; 10],
+            file_spread: [0; 1024],
+        });
+
+        const ORTHOGONAL: [(i8, i8); 4] = [(1, 0), (-1, 0), (0, 1), (0, -1)];
+        const DIAGONAL: [(i8, i8); 4] = [(1, 1), (1, -1), (-1, 1), (-1, -1)];
+
+        for y in 0..10i8{
+            for x in 0..9i8{
+                let index = (x + y * 9) as usize;
+
+                for color in [Color::Red, Color::Black]{
+                    let side = side_index(color);
+
+                    // 将帅和士只能走到己方九宫内
+                    for (dx, dy) in ORTHOGONAL{
+                        if in_palace(color, x + dx, y + dy){
+                            t.king[side][index] |= bit(x + dx, y + dy);
+                            t.king_attackers[side][(x + dx + (y + dy) * 9) as usize] |= bit(x, y);
+                        }
+                    }
+                    for (dx, dy) in DIAGONAL{
+                        if in_palace(color, x + dx, y + dy){
+                            t.advisor[side][index] |= bit(x + dx, y + dy);
+                            t.advisor_attackers[side][(x + dx + (y + dy) * 9) as usize] |= bit(x, y);
+                        }
+                    }
+
+                    // 象走田字，目标在己方一侧；反过来看，只有目标在己方一侧时才会被攻击
+                    for (i, (dx, dy)) in DIAGONAL.into_iter().enumerate(){
+                        let (tx, ty) = (x + 2 * dx, y + 2 * dy);
+                        if !on_board(tx, ty){
+                            continue;
+                        }
+                        let eye = bit(x + dx, y + dy);
+                        if own_side(color, ty){
+                            t.elephant[side][index][i] = (eye, bit(tx, ty));
+                        }
+                        if own_side(color, y){
+                            t.elephant_attackers[side][index][i] = (eye, bit(tx, ty));
+                        }
+                    }
+
+                    // 兵向前一步，过河后可以横走
+                    let forward = if color == Color::Red{ 1 }else{ -1 };
+                    let crossed = !own_side(color, y);
+                    for (tx, ty, allowed) in [(x, y + forward, true), (x - 1, y, crossed), (x + 1, y, crossed)]{
+                        if allowed && on_board(tx, ty){
+                            t.pawn[side][index] |= bit(tx, ty);
+                            t.pawn_attackers[side][(tx + ty * 9) as usize] |= bit(x, y);
+                        }
+                    }
+                }
+
+                // 马腿在马的直线方向相邻处，挡住该方向的两个目标
+                for (i, (dx, dy)) in ORTHOGONAL.into_iter().enumerate(){
+                    if !on_board(x + dx, y + dy){
+                        continue;
+                    }
+                    let mut targets = 0;
+                    for side in [-1i8, 1]{
+                        let (tx, ty) = if dx != 0{
+                            (x + 2 * dx, y + side)
+                        }else{
+                            (x + side, y + 2 * dy)
+                        };
+                        if on_board(tx, ty){
+                            targets |= bit(tx, ty);
+                        }
+                    }
+                    t.horse[index][i] = (bit(x + dx, y + dy), targets);
+                }
+
+                // 攻击该位置的马：马腿在该位置斜方向相邻处
+                for (i, (dx, dy)) in DIAGONAL.into_iter().enumerate(){
+                    let (lx, ly) = (x + dx, y + dy);
+                    if !on_board(lx, ly){
+                        continue;
+                    }
+                    let mut horses = 0;
+                    for (hx, hy) in [(lx + dx, ly), (lx, ly + dy)]{
+                        if on_board(hx, hy){
+                            horses |= bit(hx, hy);
+                        }
+                    }
+                    t.horse_attackers[index][i] = (bit(lx, ly), horses);
+                }
+            }
+        }
+
+        for pos in 0..9{
+            for occupied in 0..512u16{
+                let (chariot, cannon) = line_attacks(9, pos as i8, occupied);
+                t.rank_chariot[pos][occupied as usize] = chariot;
+                t.rank_cannon[pos][occupied as usize] = cannon;
+            }
+        }
+        for pos in 0..10{
+            for occupied in 0..1024u16{
+                let (chariot, cannon) = line_attacks(10, pos as i8, occupied);
+                t.file_chariot[pos][occupied as usize] = chariot;
+                t.file_cannon[pos][occupied as usize] = cannon;
+            }
+        }
+        for (ranks, spread) in t.file_spread.iter_mut().enumerate(){
+            for y in 0..10{
+                if ranks & (1 << y) != 0{
+                    *spread |= 1u128 << (y * 9);
+                }
+            }
+        }
+
+        t
+    }
+}
+
+// 马腿或象眼为空的目标
+fn unblocked(groups: &Blocked, occupied: u128) -> u128{
+    groups.iter()
+        .filter(|(block, _)| occupied & block == 0)
+        .fold(0, |targets, (_, target)| targets | target)
+}
+
+// 车和炮所在的行与列的占用
+fn lines(square: usize, bitboard: &Bitboard) -> (usize, usize, usize, usize){
+    let (x, y) = (square % 9, square / 9);
+    let rank = (bitboard.occupied() >> (y * 9)) as usize & 0x1FF;
+    let file = (bitboard.file_occupancy() >> (x * 10)) as usize & 0x3FF;
+    (x, y, rank, file)
+}
+
+pub fn king_attacks(color: Color, square: usize) -> u128{
+    tables().king[side_index(color)][square]
+}
+
+pub fn advisor_attacks(color: Color, square: usize) -> u128{
+    tables().advisor[side_index(color)][square]
+}
+
+// 可以走到 square 的将的位置
+pub fn king_attackers(color: Color, square: usize) -> u128{
+    tables().king_attackers[side_index(color)][square]
+}
+
+// 可以走到 square 的士的位置
+pub fn advisor_attackers(color: Color, square: usize) -> u128{
+    tables().advisor_attackers[side_index(color)][square]
+}
+
+pub fn elephant_attacks(color: Color, square: usize, occupied: u128) -> u128{
+    unblocked(&tables().elephant[side_index(color)][square], occupied)
+}
+
+// 可以走到 square 的象的位置
+pub fn elephant_attackers(color: Color, square: usize, occupied: u128) -> u128{
+    unblocked(&tables().elephant_attackers[side_index(color)][square], occupied)
+}
+
+pub fn horse_attacks(square: usize, occupied: u128) -> u128{
+    unblocked(&tables().horse[square], occupied)
+}
+
+// 可以走到 square 的马的位置
+pub fn horse_attackers(square: usize, occupied: u128) -> u128{
+    unblocked(&tables().horse_attackers[square], occupied)
+}
+
+pub fn pawn_attacks(color: Color, square: usize) -> u128{
+    tables().pawn[side_index(color)][square]
+}
+
+// 可以走到 square 的兵的位置
+pub fn pawn_attackers(color: Color, square: usize) -> u128{
+    tables().pawn_attackers[side_index(color)][square]
+}
+
+// 车可以到达的位置，包括每个方向上第一个棋子
+pub fn chariot_attacks(square: usize, bitboard: &Bitboard) -> u128{
+    let t = tables();
+    let (x, y, rank, file) = lines(square, bitboard);
+    ((t.rank_chariot[x][rank] as u128) << (y * 9)) | (t.file_spread[t.file_chariot[y][file] as usize] << x)
+}
+
+// 炮可以吃到的位置，即每个方向上炮架之后的第一个棋子
+pub fn cannon_attacks(square: usize, bitboard: &Bitboard) -> u128{
+    let t = tables();
+    let (x, y, rank, file) = lines(square, bitboard);
+    ((t.rank_cannon[x][rank] as u128) << (y * 9)) | (t.file_spread[t.file_cannon[y][file] as usize] << x)
+}
+
+// 两个位置之间（不含两端）的位置，不在同一行或同一列时为空
+pub fn between(from: usize, to: usize) -> u128{
+    let (low, high) = (from.min(to), from.max(to));
+    if low == high{
+        return 0;
+    }
+    let range = ((1u128 << high) - 1) & !((1u128 << (low + 1)) - 1);
+    if low / 9 == high / 9{
+        range
+    }else if low % 9 == high % 9{
+        range & (FILE_0 << (low % 9))
+    }else{
+        0
+    }
+}
+
+// 依次取出位棋盘中的位置
+pub fn squares(mut bits: u128) -> impl Iterator<Item = usize>{
+    std::iter::from_fn(move ||{
+        if bits == 0{
+            return None;
+        }
+        let square = bits.trailing_zeros() as usize;
+        bits &= bits - 1;
+        Some(square)
+    })
+}
@@ -1,13 +1,20 @@
 use super::position::Position;
 use super::piece::{Piece, PieceType, Color};
 
-#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+// 棋盘共90个位置，每个位棋盘用 u128 表示
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
 pub struct Bitboard{
-    pieces: [u64; 8],   // [King Advisor Elephant Horse Chariot Cannon Pawn None]
-    colors: [u64; 3],   // [None Red Black]
-    palace: [u64; 2],   // [Red Black]
+    pieces: [u128; 8],   // [King Advisor Elephant Horse Chariot Cannon Pawn None]
+    colors: [u128; 3],   // [None Red Black]
+    palace: [u128; 2],   // [Red Black]
+    files: u128,         // 按列存放的占用（旋转位棋盘），下标为 y + x * 10
 }
 
+impl Default for Bitboard{
+    fn default() -> Self{
+        Self::new()
+    }
+}
 
 impl Bitboard{
     // 创建新的bit棋盘
@@ -16,6 +23,7 @@ impl Bitboard{
             pieces: [0; 8],
             colors: [0; 3],
             palace: [0; 2],
+            files: 0,
         }
     }
 
@@ -26,8 +34,8 @@ impl Bitboard{
         for x in 3..=5{
             for y in 0..=2{
                 let index = x + y*9;
-                if index < 64{
-                    self.palace[0] |= 1 << index;
+                if index < 90{
+                    self.palace[0] |= 1u128 << index;
                 }
             }
         }
@@ -36,8 +44,8 @@ impl Bitboard{
         for x in 3..=5 {
             for y in 7..=9 {
                 let index = x + y * 9;
-                if index < 64 {
-                    self.palace[1] |= 1 << index;
+                if index < 90 {
+                    self.palace[1] |= 1u128 << index;
                 }
             }
         }
@@ -56,8 +64,8 @@ impl Bitboard{
     // 设置棋子
     pub fn set_piece(&mut self, pos: Position, piece: Piece) {
         let index = Self::pos_to_index(pos);
-        if index < 64 {
-            let bit = 1 << index;
+        if index < 90 {
+            let bit = 1u128 << index;
             
             // 清除旧的棋子信息
             for i in 0..8 {
@@ -66,6 +74,8 @@ impl Bitboard{
             for i in 0..3 {
                 self.colors[i] &= !bit;
             }
+            let file_bit = 1u128 << (pos.y() as u32 + pos.x() as u32 * 10);
+            self.files &= !file_bit;
             
             // 设置新的棋子信息
             if piece.piece_type != PieceType::None {
@@ -87,6 +97,7 @@ impl Bitboard{
                     Color::None => 0,
                 };
                 self.colors[color_index] |= bit;
+                self.files |= file_bit;
             }
         }
     }
@@ -94,8 +105,8 @@ impl Bitboard{
     // 检查位置是否有特定类型的棋子
     pub fn has_piece(&self, pos: Position, piece_type: PieceType) -> bool {
         let index = Self::pos_to_index(pos);
-        if index < 64 {
-            let bit = 1 << index;
+        if index < 90 {
+            let bit = 1u128 << index;
             
             let piece_index = match piece_type {
                 PieceType::King => 0,
@@ -117,8 +128,8 @@ impl Bitboard{
     // 检查位置是否有特定颜色的棋子
     pub fn has_color(&self, pos: Position, color: Color) -> bool {
         let index = Self::pos_to_index(pos);
-        if index < 64 {
-            let bit = 1 << index;
+        if index < 90 {
+            let bit = 1u128 << index;
             
             let color_index = match color {
                 Color::Red => 1,
@@ -132,11 +143,57 @@ impl Bitboard{
         }
     }
     
+    // 从位棋盘还原一个位置上的棋子，各位棋盘互相矛盾时返回 None
+    pub fn piece_at(&self, pos: Position) -> Option<Piece> {
+        const TYPES: [PieceType; 7] = [
+            PieceType::King, PieceType::Advisor, PieceType::Elephant, PieceType::Horse,
+            PieceType::Chariot, PieceType::Cannon, PieceType::Pawn,
+        ];
+
+        let bit = 1u128 << Self::pos_to_index(pos);
+        let file_bit = 1u128 << (pos.y() as u32 + pos.x() as u32 * 10);
+        let mut types = TYPES.iter().filter(|piece_type| self.pieces[piece_type.from_piece_type_to_usize()] & bit != 0);
+        let piece_type = types.next().copied();
+        let red = self.colors[1] & bit != 0;
+        let black = self.colors[2] & bit != 0;
+        let occupied = self.files & file_bit != 0;
+        if types.next().is_some() || self.pieces[7] & bit != 0 || self.colors[0] & bit != 0 {
+            return None;
+        }
+
+        match (piece_type, red, black, occupied) {
+            (None, false, false, false) => Some(Piece::default()),
+            (Some(piece_type), true, false, true) => Some(Piece::new(piece_type, Color::Red)),
+            (Some(piece_type), false, true, true) => Some(Piece::new(piece_type, Color::Black)),
+            _ => None,
+        }
+    }
+
+    // 所有棋子的位置
+    pub fn occupied(&self) -> u128 {
+        self.colors[1] | self.colors[2]
+    }
+
+    // 按列存放的占用，第 x 列为第 x * 10 位起的 10 位
+    pub fn file_occupancy(&self) -> u128 {
+        self.files
+    }
+
+    // 一方所有棋子的位置
+    pub fn color_mask(&self, color: Color) -> u128 {
+        self.colors[color.from_color_to_usize()]
+    }
+
+    // 一方某种棋子的位置
+    pub fn pieces_of(&self, piece_type: PieceType, color: Color) -> u128 {
+        self.pieces[piece_type.from_piece_type_to_usize()] & self.colors[color.from_color_to_usize()]
+    }
+
     // 检查位置是否在九宫中
     pub fn is_in_palace(&self, pos: Position, color: Color) -> bool {
         let index = Self::pos_to_index(pos);
-        if index < 64 {
-            let bit = 1 << index;
+        if index < 90 {
+            let bit = 1u128 << index;
             
             let palace_index = match color {
                 Color::Red => 0,
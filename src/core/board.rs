@@ -1,8 +1,22 @@
 use super::position::Position;
 use super::piece::{Piece, PieceType, Color};
 use super::bitboard::Bitboard;
+use super::attacks;
 use super::zobrist::ZobristHash;
 use super::board_cache::BoardCache;
+use super::move_error::MoveError;
+use super::moves::{Move, MoveList};
+use super::validate::{self, Inconsistency, InconsistencyReport};
+
+// 撤销一步棋所需的信息，由 make_move 返回
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct UndoInfo{
+    pub mv: Move,
+    pub moved_piece: Piece,
+    pub captured_piece: Piece,
+    // 走子前的哈希值
+    pub hash: u64,
+}
 
 // 棋盘
 #[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
@@ -53,9 +67,24 @@ impl Board{
         board.current_hash = board.zobrist_hash.calculate(&board);
         board.cache.cache_hash(board.current_hash);
 
+        board.debug_validate();
         board
     }
 
+    // 由棋子摆放构造棋盘，用于导入 FEN 和棋盘图，摆放不合理时返回原因
+    pub fn from_pieces(pieces: impl IntoIterator<Item = (Position, Piece)>) -> Result<Board, InconsistencyReport>{
+        let mut board = Board::default();
+        for (pos, piece) in pieces{
+            board.set_piece_sync(pos, piece);
+        }
+        board.update_king_positions();
+        board.current_hash = board.zobrist_hash.calculate(&board);
+        board.cache.cache_hash(board.current_hash);
+
+        board.validate()?;
+        Ok(board)
+    }
+
     // 初始化棋盘
     fn initialize(&mut self){
         // 放置红方棋子
@@ -142,31 +171,64 @@ impl Board{
     // 直接设置棋子（用于悔棋等操作）
     pub fn set_piece(&mut self, pos: Position, piece: super::piece::Piece) {
         if pos.is_valid() {
+            let replaced = self.pieces[pos.x() as usize][pos.y() as usize];
             self.pieces[pos.x() as usize][pos.y() as usize] = piece;
             self.bitboard.set_piece(pos, piece);
             
-            // 如果设置的是王，更新缓存
-            if piece.piece_type == super::piece::PieceType::King {
+            // 如果设置或覆盖的是王，更新缓存
+            if piece.piece_type == PieceType::King || replaced.piece_type == PieceType::King {
                 self.update_king_positions();
             }
             
             // 更新哈希值
-            self.current_hash = self.zobrist_hash.calculate(&self);
+            self.current_hash = self.zobrist_hash.calculate(self);
             self.cache.clear();
             self.cache.cache_hash(self.current_hash);
+
+            // 逐个摆放时可能暂时不符合规则，只检查数据是否一致
+            #[cfg(debug_assertions)]
+            {
+                let mut issues = Vec::new();
+                self.consistency_issues(&mut issues);
+                assert!(issues.is_empty(), "board inconsistent after set_piece: {}", InconsistencyReport{ issues });
+            }
         }
     }
 
-    pub fn make_move(&mut self, from: Position, to: Position){
+    // 执行走法，返回用于 unmake_move 的恢复信息
+    pub fn make_move(&mut self, mv: Move) -> UndoInfo{
+        let undo = self.apply_move(mv);
+        self.debug_validate();
+        undo
+    }
+
+    // 撤销 make_move，恢复棋子、位棋盘、王的位置、哈希值和缓存
+    pub fn unmake_move(&mut self, undo: UndoInfo){
+        self.restore_move(undo);
+        self.debug_validate();
+    }
+
+    // make_move 的实现，试走时使用，不做调试检查
+    fn apply_move(&mut self, mv: Move) -> UndoInfo{
+        let (from, to) = (mv.from(), mv.to());
         let moved_piece = self.get_piece(from);
         let captured_piece = self.get_piece(to);
+        let undo = UndoInfo{
+            mv,
+            moved_piece,
+            captured_piece,
+            hash: self.current_hash,
+        };
 
         self.set_piece_sync(from, Piece::default());
         self.set_piece_sync(to, moved_piece);
 
-        // 如果移动的是王或者吃掉的是王，更新缓存
-        if moved_piece.piece_type == PieceType::King || captured_piece.piece_type == PieceType::King{
-            self.update_king_positions();
+        // 增量更新王的位置
+        if captured_piece.piece_type == PieceType::King{
+            self.set_king_position(captured_piece.color, None);
+        }
+        if moved_piece.piece_type == PieceType::King{
+            self.set_king_position(moved_piece.color, Some(to));
         }
 
         self.current_hash = self.zobrist_hash.update(
@@ -179,6 +241,158 @@ impl Board{
 
         self.cache.clear();
         self.cache.cache_hash(self.current_hash);
+        undo
+    }
+
+    fn restore_move(&mut self, undo: UndoInfo){
+        let (from, to) = (undo.mv.from(), undo.mv.to());
+
+        self.set_piece_sync(from, undo.moved_piece);
+        self.set_piece_sync(to, undo.captured_piece);
+
+        if undo.moved_piece.piece_type == PieceType::King{
+            self.set_king_position(undo.moved_piece.color, Some(from));
+        }
+        if undo.captured_piece.piece_type == PieceType::King{
+            self.set_king_position(undo.captured_piece.color, Some(to));
+        }
+
+        self.current_hash = undo.hash;
+        self.cache.clear();
+        self.cache.cache_hash(undo.hash);
+    }
+
+    fn set_king_position(&mut self, color: Color, pos: Option<Position>){
+        match color{
+            Color::Red => self.red_king_pos = pos,
+            Color::Black => self.black_king_pos = pos,
+            Color::None => {}
+        }
+    }
+
+    // 检查棋盘数据是否一致、摆放是否符合规则
+    // 交叉检查棋子数组、位棋盘、王的位置、哈希值和缓存，并检查棋子数量和位置
+    pub fn validate(&self) -> Result<(), InconsistencyReport>{
+        let mut issues = Vec::new();
+        self.consistency_issues(&mut issues);
+        self.placement_issues(&mut issues);
+
+        if issues.is_empty(){
+            Ok(())
+        }else{
+            Err(InconsistencyReport{ issues })
+        }
+    }
+
+    // 调试构建中，每次修改棋盘后检查
+    fn debug_validate(&self){
+        #[cfg(debug_assertions)]
+        if let Err(report) = self.validate(){
+            panic!("board inconsistent: {}", report);
+        }
+    }
+
+    // 同一信息的各处记录是否一致
+    fn consistency_issues(&self, issues: &mut Vec<Inconsistency>){
+        // 位棋盘，不一致时再逐个位置查找
+        let mut expected = Bitboard::new();
+        expected.init_special_positions();
+        for x in 0..9{
+            for y in 0..10{
+                let pos = Position::new(x, y);
+                expected.set_piece(pos, self.get_piece(pos));
+            }
+        }
+        if self.bitboard != expected{
+            let count = issues.len();
+            for x in 0..9{
+                for y in 0..10{
+                    let pos = Position::new(x, y);
+                    let piece = self.get_piece(pos);
+                    if self.bitboard.piece_at(pos) != Some(piece){
+                        issues.push(Inconsistency::Bitboard{ pos, piece });
+                    }
+                }
+            }
+            if issues.len() == count{
+                issues.push(Inconsistency::BitboardMasks);
+            }
+        }
+
+        // 王的位置
+        for (color, cached) in [(Color::Red, self.red_king_pos), (Color::Black, self.black_king_pos)]{
+            let actual = self.find_king(color);
+            if cached != actual{
+                issues.push(Inconsistency::KingPosition{ color, cached, actual });
+            }
+        }
+
+        // 哈希值
+        let computed = self.zobrist_hash.calculate(self);
+        if computed != self.current_hash{
+            issues.push(Inconsistency::Hash{ stored: self.current_hash, computed });
+        }
+
+        // 缓存
+        if let Some(cached) = self.cache.get_cached_hash() && cached != self.current_hash{
+            issues.push(Inconsistency::CachedHash{ cached, current: self.current_hash });
+        }
+        for (color, cached) in self.cache.cached_check_statuses(){
+            let actual = self.find_king(color)
+                .is_some_and(|king_pos| self.attackers_to(king_pos, color.opponent()) != 0);
+            if cached != actual{
+                issues.push(Inconsistency::CachedCheck{ color, cached, actual });
+            }
+        }
+        // validate 不修改棋盘，试走需要副本，只在确实缓存了走法时复制一次
+        let mut scratch: Option<Board> = None;
+        for (&(pos, color), targets) in self.cache.cached_legal_moves(){
+            let mut actual: Vec<Position> = if self.get_piece(pos).color == color{
+                let board = scratch.get_or_insert_with(|| self.clone());
+                board.get_legal_moves(pos).iter().map(|mv| mv.to()).collect()
+            }else{
+                Vec::new()
+            };
+            let mut cached = targets.clone();
+            actual.sort_by_key(|pos| pos.index());
+            cached.sort_by_key(|pos| pos.index());
+            if cached != actual{
+                issues.push(Inconsistency::CachedLegalMoves{ pos, color });
+            }
+        }
+    }
+
+    // 棋子数量和位置是否符合规则
+    fn placement_issues(&self, issues: &mut Vec<Inconsistency>){
+        let mut counts: Vec<(Piece, usize)> = Vec::new();
+
+        for y in 0..10{
+            for x in 0..9{
+                let pos = Position::new(x, y);
+                let piece = self.get_piece(pos);
+                if piece == Piece::default(){
+                    continue;
+                }
+                if !validate::placement_allowed(pos, piece){
+                    issues.push(Inconsistency::IllegalPlacement{ pos, piece });
+                }
+                match counts.iter_mut().find(|(counted, _)| *counted == piece){
+                    Some((_, count)) => *count += 1,
+                    None => counts.push((piece, 1)),
+                }
+            }
+        }
+
+        for (piece, count) in counts{
+            if count > validate::max_count(piece.piece_type){
+                issues.push(Inconsistency::TooManyPieces{ piece, count });
+            }
+        }
+
+        // 走子时照面由 check_move 拒绝，这里拦住导入的照面局面
+        if self.kings_facing() && let (Some(red), Some(black)) = (self.red_king_pos, self.black_king_pos){
+            issues.push(Inconsistency::KingsFacing{ red, black });
+        }
     }
 
     // 获取当前哈希值
@@ -188,27 +402,33 @@ impl Board{
 
     // 检查移动是否合法
     pub fn is_move_valid(&self, from: Position, to: Position, color: Color) -> bool {
+        self.validate_move(from, to, color).is_ok()
+    }
+
+    // 检查移动是否符合棋子走法，不合法时返回原因
+    // 不检查走子后是否被将军，见 check_move
+    pub fn validate_move(&self, from: Position, to: Position, color: Color) -> Result<(), MoveError> {
         // 检查位置是否有效
         if !from.is_valid() || !to.is_valid(){
-            return false;
+            return Err(MoveError::OutOfBounds);
         }
 
         // 检查起始位置是否存在棋子
         let from_piece = self.get_piece(from);
         if from_piece.piece_type == PieceType::None{
-            return false;
+            return Err(MoveError::NoPiece);
         }
 
         // 检查棋子颜色是否正确
         if from_piece.color != color{
-            return false;
+            return Err(MoveError::WrongColor);
         }
 
         // 检查目标位置是否有己方棋子
         let to_piece = self.get_piece(to);
         if to_piece.color == color{
             // 目标位置棋子所属不能是己方
-            return false;
+            return Err(MoveError::OwnPieceOnTarget);
         }
 
         match from_piece.piece_type {
@@ -219,46 +439,63 @@ impl Board{
             PieceType::Chariot => self.is_chariot_move_valid(from, to),
             PieceType::Cannon => self.is_cannon_move_valid(from, to),
             PieceType::Pawn => self.is_pawn_move_valid(from, to, color),
-            _ => false,
+            _ => Err(MoveError::NoPiece),
         }
     }
 
     // 检查 Advisor 移动是否合法
-    fn is_advisor_move_valid(&self, from: Position, to: Position) -> bool{
+    fn is_advisor_move_valid(&self, from: Position, to: Position) -> Result<(), MoveError>{
         let dx = (from.x() as i8 - to.x() as i8).abs();
         let dy = (from.y() as i8 - to.y() as i8).abs();
 
         // Advisor 只能九宫格内斜着走一步
         if !(dx == 1 && dy == 1){
-            return false;
+            return Err(MoveError::InvalidMovePattern);
         }
 
         let piece = self.get_piece(from);
-        self.bitboard.is_in_palace(to, piece.color)
+        if !self.bitboard.is_in_palace(to, piece.color){
+            return Err(MoveError::OutOfPalace);
+        }
+        Ok(())
     }
 
     // 检查王的移动是否合法
-    fn is_king_move_valid(&self, from: Position, to: Position) -> bool{
+    fn is_king_move_valid(&self, from: Position, to: Position) -> Result<(), MoveError>{
         let dx = (from.x() as i8 - to.x() as i8).abs();
         let dy = (from.y() as i8 - to.y() as i8).abs();
 
         // 王只能走一步
         if !(dx == 1 && dy == 0 || dx == 0 && dy ==1){
-            return false;
+            return Err(MoveError::InvalidMovePattern);
         }
 
         // 王只能在九宫格内移动
         let piece = self.get_piece(from);
-        self.bitboard.is_in_palace(to, piece.color)
+        if !self.bitboard.is_in_palace(to, piece.color){
+            return Err(MoveError::OutOfPalace);
+        }
+        Ok(())
     }
 
-    fn is_elephant_move_valid(&self, from: Position, to: Position) -> bool{
+    fn is_elephant_move_valid(&self, from: Position, to: Position) -> Result<(), MoveError>{
         let dx = (from.x() as i8 - to.x() as i8).abs();
         let dy = (from.y() as i8 - to.y() as i8).abs();
 
         // 象只能写着走两格子（田字的对角线）
         if !(dx == 2 && dy == 2){
-            return false;
+            return Err(MoveError::InvalidMovePattern);
+        }
+
+        // 象不能过河
+        let piece = self.get_piece(from);
+        let own_side = match piece.color{
+            Color::Red => to.y() < 5,
+            Color::Black => to.y() >= 5,
+            _ => false,
+        };
+        if !own_side{
+            return Err(MoveError::ElephantCrossRiver);
         }
 
         // 检查象眼是否被堵住
@@ -266,26 +503,19 @@ impl Board{
         let eye_y = (from.y() + to.y()) / 2;
         let eye_pos = Position::new(eye_x, eye_y);
         if self.get_piece(eye_pos).piece_type != PieceType::None{
-            return false;
-        }
-
-        // 象不能过河
-        let piece = self.get_piece(from);
-        match piece.color{
-            Color::Red => to.y() < 5, 
-            Color::Black => to.y() >= 5,
-            _ => false,
+            return Err(MoveError::ElephantEyeBlocked);
         }
+        Ok(())
     }
 
 
-    fn is_horse_move_valid(&self, from: Position, to: Position) -> bool{
+    fn is_horse_move_valid(&self, from: Position, to: Position) -> Result<(), MoveError>{
         let dx = (from.x() as i8 - to.x() as i8).abs();
         let dy = (from.y() as i8 - to.y() as i8).abs();
 
         // 马走日字
         if !(dx == 1 && dy == 2 || dx == 2 && dy == 1){
-            return false;
+            return Err(MoveError::InvalidMovePattern);
         }
 
         // 检查是否绊马腿
@@ -303,25 +533,31 @@ impl Board{
         }
 
         let leg_pos = Position::new(leg_x, leg_y);
-        self.get_piece(leg_pos).piece_type == PieceType::None 
+        if self.get_piece(leg_pos).piece_type != PieceType::None{
+            return Err(MoveError::HorseLegBlocked);
+        }
+        Ok(())
     }
 
     // 检查车的移动是否符合规则
-    fn is_chariot_move_valid(&self, from: Position, to: Position) -> bool{
+    fn is_chariot_move_valid(&self, from: Position, to: Position) -> Result<(), MoveError>{
         // 车只能直线移动
         if from.x() != to.x() && from.y() != to.y(){
-            return false;
+            return Err(MoveError::InvalidMovePattern);
         }
 
         // 检查路径是否有阻挡
-        self.is_path_clear(from, to)
+        if !self.is_path_clear(from, to){
+            return Err(MoveError::PathBlocked);
+        }
+        Ok(())
     }
 
     // 检查炮的移动是否符合规则
-    fn is_cannon_move_valid(&self, from: Position, to: Position) -> bool{
+    fn is_cannon_move_valid(&self, from: Position, to: Position) -> Result<(), MoveError>{
         // 炮只能直线移动
         if from.x() != to.x() && from.y() != to.y(){
-            return false;
+            return Err(MoveError::InvalidMovePattern);
         }
 
         // 检查是否有棋子被吃掉
@@ -333,28 +569,32 @@ impl Board{
 
         // 炮移动时，如果没有吃子，路径必须清空
         // 如果吃子，路径上必须有且只有一个棋子
-        if has_capture{
-            piece_count == 1
-        }else{
-            piece_count == 0
+        match (has_capture, piece_count){
+            (false, 0) | (true, 1) => Ok(()),
+            (true, 0) => Err(MoveError::CannonNeedsScreen),
+            _ => Err(MoveError::PathBlocked),
         }
     }
 
     // 检查兵的移动是否合法
-    fn is_pawn_move_valid(&self, from: Position, to: Position, color: Color) -> bool{
+    fn is_pawn_move_valid(&self, from: Position, to: Position, color: Color) -> Result<(), MoveError>{
         let dx = (from.x() as i8 - to.x() as i8).abs();
         let dy = (from.y() as i8 - to.y() as i8).abs();
 
-        // 兵只能移动一步并且过河之前不能横着走
-        if !(dx == 0 && dy == 1 || (dx == 1 && dy == 0 && self.is_pawn_crossed_river(from, color))){
-            return false;
-        }
-
-        match color{
+        let forward = match color{
             Color::Red => to.y() > from.y(),
             Color::Black => to.y() < from.y(),
             _ => false,
+        };
+        if dx == 0 && dy == 1 && !forward{
+            return Err(MoveError::PawnBackward);
         }
+
+        // 兵只能移动一步并且过河之前不能横着走
+        if !(dx == 0 && dy == 1 || (dx == 1 && dy == 0 && self.is_pawn_crossed_river(from, color))){
+            return Err(MoveError::InvalidMovePattern);
+        }
+        Ok(())
     }
 
     // 检查兵是否过河
@@ -371,34 +611,10 @@ impl Board{
         self.count_pieces_on_path(from, to) == 0
     }
 
-    // 计算路径上棋子的数量
+    // 计算路径上棋子的数量，两个位置不在同一直线上时为 0
     fn count_pieces_on_path(&self, from: Position, to: Position) -> u32{
-        let mut count = 0;
-
-        if from.x() == to.x(){
-            // 垂直移动
-            let start = std::cmp::min(from.y(), to.y()) + 1;
-            let end = std::cmp::max(from.y(), to.y());
-
-            for y in start..end{
-                let pos = Position::new(from.x(), y);
-                if self.get_piece(pos).piece_type != PieceType::None{
-                    count += 1;
-                }
-            }
-        }else if from.y() == to.y(){
-            // 水平移动
-            let start = std::cmp::min(from.x(), to.x()) + 1;
-            let end = std::cmp::max(from.x(), to.x());
-
-            for x in start..end{
-                let pos = Position::new(x, from.y());
-                if self.get_piece(pos).piece_type != PieceType::None{
-                    count += 1;
-                }
-            }
-        }
-        count 
+        let path = attacks::between(from.index() as usize, to.index() as usize);
+        (self.bitboard.occupied() & path).count_ones()
     }
 
     // 检查是否将军
@@ -408,113 +624,232 @@ impl Board{
             return status;
         }
 
-        // 找到己方王的位置(使用缓存位置，避免重复搜索)
-        let king_pos = match color{
+        self.checkers(color) != 0
+    }
+
+    // 缓存的王的位置，避免重复搜索
+    fn king_position(&self, color: Color) -> Option<Position>{
+        match color{
             Color::Red => self.red_king_pos,
             Color::Black => self.black_king_pos,
-            _ => return false,
+            _ => None,
+        }
+    }
+
+    // color 一方可以走到 square 的棋子，以位棋盘表示
+    // 只考虑走法，不考虑走子后是否被将军
+    pub fn attackers_to(&self, square: Position, color: Color) -> u128{
+        let index = square.index() as usize;
+        let occupied = self.bitboard.occupied();
+        let pieces = |piece_type| self.bitboard.pieces_of(piece_type, color);
+
+        // 车和炮的走法是对称的，从目标位置反查即可；其他棋子使用反查表
+        let chariot_lines = attacks::chariot_attacks(index, &self.bitboard);
+        // 炮吃子时需要炮架，走到空位时与车相同
+        let cannon_lines = if occupied & (1u128 << index) != 0{
+            attacks::cannon_attacks(index, &self.bitboard)
+        }else{
+            chariot_lines
         };
+        let attackers = chariot_lines & pieces(PieceType::Chariot)
+            | cannon_lines & pieces(PieceType::Cannon)
+            | attacks::horse_attackers(index, occupied) & pieces(PieceType::Horse)
+            | attacks::elephant_attackers(color, index, occupied) & pieces(PieceType::Elephant)
+            | attacks::advisor_attackers(color, index) & pieces(PieceType::Advisor)
+            | attacks::king_attackers(color, index) & pieces(PieceType::King)
+            | attacks::pawn_attackers(color, index) & pieces(PieceType::Pawn);
+
+        // 不能吃己方棋子
+        if self.bitboard.color_mask(color) & (1u128 << index) != 0{
+            0
+        }else{
+            attackers
+        }
+    }
 
-        let mut is_check = false;
 
-        if let Some(king_pos) = king_pos {
-            // 检查所有敌方棋子是否可以攻击到王
-            let opponent_color = match color{
-                Color::Red => Color::Black,
-                Color::Black => Color::Red,
-                _ => return false,
-            };
+    // 将军 color 一方的对方棋子，以位棋盘表示
+    pub fn checkers(&self, color: Color) -> u128{
+        self.king_position(color).map_or(0, |king_pos| self.attackers_to(king_pos, color.opponent()))
+    }
 
-            for x in 0..9{
-                for y in 0..10{
-                    let from = Position::new(x, y);
-                    if self.bitboard.has_color(from, opponent_color){
-                        let piece = self.get_piece(from);
-                        if piece.piece_type != PieceType::None{
-                            // 检查对方棋子是否可以移动到王的位置
-                            if self.is_move_valid(from, king_pos, opponent_color){
-                                is_check = true;
-                                break;
-                            }
-                        }
-                    }
+    // 被将军时的所有应将走法，没有被将军时为空
+    // 只考虑动将、吃掉将军的棋子、在车炮与将之间垫子、塞马腿和移走炮架，再检查合法性
+    pub fn generate_evasions(&mut self, color: Color) -> MoveList{
+        let mut moves = MoveList::new();
+        let checkers = self.checkers(color);
+        let Some(king_pos) = self.king_position(color).filter(|_| checkers != 0) else{
+            return moves;
+        };
+
+        let king = king_pos.index() as usize;
+        let occupied = self.bitboard.occupied();
+        // 走到这些位置可能化解将军
+        let mut targets = checkers;
+        // 移走这些炮架可能化解将军
+        let mut screens = 0;
+        for checker in attacks::squares(checkers){
+            let path = attacks::between(checker, king);
+            match self.bitboard.piece_at(Bitboard::index_to_pos(checker)).map(|piece| piece.piece_type){
+                Some(PieceType::Chariot) => targets |= path,
+                Some(PieceType::Cannon) => {
+                    targets |= path & !occupied;
+                    screens |= path & occupied;
                 }
-            
-                if is_check{
-                    break;
+                Some(PieceType::Horse) => {
+                    // 马腿在马向将的方向上相邻处
+                    let (hx, hy) = (checker % 9, checker / 9);
+                    let (kx, ky) = (king % 9, king / 9);
+                    let leg = if hx.abs_diff(kx) == 2{
+                        (hx + kx) / 2 + hy * 9
+                    }else{
+                        hx + (hy + ky) / 2 * 9
+                    };
+                    targets |= 1u128 << leg;
                 }
+                _ => {}
             }
-            
         }
 
-        is_check
+        for from in attacks::squares(self.bitboard.color_mask(color)){
+            let mask = if from == king || screens & (1u128 << from) != 0{ !0 }else{ targets };
+            self.pseudo_moves(Bitboard::index_to_pos(from), mask, &mut moves);
+        }
+        moves.retain(|&mv| self.exposure_after(mv, color).is_none());
+        moves
     }
 
+    // 所有吃子的合法走法，按 MVV-LVA 排序：先吃价值高的棋子，吃同样的棋子时先用价值低的棋子
+    pub fn generate_captures(&mut self, color: Color) -> MoveList{
+        let mut moves = MoveList::new();
+        let enemies = self.bitboard.color_mask(color.opponent());
+
+        for from in attacks::squares(self.bitboard.color_mask(color)){
+            self.pseudo_moves(Bitboard::index_to_pos(from), enemies, &mut moves);
+        }
+        moves.retain(|&mv| self.exposure_after(mv, color).is_none());
+
+        moves.sort_by_key(|mv|{
+            let victim = self.get_piece(mv.to()).piece_type.value();
+            let attacker = self.get_piece(mv.from()).piece_type.value();
+            (-victim, attacker)
+        });
+        moves
+    }
 
     // 检查移动后是否仍然被将军
-    pub fn is_move_safe(&mut self, from: Position, to: Position, color: Color) -> bool{
-        if !self.is_move_valid(from, to, color){
-            return false;
+    pub fn is_move_safe(&mut self, mv: Move, color: Color) -> bool{
+        self.is_move_valid(mv.from(), mv.to(), color) && self.exposure_after(mv, color).is_none()
+    }
+
+    // 完整检查一步棋：符合走法，且走子后不照面、不被将军
+    pub fn check_move(&mut self, mv: Move, color: Color) -> Result<(), MoveError>{
+        self.validate_move(mv.from(), mv.to(), color)?;
+        match self.exposure_after(mv, color){
+            Some(error) => Err(error),
+            None => Ok(()),
         }
+    }
 
-        // 保存原始状态
-        let original_from = self.get_piece(from);
-        let original_to = self.get_piece(to);
-        let original_hash = self.current_hash;
-        let original_red_king = self.red_king_pos;
-        let original_black_king = self.black_king_pos;
+    // 将帅是否在同一纵线上且中间没有棋子
+    pub fn kings_facing(&self) -> bool{
+        match (self.red_king_pos, self.black_king_pos){
+            (Some(red), Some(black)) => red.x() == black.x() && self.is_path_clear(red, black),
+            _ => false,
+        }
+    }
 
-        // 执行移动
-        self.make_move(from, to);
+    // 试走一步，检查走子后是否将帅照面或己方被将军
+    fn exposure_after(&mut self, mv: Move, color: Color) -> Option<MoveError>{
+        let undo = self.apply_move(mv);
 
-        let is_safe = !self.is_in_check(color);
+        let exposure = if self.kings_facing(){
+            Some(MoveError::KingsFacing)
+        }else if self.is_in_check(color){
+            Some(MoveError::LeavesKingInCheck)
+        }else{
+            None
+        };
 
-        // 恢复原始状态
-        self.set_piece_sync(from, original_from);
-        self.set_piece_sync(to, original_to);
-        self.current_hash = original_hash;
-        self.red_king_pos = original_red_king;
-        self.black_king_pos = original_black_king;
+        self.restore_move(undo);
+        exposure
+    }
+
+    // 符合棋子走法的目标位置，尚未检查走子后是否照面或被将军
+    fn pseudo_targets(&self, from: Position) -> u128{
+        let piece = self.get_piece(from);
+        let index = from.index() as usize;
+        let occupied = self.bitboard.occupied();
+        let own = self.bitboard.color_mask(piece.color);
+
+        let targets = match piece.piece_type{
+            PieceType::King => attacks::king_attacks(piece.color, index),
+            PieceType::Advisor => attacks::advisor_attacks(piece.color, index),
+            PieceType::Elephant => attacks::elephant_attacks(piece.color, index, occupied),
+            PieceType::Horse => attacks::horse_attacks(index, occupied),
+            PieceType::Chariot => attacks::chariot_attacks(index, &self.bitboard),
+            PieceType::Cannon => {
+                // 不吃子时与车相同，吃子时需要炮架
+                attacks::chariot_attacks(index, &self.bitboard) & !occupied
+                    | attacks::cannon_attacks(index, &self.bitboard) & self.bitboard.color_mask(piece.color.opponent())
+            }
+            PieceType::Pawn => attacks::pawn_attacks(piece.color, index),
+            PieceType::None => 0,
+        };
+        targets & !own
+    }
+
+    // 把符合棋子走法的走法加入列表，只保留 mask 中的目标
+    fn pseudo_moves(&self, from: Position, mask: u128, moves: &mut MoveList){
+        for to in attacks::squares(self.pseudo_targets(from) & mask){
+            moves.extend(Move::from_indices(from.index(), to as u8));
+        }
+    }
+
+    // 获取指定位置棋子的所有合法走法，直接在本棋盘上试走并撤销
+    pub fn get_legal_moves(&mut self, from: Position) -> MoveList{
+        let piece = self.get_piece(from);
+        let mut moves = MoveList::new();
+        if piece.piece_type == PieceType::None{
+            return moves;
+        }
+
+        self.pseudo_moves(from, !0, &mut moves);
+        moves.retain(|&mv| self.exposure_after(mv, piece.color).is_none());
+        moves
+    }
+
+    // 生成一方所有合法走法，直接在本棋盘上试走并撤销，不复制棋盘
+    pub fn legal_moves(&mut self, color: Color) -> MoveList{
+        let mut moves = MoveList::new();
 
-        // 恢复位棋盘
-        self.bitboard = Bitboard::new();
-        self.bitboard.init_special_positions();
         for x in 0..9{
             for y in 0..10{
-                let pos = Position::new(x, y);
-                let piece = self.get_piece(pos);
-                if piece.piece_type != PieceType::None{
-                    self.bitboard.set_piece(pos, piece);
+                let from = Position::new(x, y);
+                if self.get_piece(from).color == color{
+                    self.pseudo_moves(from, !0, &mut moves);
                 }
             }
         }
-
-        self.cache.clear();
-        self.cache.cache_hash(original_hash);
-
-        is_safe
+        moves.retain(|&mv| self.exposure_after(mv, color).is_none());
+        moves
     }
 
     // 检查是否有合法移动
-    pub fn has_legal_moves(&self, color: Color) -> bool{
-        let mut temp_board = self.clone();
-
-        for x1 in 0..9{
-            for y1 in 0..10{
-                let from = Position::new(x1, y1);
-                let from_piece = self.get_piece(from);
-
-                if from_piece.color == color{
-                    for x2 in 0..9{
-                        for y2 in 0..10{
-                            let to = Position::new(x2, y2);
-                            if self.is_move_valid(from, to, color){
-                                if temp_board.is_move_safe(from, to, color){
-                                    return true;
-                                }
-                            }
-                        }
-                    }
+    pub fn has_legal_moves(&mut self, color: Color) -> bool{
+        let mut moves = MoveList::new();
+
+        for x in 0..9{
+            for y in 0..10{
+                let from = Position::new(x, y);
+                if self.get_piece(from).color != color{
+                    continue;
+                }
+
+                moves.clear();
+                self.pseudo_moves(from, !0, &mut moves);
+                if moves.iter().any(|&mv| self.exposure_after(mv, color).is_none()){
+                    return true;
                 }
             }
         }
@@ -523,18 +858,18 @@ impl Board{
 
 
     // 检查是否将死
-    pub fn is_checkmate(&self, color: Color) -> bool {
+    pub fn is_checkmate(&mut self, color: Color) -> bool {
         // 首先检查是否被将军
         if !self.is_in_check(color) {
             return false;
         }
         
-        // 然后检查是否没有合法移动
-        !self.has_legal_moves(color)
+        // 然后检查是否没有应将的走法
+        self.generate_evasions(color).is_empty()
     }
     
     // 检查是否困毙
-    pub fn is_stalemate(&self, color: Color) -> bool {
+    pub fn is_stalemate(&mut self, color: Color) -> bool {
         // 首先检查是否没有被将军
         if self.is_in_check(color) {
             return false;
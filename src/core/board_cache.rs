@@ -18,6 +18,12 @@ pub struct BoardCache{
     current_hash: Option<u64>,
 }
 
+impl Default for BoardCache{
+    fn default() -> Self{
+        Self::new()
+    }
+}
+
 impl BoardCache{
     // 创建新的缓存
     pub fn new() -> Self{
@@ -38,6 +44,16 @@ impl BoardCache{
         self.legal_moves.get(&(from, color))
     }
 
+    // 所有缓存的合法移动
+    pub fn cached_legal_moves(&self) -> impl Iterator<Item = (&(Position, Color), &Vec<Position>)>{
+        self.legal_moves.iter()
+    }
+
+    // 所有缓存的将军状态
+    pub fn cached_check_statuses(&self) -> impl Iterator<Item = (Color, bool)> + '_{
+        self.check_status.iter().map(|(color, status)| (*color, *status))
+    }
+
     // 缓存将军状态
     pub fn cache_check_status(&mut self, color: Color, status: bool){
         self.check_status.insert(color, status);
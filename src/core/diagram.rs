@@ -0,0 +1,318 @@
+use super::board::Board;
+use super::position::Position;
+use super::piece::{Color, Piece};
+use super::validate::InconsistencyReport;
+
+// 棋盘文本样式
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RenderStyle{
+    // 带格线，红方大写字母、黑方小写字母
+    Ascii,
+    // 带格线，繁体中文字形，红黑双方字形不同
+    Unicode,
+    // 无格线，每行9个字母，空位为 '.'
+    Compact,
+}
+
+// 棋盘图解析错误
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DiagramError{
+    // 缺少某一行
+    MissingRank(u8),
+    // 某一行出现了多次
+    DuplicateRank(u8),
+    // 某一行的格子数不是9
+    BadRow{ rank: u8, cells: usize },
+    // 无法识别的棋子
+    UnknownPiece{ rank: u8, symbol: String },
+    // 棋子数量或位置不符合规则
+    Inconsistent(InconsistencyReport),
+}
+
+impl std::fmt::Display for DiagramError{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self{
+            DiagramError::MissingRank(rank) => write!(f, "missing rank {}", rank),
+            DiagramError::DuplicateRank(rank) => write!(f, "rank {} appears more than once", rank),
+            DiagramError::BadRow{ rank, cells } => write!(f, "rank {} has {} cells, expected 9", rank, cells),
+            DiagramError::UnknownPiece{ rank, symbol } => write!(f, "unknown piece '{}' on rank {}", symbol, rank),
+            DiagramError::Inconsistent(report) => write!(f, "invalid position: {}", report),
+        }
+    }
+}
+
+impl std::error::Error for DiagramError {}
+
+// FEN 解析错误
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum FenError{
+    // 行数不是10
+    RankCount(usize),
+    // 某一行的格子数不是9，rank 为棋盘上的行号
+    BadRank{ rank: u8, cells: usize },
+    // 无法识别的棋子
+    UnknownPiece(char),
+    // 无法识别的走子方
+    BadSideToMove(String),
+    // 棋子数量或位置不符合规则
+    Inconsistent(InconsistencyReport),
+}
+
+impl std::fmt::Display for FenError{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self{
+            FenError::RankCount(count) => write!(f, "expected 10 ranks, found {}", count),
+            FenError::BadRank{ rank, cells } => write!(f, "rank {} has {} cells, expected 9", rank, cells),
+            FenError::UnknownPiece(c) => write!(f, "unknown piece '{}'", c),
+            FenError::BadSideToMove(side) => write!(f, "unknown side to move '{}'", side),
+            FenError::Inconsistent(report) => write!(f, "invalid position: {}", report),
+        }
+    }
+}
+
+impl std::error::Error for FenError {}
+
+// 按视角排列的列，perspective 一方在下方
+fn files(perspective: Color) -> Vec<u8>{
+    match perspective{
+        Color::Black => (0..9).rev().collect(),
+        _ => (0..9).collect(),
+    }
+}
+
+// 按视角排列的行，从上到下
+fn ranks(perspective: Color) -> Vec<u8>{
+    match perspective{
+        Color::Black => (0..10).collect(),
+        _ => (0..10).rev().collect(),
+    }
+}
+
+// 解析一个格子，rank 为棋盘上的行号
+// traditional 表示棋盘图使用繁体字形（出现了帥或將），此时馬、車为黑方，炮为红方；
+// 否则红黑同形的马、车、炮按所在的半边棋盘确定颜色
+fn parse_cell(symbol: &str, rank: u8, traditional: bool) -> Result<Piece, DiagramError>{
+    let mut chars = symbol.chars();
+    let (c, mut piece) = match (chars.next(), chars.next()){
+        (None, _) | (Some('.'), None) | (Some('+'), None) | (Some('·'), None) => return Ok(Piece::default()),
+        (Some(c), None) => match Piece::from_fen_char(c).or_else(|| Piece::from_chinese_name(c)){
+            Some(piece) => (c, piece),
+            None => return Err(DiagramError::UnknownPiece{ rank, symbol: symbol.to_string() }),
+        },
+        _ => return Err(DiagramError::UnknownPiece{ rank, symbol: symbol.to_string() }),
+    };
+
+    if piece.color == Color::None{
+        piece.color = match c{
+            '馬' | '車' if traditional => Color::Black,
+            '炮' if traditional => Color::Red,
+            _ if rank <= 4 => Color::Red,
+            _ => Color::Black,
+        };
+    }
+    Ok(piece)
+}
+
+impl Board{
+    // 把棋盘绘制成文本，perspective 一方在下方
+    pub fn render(&self, style: RenderStyle, perspective: Color) -> String{
+        let files = files(perspective);
+        let mut out = String::new();
+
+        if style == RenderStyle::Compact{
+            out.push_str("  ");
+            for x in &files{
+                out.push_str(&x.to_string());
+            }
+            out.push('\n');
+
+            for y in ranks(perspective){
+                out.push_str(&format!("{} ", y));
+                for &x in &files{
+                    out.push(self.get_piece(Position::new(x, y)).to_fen_char());
+                }
+                out.push('\n');
+            }
+            return out;
+        }
+
+        let separator = format!("  +{}\n", "--+".repeat(9));
+        let header: String = files.iter().map(|x| format!(" {} ", x)).collect();
+        out.push_str(&format!("  {}\n", header.trim_end()));
+        out.push_str(&separator);
+
+        for y in ranks(perspective){
+            out.push_str(&format!("{} |", y));
+            for &x in &files{
+                let piece = self.get_piece(Position::new(x, y));
+                if piece.is_empty(){
+                    out.push_str("  ");
+                }else if style == RenderStyle::Unicode{
+                    out.push(piece.traditional_name());
+                }else{
+                    out.push(piece.to_fen_char());
+                    out.push(' ');
+                }
+                out.push('|');
+            }
+            out.push('\n');
+            out.push_str(&separator);
+        }
+
+        out
+    }
+
+    // 从棋盘图解析棋盘，支持 render 输出的所有样式和视角
+    // 列的顺序由表头决定（0 在左为红方视角，8 在左为黑方视角），没有表头时按红方视角处理
+    pub fn from_diagram(diagram: &str) -> Result<Board, DiagramError>{
+        let traditional = diagram.contains(['帥', '將']);
+        let mut files: Vec<u8> = (0..9).collect();
+        let mut rows: Vec<Option<Vec<Piece>>> = vec![None; 10];
+
+        for line in diagram.lines(){
+            let trimmed = line.trim();
+            if trimmed.is_empty() || trimmed.starts_with('+'){
+                continue;
+            }
+
+            // 表头：只包含数字和空白
+            let digits: Vec<u8> = trimmed.chars()
+                .filter(|c| !c.is_whitespace())
+                .map(|c| c.to_digit(10).map(|d| d as u8))
+                .collect::<Option<Vec<u8>>>()
+                .unwrap_or_default();
+            if digits.len() == 9{
+                files = digits;
+                continue;
+            }
+
+            // 棋盘行：以行号开头
+            let mut chars = trimmed.chars();
+            let rank = match chars.next().and_then(|c| c.to_digit(10)){
+                Some(rank) => rank as u8,
+                None => continue,
+            };
+            let rest = chars.as_str();
+
+            let cells: Vec<String> = if rest.contains('|'){
+                let parts: Vec<&str> = rest.split('|').collect();
+                // 去掉第一个'|'之前和最后一个'|'之后的内容
+                parts[1..parts.len().saturating_sub(1).max(1)]
+                    .iter()
+                    .map(|cell| cell.trim().to_string())
+                    .collect()
+            }else{
+                rest.chars()
+                    .filter(|c| !c.is_whitespace())
+                    .map(|c| c.to_string())
+                    .collect()
+            };
+
+            if cells.len() != 9{
+                return Err(DiagramError::BadRow{ rank, cells: cells.len() });
+            }
+            if rows[rank as usize].is_some(){
+                return Err(DiagramError::DuplicateRank(rank));
+            }
+
+            let pieces = cells.iter()
+                .map(|cell| parse_cell(cell, rank, traditional))
+                .collect::<Result<Vec<Piece>, DiagramError>>()?;
+            rows[rank as usize] = Some(pieces);
+        }
+
+        let mut pieces = Vec::new();
+        for (rank, row) in rows.iter().enumerate(){
+            let row = row.as_ref().ok_or(DiagramError::MissingRank(rank as u8))?;
+            for (column, piece) in row.iter().enumerate(){
+                if !piece.is_empty(){
+                    pieces.push((Position::new(files[column], rank as u8), *piece));
+                }
+            }
+        }
+
+        Board::from_pieces(pieces).map_err(DiagramError::Inconsistent)
+    }
+}
+
+impl Board{
+    // FEN 的棋子部分，从第9行到第0行，红方大写
+    pub fn fen_placement(&self) -> String{
+        let mut ranks = Vec::with_capacity(10);
+        for y in (0..10).rev(){
+            let mut rank = String::new();
+            let mut empty = 0;
+            for x in 0..9{
+                let piece = self.get_piece(Position::new(x, y));
+                if piece.is_empty(){
+                    empty += 1;
+                    continue;
+                }
+                if empty > 0{
+                    rank.push_str(&empty.to_string());
+                    empty = 0;
+                }
+                rank.push(piece.to_fen_char());
+            }
+            if empty > 0{
+                rank.push_str(&empty.to_string());
+            }
+            ranks.push(rank);
+        }
+        ranks.join("/")
+    }
+
+    // 完整的 FEN，红方走子为 w，黑方为 b
+    pub fn to_fen(&self, side_to_move: Color) -> String{
+        format!("{} {} - - 0 1", self.fen_placement(), fen_side(side_to_move))
+    }
+
+    // 解析 FEN，返回棋盘和走子方，省略走子方时为红方
+    pub fn from_fen(fen: &str) -> Result<(Board, Color), FenError>{
+        let mut fields = fen.split_whitespace();
+        let placement = fields.next().unwrap_or("");
+        let ranks: Vec<&str> = placement.split('/').collect();
+        if ranks.len() != 10{
+            return Err(FenError::RankCount(ranks.len()));
+        }
+
+        let mut pieces = Vec::new();
+        for (index, text) in ranks.iter().enumerate(){
+            let y = 9 - index as u8;
+            let mut x = 0usize;
+            for c in text.chars(){
+                if let Some(n) = c.to_digit(10){
+                    x += n as usize;
+                    continue;
+                }
+                let piece = Piece::from_fen_char(c).ok_or(FenError::UnknownPiece(c))?;
+                if x < 9{
+                    pieces.push((Position::new(x as u8, y), piece));
+                }
+                x += 1;
+            }
+            if x != 9{
+                return Err(FenError::BadRank{ rank: y, cells: x });
+            }
+        }
+
+        let side = match fields.next(){
+            None | Some("w") | Some("r") => Color::Red,
+            Some("b") => Color::Black,
+            Some(other) => return Err(FenError::BadSideToMove(other.to_string())),
+        };
+
+        let board = Board::from_pieces(pieces).map_err(FenError::Inconsistent)?;
+        Ok((board, side))
+    }
+}
+
+pub(crate) fn fen_side(color: Color) -> char{
+    if color == Color::Black { 'b' } else { 'w' }
+}
+
+impl std::fmt::Display for Board{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.render(RenderStyle::Unicode, Color::Red))
+    }
+}
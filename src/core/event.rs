@@ -0,0 +1,178 @@
+use std::sync::mpsc::{self, Receiver, Sender};
+use super::board::Board;
+use super::position::Position;
+use super::piece::{Color, Piece};
+use super::ruler::{GameStatus, Player};
+
+// 对局事件
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub enum GameEvent{
+    // 走子
+    Moved{
+        color: Color,
+        from: Position,
+        to: Position,
+        piece: Piece,
+        captured: Piece,
+    },
+
+    // 将军，color 为被将军的一方
+    Check{
+        color: Color,
+    },
+
+    // 悔棋，记录被撤销的那一步
+    Undo{
+        from: Position,
+        to: Position,
+        piece: Piece,
+        captured: Piece,
+    },
+
+    // 提和
+    DrawOffered{
+        color: Color,
+    },
+
+    // 对局结束
+    GameOver{
+        status: GameStatus,
+    },
+}
+
+// 带序号的事件记录
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct EventRecord{
+    // 序号从1开始，在同一局中严格递增
+    pub seq: u64,
+    pub game_id: u32,
+    pub event: GameEvent,
+}
+
+// 对局快照，供中途加入的观众追赶进度
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct GameSnapshot{
+    pub game_id: u32,
+    pub board: Board,
+    pub players: Vec<Player>,
+    pub status: GameStatus,
+    pub current_turn: Color,
+    pub move_count: usize,
+    // 快照包含的最后一个事件序号
+    pub last_seq: u64,
+}
+
+// 订阅时的追赶方式
+#[derive(Debug)]
+pub enum CatchUp{
+    // 新观众：从快照开始，之后的事件从接收端到达
+    Snapshot(Box<GameSnapshot>),
+    // 断线续传：只补发错过的事件（序号大于请求的序号）
+    Replay(Vec<EventRecord>),
+}
+
+// 观众订阅
+#[derive(Debug)]
+pub struct Subscription{
+    // 订阅ID，在同一局中唯一，用于取消订阅
+    pub id: u64,
+    pub catch_up: CatchUp,
+    // 之后的事件按序号顺序到达
+    pub receiver: Receiver<EventRecord>,
+}
+
+// 观众
+#[derive(Debug)]
+pub struct Spectator{
+    pub id: u64,
+    pub name: String,
+    sender: Sender<EventRecord>,
+}
+
+// 事件日志：按序保存对局事件并广播给观众
+#[derive(Debug, Default, serde::Serialize, serde::Deserialize)]
+pub struct EventLog{
+    records: Vec<EventRecord>,
+
+    // 观众的通道无法序列化
+    #[serde(skip)]
+    spectators: Vec<Spectator>,
+    #[serde(skip)]
+    next_spectator_id: u64,
+}
+
+// 复制的对局不向原对局的观众推送事件，因此不复制观众
+impl Clone for EventLog{
+    fn clone(&self) -> Self{
+        EventLog{
+            records: self.records.clone(),
+            spectators: Vec::new(),
+            next_spectator_id: self.next_spectator_id,
+        }
+    }
+}
+
+impl EventLog{
+    pub fn new() -> Self{
+        EventLog{
+            records: Vec::new(),
+            spectators: Vec::new(),
+            next_spectator_id: 0,
+        }
+    }
+
+    // 记录事件并推送给所有观众，返回事件序号
+    pub fn emit(&mut self, game_id: u32, event: GameEvent) -> u64{
+        let record = EventRecord{
+            seq: self.last_seq() + 1,
+            game_id,
+            event,
+        };
+
+        // 接收端已经关闭的观众直接移除
+        self.spectators.retain(|spectator| spectator.sender.send(record.clone()).is_ok());
+
+        let seq = record.seq;
+        self.records.push(record);
+        seq
+    }
+
+    // 最后一个事件的序号，没有事件时为0
+    pub fn last_seq(&self) -> u64{
+        self.records.last().map_or(0, |record| record.seq)
+    }
+
+    // 获取序号大于 seq 的所有事件
+    pub fn since(&self, seq: u64) -> Vec<EventRecord>{
+        // 记录按序号递增保存
+        let start = self.records.partition_point(|record| record.seq <= seq);
+        self.records[start..].to_vec()
+    }
+
+    // 添加观众，返回订阅ID和事件接收端
+    pub fn subscribe(&mut self, name: String) -> (u64, Receiver<EventRecord>){
+        // 订阅ID从1开始递增，取消订阅后不会复用
+        self.next_spectator_id += 1;
+        let id = self.next_spectator_id;
+
+        let (sender, receiver) = mpsc::channel();
+        self.spectators.push(Spectator{id, name, sender});
+        (id, receiver)
+    }
+
+    // 按订阅ID移除观众
+    pub fn unsubscribe(&mut self, id: u64) -> bool{
+        match self.spectators.iter().position(|spectator| spectator.id == id) {
+            Some(index) => {
+                self.spectators.remove(index);
+                true
+            }
+            None => false,
+        }
+    }
+
+    // 获取所有观众
+    pub fn spectators(&self) -> &[Spectator]{
+        &self.spectators
+    }
+}
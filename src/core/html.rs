@@ -0,0 +1,269 @@
+use std::fmt::Write;
+
+use super::board::Board;
+use super::notation;
+use super::piece::Color;
+use super::position::Position;
+use super::record::GameRecord;
+use super::ruler::{Game, GameStatus};
+use super::svg::{self, SvgOptions};
+
+// 查看器的样式
+const STYLE: &str = r#"
+body { font-family: "PingFang SC", "Microsoft YaHei", sans-serif; background: #f4efe6; color: #222; margin: 0; padding: 16px; }
+h1 { font-size: 20px; margin: 0 0 4px; }
+.meta { color: #666; margin-bottom: 12px; }
+.viewer { display: flex; flex-wrap: wrap; gap: 16px; align-items: flex-start; }
+.controls { margin: 8px 0; display: flex; gap: 4px; align-items: center; }
+.controls button { min-width: 40px; padding: 4px 8px; }
+.controls input { width: 56px; }
+.comment { min-height: 3em; white-space: pre-wrap; background: #fff; border: 1px solid #ddd; padding: 8px; max-width: 500px; }
+.moves { border-collapse: collapse; background: #fff; }
+.moves td { padding: 2px 8px; border-bottom: 1px solid #eee; }
+.moves td.number { color: #999; text-align: right; }
+.move { cursor: pointer; }
+.move .wxf { color: #888; font-size: 12px; margin-left: 6px; }
+.move.current { background: #ffd36b; }
+.move.commented::after { content: "*"; color: #c0141c; }
+.result { font-weight: bold; margin-top: 8px; }
+"#;
+
+// 查看器的脚本，数据来自 id 为 game-data 的 JSON
+const SCRIPT: &str = r##"
+(function () {
+  var data = JSON.parse(document.getElementById("game-data").textContent);
+  var ns = "http://www.w3.org/2000/svg";
+  var pieces = document.getElementById("pieces");
+  var arrow = document.getElementById("arrow");
+  var plyInput = document.getElementById("ply");
+  var comment = document.getElementById("comment");
+  var ply = 0;
+
+  function point(x, y) {
+    var column = data.black ? 8 - x : x;
+    var row = data.black ? y : 9 - y;
+    return [data.margin + column * data.cell, data.margin + row * data.cell];
+  }
+
+  function show(n) {
+    ply = Math.max(0, Math.min(data.frames.length - 1, n));
+    var frame = data.frames[ply];
+
+    while (pieces.firstChild) pieces.removeChild(pieces.firstChild);
+    for (var i = 0; i < 90; i++) {
+      var c = frame.board.charAt(i);
+      if (c === ".") continue;
+      var p = point(i % 9, Math.floor(i / 9));
+      var use = document.createElementNS(ns, "use");
+      use.setAttribute("href", "#piece-" + c);
+      use.setAttribute("transform", "translate(" + p[0] + "," + p[1] + ")");
+      pieces.appendChild(use);
+    }
+
+    while (arrow.firstChild) arrow.removeChild(arrow.firstChild);
+    if (frame.last) {
+      var a = point(frame.last[0], frame.last[1]);
+      var b = point(frame.last[2], frame.last[3]);
+      var dx = b[0] - a[0], dy = b[1] - a[1];
+      var shrink = data.cell * 0.3 / Math.sqrt(dx * dx + dy * dy);
+      var line = document.createElementNS(ns, "line");
+      line.setAttribute("x1", a[0] + dx * shrink);
+      line.setAttribute("y1", a[1] + dy * shrink);
+      line.setAttribute("x2", b[0] - dx * shrink);
+      line.setAttribute("y2", b[1] - dy * shrink);
+      line.setAttribute("marker-end", "url(#arrowhead)");
+      arrow.appendChild(line);
+    }
+
+    comment.textContent = frame.comment;
+    plyInput.value = ply;
+    var moves = document.querySelectorAll(".move");
+    for (var j = 0; j < moves.length; j++) {
+      moves[j].classList.toggle("current", Number(moves[j].dataset.ply) === ply);
+    }
+  }
+
+  document.getElementById("first").onclick = function () { show(0); };
+  document.getElementById("prev").onclick = function () { show(ply - 1); };
+  document.getElementById("next").onclick = function () { show(ply + 1); };
+  document.getElementById("last").onclick = function () { show(data.frames.length - 1); };
+  plyInput.onchange = function () { show(Number(plyInput.value) || 0); };
+  var moves = document.querySelectorAll(".move");
+  for (var k = 0; k < moves.length; k++) {
+    moves[k].onclick = function () { show(Number(this.dataset.ply)); };
+  }
+  document.addEventListener("keydown", function (e) {
+    if (e.target === plyInput) return;
+    if (e.key === "ArrowLeft") show(ply - 1);
+    else if (e.key === "ArrowRight") show(ply + 1);
+    else if (e.key === "Home") show(0);
+    else if (e.key === "End") show(data.frames.length - 1);
+  });
+
+  show(0);
+})();
+"##;
+
+// 查看器中的一帧
+#[derive(serde::Serialize)]
+struct Frame{
+    // 90 个 FEN 字母，下标为 y * 9 + x
+    board: String,
+    // 上一步 [from_x, from_y, to_x, to_y]
+    last: Option<[u8; 4]>,
+    comment: String,
+}
+
+#[derive(serde::Serialize)]
+struct ViewerData{
+    cell: f32,
+    margin: f32,
+    black: bool,
+    frames: Vec<Frame>,
+}
+
+fn escape_html(text: &str) -> String{
+    let mut out = String::with_capacity(text.len());
+    for c in text.chars(){
+        match c{
+            '&' => out.push_str("&amp;"),
+            '<' => out.push_str("&lt;"),
+            '>' => out.push_str("&gt;"),
+            '"' => out.push_str("&quot;"),
+            '\'' => out.push_str("&#39;"),
+            _ => out.push(c),
+        }
+    }
+    out
+}
+
+fn encode_board(board: &Board) -> String{
+    let mut out = String::with_capacity(90);
+    for y in 0..10{
+        for x in 0..9{
+            out.push(board.get_piece(Position::new(x, y)).to_fen_char());
+        }
+    }
+    out
+}
+
+// plies 为总步数，用于确定困毙的一方
+// side_to_move 为终局时轮到走棋的一方
+fn result_text(result: &GameStatus, side_to_move: Color) -> &'static str{
+    match result{
+        GameStatus::RedWon => "红胜 1-0",
+        GameStatus::BlackWon => "黑胜 0-1",
+        // 困毙的一方判负
+        GameStatus::Stalemate if side_to_move == Color::Red => "红方困毙 黑胜 0-1",
+        GameStatus::Stalemate => "黑方困毙 红胜 1-0",
+        GameStatus::Draw => "和棋 1/2-1/2",
+        GameStatus::Playing => "未结束 *",
+    }
+}
+
+impl GameRecord{
+    // 导出为独立的 HTML 棋谱查看器，不依赖任何外部资源
+    // options 中的视角、坐标和格子大小对查看器有效
+    pub fn to_html(&self, options: &SvgOptions) -> String{
+        let mut viewer_options = options.clone();
+        viewer_options.last_move = None;
+        viewer_options.highlights.clear();
+        let cell = viewer_options.cell_size.max(10) as f32;
+
+        // 逐步重放，生成记谱和每一帧
+        let (mut board, mut color) = self.start_position();
+        let mut frames = vec![Frame{
+            board: encode_board(&board),
+            last: None,
+            comment: self.comments.first().cloned().unwrap_or_default(),
+        }];
+        let mut notations = Vec::with_capacity(self.moves.len());
+        for (index, &mv) in self.moves.iter().enumerate(){
+            let (from, to) = (mv.from(), mv.to());
+            notations.push((notation::to_chinese(&board, from, to), notation::to_wxf(&board, from, to)));
+            board.make_move(mv);
+            color = color.opponent();
+            frames.push(Frame{
+                board: encode_board(&board),
+                last: Some([from.x(), from.y(), to.x(), to.y()]),
+                comment: self.comments.get(index + 1).cloned().unwrap_or_default(),
+            });
+        }
+
+        let data = ViewerData{
+            cell,
+            margin: cell,
+            black: viewer_options.perspective == Color::Black,
+            frames,
+        };
+        // 防止注释中的 </script> 提前结束脚本
+        let data_json = serde_json::to_string(&data).unwrap_or_default().replace("</", "<\\/");
+
+        let red = self.tag("Red").unwrap_or("红方");
+        let black = self.tag("Black").unwrap_or("黑方");
+        let title = self.tag("Event").map(str::to_string).unwrap_or_else(|| format!("{} 对 {}", red, black));
+
+        let mut out = String::new();
+        out.push_str("<!DOCTYPE html>\n<html lang=\"zh-CN\">\n<head>\n<meta charset=\"utf-8\">\n");
+        let _ = writeln!(out, "<title>{}</title>", escape_html(&title));
+        let _ = writeln!(out, "<style>{}</style>\n</head>\n<body>", STYLE);
+        let _ = writeln!(out, "<h1>{}</h1>", escape_html(&title));
+
+        let mut meta = format!("红方 {} · 黑方 {}", escape_html(red), escape_html(black));
+        for key in ["Date", "Site", "Round"]{
+            if let Some(value) = self.tag(key){
+                let _ = write!(meta, " · {}", escape_html(value));
+            }
+        }
+        let _ = writeln!(out, "<div class=\"meta\">{}</div>", meta);
+
+        out.push_str("<div class=\"viewer\">\n<div>\n");
+        out.push_str(&svg::viewer_svg(&viewer_options));
+        out.push_str(concat!(
+            "<div class=\"controls\">",
+            "<button id=\"first\" title=\"开局\">|&lt;</button>",
+            "<button id=\"prev\" title=\"上一步\">&lt;</button>",
+            "<button id=\"next\" title=\"下一步\">&gt;</button>",
+            "<button id=\"last\" title=\"终局\">&gt;|</button>",
+            " 第 <input id=\"ply\" type=\"number\" min=\"0\"> 步",
+            "</div>\n",
+            "<div id=\"comment\" class=\"comment\"></div>\n",
+            "</div>\n",
+        ));
+
+        // 着法列表，每行一个回合
+        out.push_str("<div>\n<table class=\"moves\">\n");
+        for (round, pair) in notations.chunks(2).enumerate(){
+            let _ = write!(out, "<tr><td class=\"number\">{}.</td>", round + 1);
+            for (offset, (chinese, wxf)) in pair.iter().enumerate(){
+                let ply = round * 2 + offset + 1;
+                let commented = self.comments.get(ply).is_some_and(|comment| !comment.is_empty());
+                let _ = write!(
+                    out,
+                    "<td class=\"move{}\" data-ply=\"{}\">{}<span class=\"wxf\">{}</span></td>",
+                    if commented { " commented" } else { "" },
+                    ply,
+                    chinese,
+                    wxf
+                );
+            }
+            out.push_str("</tr>\n");
+        }
+        out.push_str("</table>\n");
+        let _ = writeln!(out, "<div class=\"result\">{}</div>", result_text(&self.result, color));
+        out.push_str("</div>\n</div>\n");
+
+        let _ = writeln!(out, "<script id=\"game-data\" type=\"application/json\">{}</script>", data_json);
+        let _ = writeln!(out, "<script>{}</script>", SCRIPT);
+        out.push_str("</body>\n</html>\n");
+
+        out
+    }
+}
+
+impl Game{
+    // 导出为独立的 HTML 棋谱查看器
+    pub fn to_html(&self, options: &SvgOptions) -> String{
+        GameRecord::from_game(self).to_html(options)
+    }
+}
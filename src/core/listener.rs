@@ -0,0 +1,78 @@
+use std::sync::Arc;
+use super::ruler::{GameStatus, MoveRecord, Player};
+
+// 监听器编号
+pub type ListenerId = u64;
+
+// 对局监听器
+// 所有方法都有空的默认实现，只需实现关心的事件
+pub trait GameListener: Send + Sync {
+    // 创建游戏
+    fn on_game_created(&self, _game_id: u32) {}
+
+    // 玩家加入
+    fn on_player_joined(&self, _game_id: u32, _player: &Player) {}
+
+    // 走子，check 表示走子后对方是否被将军
+    fn on_move_made(&self, _game_id: u32, _record: &MoveRecord, _check: bool) {}
+
+    // 悔棋，record 为被撤销的那一步
+    fn on_undo(&self, _game_id: u32, _record: &MoveRecord) {}
+
+    // 对局结束
+    fn on_game_finished(&self, _game_id: u32, _status: &GameStatus) {}
+}
+
+// 监听器注册表
+#[derive(Clone, Default)]
+pub struct Listeners{
+    entries: Vec<(ListenerId, Arc<dyn GameListener>)>,
+    next_id: ListenerId,
+}
+
+impl std::fmt::Debug for Listeners{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Listeners")
+            .field("count", &self.entries.len())
+            .field("next_id", &self.next_id)
+            .finish()
+    }
+}
+
+impl Listeners{
+    pub fn new() -> Self{
+        Listeners{
+            entries: Vec::new(),
+            next_id: 0,
+        }
+    }
+
+    // 注册监听器，返回编号（从1开始）
+    pub fn add(&mut self, listener: Arc<dyn GameListener>) -> ListenerId{
+        self.next_id += 1;
+        self.entries.push((self.next_id, listener));
+        self.next_id
+    }
+
+    // 移除监听器
+    pub fn remove(&mut self, id: ListenerId) -> bool{
+        let count = self.entries.len();
+        self.entries.retain(|(entry_id, _)| *entry_id != id);
+        self.entries.len() != count
+    }
+
+    // 按注册顺序通知所有监听器
+    pub fn notify<F: Fn(&dyn GameListener)>(&self, f: F){
+        for (_, listener) in &self.entries{
+            f(listener.as_ref());
+        }
+    }
+
+    pub fn len(&self) -> usize{
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool{
+        self.entries.is_empty()
+    }
+}
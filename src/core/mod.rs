@@ -1,7 +1,21 @@
 pub mod position;
 pub mod piece;
 pub mod bitboard;
+pub mod attacks;
 pub mod zobrist;
 pub mod board_cache;
 pub mod board;
+pub mod move_error;
+pub mod diagram;
+pub mod svg;
+pub mod record;
+pub mod html;
 pub mod ruler;
+pub mod view;
+pub mod event;
+pub mod listener;
+pub mod notation;
+pub mod moves;
+pub mod search;
+pub mod perft;
+pub mod validate;
@@ -0,0 +1,132 @@
+// 走法被拒绝的原因
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, serde::Serialize, serde::Deserialize)]
+pub enum MoveError{
+    // 对局已经结束
+    GameOver,
+    // 坐标不在棋盘上
+    OutOfBounds,
+    // 起点没有棋子
+    NoPiece,
+    // 起点是对方的棋子
+    WrongColor,
+    // 不是该方走棋
+    NotYourTurn,
+    // 终点有己方棋子
+    OwnPieceOnTarget,
+    // 不符合该棋子的走法
+    InvalidMovePattern,
+    // 蹩马腿
+    HorseLegBlocked,
+    // 塞象眼
+    ElephantEyeBlocked,
+    // 相/象不能过河
+    ElephantCrossRiver,
+    // 将/帅、仕/士不能出九宫
+    OutOfPalace,
+    // 炮吃子需要隔一个棋子
+    CannonNeedsScreen,
+    // 路径上有棋子阻挡
+    PathBlocked,
+    // 兵/卒不能后退
+    PawnBackward,
+    // 走子后己方被将军
+    LeavesKingInCheck,
+    // 走子后将帅照面
+    KingsFacing,
+}
+
+impl MoveError{
+    // 错误码，用于文本和 JSON 结果
+    pub fn code(&self) -> &'static str{
+        match self{
+            MoveError::GameOver => "GAME_OVER",
+            MoveError::OutOfBounds => "OUT_OF_BOUNDS",
+            MoveError::NoPiece => "NO_PIECE",
+            MoveError::WrongColor => "WRONG_COLOR",
+            MoveError::NotYourTurn => "NOT_YOUR_TURN",
+            MoveError::OwnPieceOnTarget => "OWN_PIECE_ON_TARGET",
+            MoveError::InvalidMovePattern => "INVALID_MOVE_PATTERN",
+            MoveError::HorseLegBlocked => "HORSE_LEG_BLOCKED",
+            MoveError::ElephantEyeBlocked => "ELEPHANT_EYE_BLOCKED",
+            MoveError::ElephantCrossRiver => "ELEPHANT_CROSS_RIVER",
+            MoveError::OutOfPalace => "OUT_OF_PALACE",
+            MoveError::CannonNeedsScreen => "CANNON_NEEDS_SCREEN",
+            MoveError::PathBlocked => "PATH_BLOCKED",
+            MoveError::PawnBackward => "PAWN_BACKWARD",
+            MoveError::LeavesKingInCheck => "LEAVES_KING_IN_CHECK",
+            MoveError::KingsFacing => "KINGS_FACING",
+        }
+    }
+
+    // 面向棋手的中文说明
+    pub fn description(&self) -> &'static str{
+        match self{
+            MoveError::GameOver => "对局已经结束",
+            MoveError::OutOfBounds => "坐标不在棋盘上",
+            MoveError::NoPiece => "起点没有棋子",
+            MoveError::WrongColor => "不能走对方的棋子",
+            MoveError::NotYourTurn => "还没有轮到这一方走棋",
+            MoveError::OwnPieceOnTarget => "不能吃自己的棋子",
+            MoveError::InvalidMovePattern => "这个棋子不能这样走",
+            MoveError::HorseLegBlocked => "蹩马腿",
+            MoveError::ElephantEyeBlocked => "塞象眼",
+            MoveError::ElephantCrossRiver => "相（象）不能过河",
+            MoveError::OutOfPalace => "帅（将）和仕（士）不能走出九宫",
+            MoveError::CannonNeedsScreen => "炮吃子必须隔一个棋子",
+            MoveError::PathBlocked => "路线上有棋子阻挡",
+            MoveError::PawnBackward => "兵（卒）不能后退",
+            MoveError::LeavesKingInCheck => "走完后己方被将军",
+            MoveError::KingsFacing => "走完后将帅照面",
+        }
+    }
+}
+
+impl std::fmt::Display for MoveError{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let message = match self{
+            MoveError::GameOver => "game is already over",
+            MoveError::OutOfBounds => "position is off the board",
+            MoveError::NoPiece => "no piece on the starting square",
+            MoveError::WrongColor => "piece belongs to the opponent",
+            MoveError::NotYourTurn => "it is not this side's turn",
+            MoveError::OwnPieceOnTarget => "target square holds one of your own pieces",
+            MoveError::InvalidMovePattern => "piece cannot move that way",
+            MoveError::HorseLegBlocked => "horse leg is blocked",
+            MoveError::ElephantEyeBlocked => "elephant eye is blocked",
+            MoveError::ElephantCrossRiver => "elephant cannot cross the river",
+            MoveError::OutOfPalace => "king and advisors must stay in the palace",
+            MoveError::CannonNeedsScreen => "cannon needs exactly one screen to capture",
+            MoveError::PathBlocked => "path is blocked",
+            MoveError::PawnBackward => "pawn cannot move backward",
+            MoveError::LeavesKingInCheck => "move leaves the king in check",
+            MoveError::KingsFacing => "kings may not face each other",
+        };
+        write!(f, "{}", message)
+    }
+}
+
+impl std::error::Error for MoveError {}
+
+// Ruler 走子失败的原因：游戏不存在，或者走法被拒绝
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MakeMoveError{
+    GameNotFound,
+    Rejected(MoveError),
+}
+
+impl From<MoveError> for MakeMoveError{
+    fn from(error: MoveError) -> Self{
+        MakeMoveError::Rejected(error)
+    }
+}
+
+impl std::fmt::Display for MakeMoveError{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self{
+            MakeMoveError::GameNotFound => write!(f, "game does not exist"),
+            MakeMoveError::Rejected(error) => write!(f, "{}", error),
+        }
+    }
+}
+
+impl std::error::Error for MakeMoveError {}
@@ -0,0 +1,235 @@
+use super::move_error::MoveError;
+use super::notation;
+use super::position::Position;
+
+// 一步走法，16 位：0-6 位为起点编号，7-13 位为终点编号，14-15 位为 0
+// 起点和终点都在棋盘上，序列化为这个 16 位整数，反序列化时检查
+#[derive(Clone, Copy, PartialEq, Eq, Hash, serde::Serialize, serde::Deserialize)]
+#[serde(try_from = "u16", into = "u16")]
+pub struct Move{
+    data: u16,
+}
+
+impl Move{
+    // 起点或终点不在棋盘上时返回 None
+    pub fn new(from: Position, to: Position) -> Option<Self>{
+        if from.is_valid() && to.is_valid(){
+            Some(Move { data: from.index() as u16 | (to.index() as u16) << 7 })
+        }else{
+            None
+        }
+    }
+
+    // 由位置编号创建，见 Position::index
+    pub fn from_indices(from: u8, to: u8) -> Option<Self>{
+        Move::new(Position::from_index(from)?, Position::from_index(to)?)
+    }
+
+    // 由坐标创建，例如 (1,2) 到 (4,2)
+    pub fn from_coordinates(from_x: u8, from_y: u8, to_x: u8, to_y: u8) -> Option<Self>{
+        Move::new(Position::try_new(from_x, from_y)?, Position::try_new(to_x, to_y)?)
+    }
+
+    // 解析 ICCS 坐标，例如 h2e2
+    pub fn from_iccs(text: &str) -> Option<Self>{
+        notation::parse_iccs(text)
+    }
+
+    // 解析坐标或 ICCS，例如 (1,2)-(4,2)、(1,2) TO (4,2) 或 b2e2
+    pub fn parse(text: &str) -> Option<Self>{
+        notation::parse_coordinates(text).or_else(|| notation::parse_iccs(text))
+    }
+
+    pub fn from_index(&self) -> u8{
+        (self.data & 0x7F) as u8
+    }
+
+    pub fn to_index(&self) -> u8{
+        ((self.data >> 7) & 0x7F) as u8
+    }
+
+    pub fn from(&self) -> Position{
+        Position::new(self.from_index() % 9, self.from_index() / 9)
+    }
+
+    pub fn to(&self) -> Position{
+        Position::new(self.to_index() % 9, self.to_index() / 9)
+    }
+
+    // 起点和终点坐标 (from_x, from_y, to_x, to_y)
+    pub fn coordinates(&self) -> (u8, u8, u8, u8){
+        let (from, to) = (self.from(), self.to());
+        (from.x(), from.y(), to.x(), to.y())
+    }
+
+    // ICCS 坐标记谱，例如 h2e2
+    pub fn to_iccs(&self) -> String{
+        notation::to_iccs(self.from(), self.to())
+    }
+}
+
+// 文本格式为 ICCS 坐标
+impl std::fmt::Display for Move{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.to_iccs())
+    }
+}
+
+impl std::fmt::Debug for Move{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Move({})", self.to_iccs())
+    }
+}
+
+impl From<Move> for u16{
+    fn from(mv: Move) -> u16{
+        mv.data
+    }
+}
+
+// 编号超出棋盘或高两位不为 0 时返回 OutOfBounds
+impl TryFrom<u16> for Move{
+    type Error = MoveError;
+
+    fn try_from(data: u16) -> Result<Self, MoveError>{
+        if data >> 14 != 0{
+            return Err(MoveError::OutOfBounds);
+        }
+        Move::from_indices((data & 0x7F) as u8, ((data >> 7) & 0x7F) as u8).ok_or(MoveError::OutOfBounds)
+    }
+}
+
+// 一方走法数的上限，包括尚未检查将军的走法
+pub const MAX_MOVES: usize = 128;
+
+// 在栈上分配的走法列表
+#[derive(Clone, Copy)]
+pub struct MoveList{
+    moves: [Move; MAX_MOVES],
+    len: usize,
+}
+
+impl Default for MoveList{
+    fn default() -> Self{
+        Self::new()
+    }
+}
+
+impl MoveList{
+    pub fn new() -> Self{
+        MoveList{
+            moves: [Move { data: 0 }; MAX_MOVES],
+            len: 0,
+        }
+    }
+
+    // 列表已满时 panic，正常局面不会发生
+    pub fn push(&mut self, mv: Move){
+        assert!(self.len < MAX_MOVES, "move list overflow");
+        self.moves[self.len] = mv;
+        self.len += 1;
+    }
+
+    pub fn clear(&mut self){
+        self.len = 0;
+    }
+
+    // 只保留满足条件的走法，保持原来的顺序
+    pub fn retain(&mut self, mut keep: impl FnMut(&Move) -> bool){
+        let mut len = 0;
+        for i in 0..self.len{
+            if keep(&self.moves[i]){
+                self.moves[len] = self.moves[i];
+                len += 1;
+            }
+        }
+        self.len = len;
+    }
+}
+
+impl std::ops::Deref for MoveList{
+    type Target = [Move];
+
+    fn deref(&self) -> &[Move]{
+        &self.moves[..self.len]
+    }
+}
+
+impl std::ops::DerefMut for MoveList{
+    fn deref_mut(&mut self) -> &mut [Move]{
+        &mut self.moves[..self.len]
+    }
+}
+
+impl std::fmt::Debug for MoveList{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_list().entries(self.iter()).finish()
+    }
+}
+
+impl PartialEq for MoveList{
+    fn eq(&self, other: &Self) -> bool{
+        **self == **other
+    }
+}
+
+impl Eq for MoveList {}
+
+impl FromIterator<Move> for MoveList{
+    fn from_iter<I: IntoIterator<Item = Move>>(iter: I) -> Self{
+        let mut list = MoveList::new();
+        for mv in iter{
+            list.push(mv);
+        }
+        list
+    }
+}
+
+impl Extend<Move> for MoveList{
+    fn extend<I: IntoIterator<Item = Move>>(&mut self, iter: I){
+        for mv in iter{
+            self.push(mv);
+        }
+    }
+}
+
+impl<'a> IntoIterator for &'a MoveList{
+    type Item = &'a Move;
+    type IntoIter = std::slice::Iter<'a, Move>;
+
+    fn into_iter(self) -> Self::IntoIter{
+        self.iter()
+    }
+}
+
+// 按值遍历
+pub struct IntoIter{
+    list: MoveList,
+    index: usize,
+}
+
+impl Iterator for IntoIter{
+    type Item = Move;
+
+    fn next(&mut self) -> Option<Move>{
+        let mv = self.list.get(self.index).copied();
+        self.index += 1;
+        mv
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>){
+        let remaining = self.list.len().saturating_sub(self.index);
+        (remaining, Some(remaining))
+    }
+}
+
+impl ExactSizeIterator for IntoIter {}
+
+impl IntoIterator for MoveList{
+    type Item = Move;
+    type IntoIter = IntoIter;
+
+    fn into_iter(self) -> IntoIter{
+        IntoIter { list: self, index: 0 }
+    }
+}
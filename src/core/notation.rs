@@ -0,0 +1,298 @@
+use super::board::Board;
+use super::moves::Move;
+use super::position::Position;
+use super::piece::{Color, Piece, PieceType};
+
+// 红方用中文数字记纵线，黑方用阿拉伯数字
+const RED_NUMBERS: [char; 9] = ['一', '二', '三', '四', '五', '六', '七', '八', '九'];
+const BLACK_NUMBERS: [char; 9] = ['1', '2', '3', '4', '5', '6', '7', '8', '9'];
+
+// ICCS 坐标记谱，例如 h2e2
+// 列 a-i 对应 x 0-8，行 0-9 对应 y
+pub fn to_iccs(from: Position, to: Position) -> String{
+    format!(
+        "{}{}{}{}",
+        (b'a' + from.x()) as char,
+        from.y(),
+        (b'a' + to.x()) as char,
+        to.y()
+    )
+}
+
+// 解析 ICCS 坐标，支持 h2e2 和 h2-e2 两种写法
+pub fn parse_iccs(text: &str) -> Option<Move>{
+    let chars: Vec<char> = text.trim()
+        .chars()
+        .filter(|&c| c != '-')
+        .map(|c| c.to_ascii_lowercase())
+        .collect();
+    if chars.len() != 4{
+        return None;
+    }
+
+    let square = |file: char, rank: char| -> Option<Position>{
+        if !('a'..='i').contains(&file) || !rank.is_ascii_digit(){
+            return None;
+        }
+        Some(Position::new(file as u8 - b'a', rank as u8 - b'0'))
+    };
+
+    Move::new(square(chars[0], chars[1])?, square(chars[2], chars[3])?)
+}
+
+// 解析坐标走法，支持 (0,0) (0,1)、(0,0) to (0,1) 和 0,0 0,1
+pub fn parse_coordinates(text: &str) -> Option<Move>{
+    let cleaned = text.to_uppercase().replace("TO", " ");
+    let numbers: Vec<u8> = cleaned
+        .split(|c: char| !c.is_ascii_digit())
+        .filter(|part| !part.is_empty())
+        .map(|part| part.parse().ok())
+        .collect::<Option<Vec<u8>>>()?;
+
+    // 必须包含逗号，避免把 ICCS 误认为坐标
+    if numbers.len() != 4 || !cleaned.contains(','){
+        return None;
+    }
+    Move::from_coordinates(numbers[0], numbers[1], numbers[2], numbers[3])
+}
+
+// 按执棋方视角的纵线编号（从右往左 1-9）
+fn file_number(x: u8, color: Color) -> usize{
+    match color{
+        Color::Red => 9 - x as usize,
+        _ => x as usize + 1,
+    }
+}
+
+fn number_char(n: usize, color: Color) -> char{
+    match color{
+        Color::Red => RED_NUMBERS[n - 1],
+        _ => BLACK_NUMBERS[n - 1],
+    }
+}
+
+// 前进方向上的距离，正数表示前进
+fn forward_distance(from: Position, to: Position, color: Color) -> i8{
+    let dy = to.y() as i8 - from.y() as i8;
+    match color{
+        Color::Red => dy,
+        _ => -dy,
+    }
+}
+
+// 同一纵线上同类棋子的前后标记，没有重复时返回 None
+fn tandem_marker(board: &Board, from: Position, piece: Piece) -> Option<char>{
+    // 按从前到后排序
+    let mut ranks: Vec<u8> = (0..10)
+        .filter(|&y| board.get_piece(Position::new(from.x(), y)) == piece)
+        .collect();
+    if ranks.len() < 2{
+        return None;
+    }
+    if piece.color == Color::Red{
+        ranks.reverse();
+    }
+
+    let index = ranks.iter().position(|&y| y == from.y())?;
+    let marker = match (ranks.len(), index){
+        (2, 0) | (3, 0) => '前',
+        (3, 1) => '中',
+        (2, 1) | (3, 2) => '后',
+        // 四五个兵在同一纵线时按从前到后编号
+        (_, i) => number_char(i + 1, piece.color),
+    };
+    Some(marker)
+}
+
+// 中文纵线记谱，例如 炮二平五、馬8进7，棋子名称与 Piece::chinese_name 一致
+// 需要走子之前的棋盘
+pub fn to_chinese(board: &Board, from: Position, to: Position) -> String{
+    let piece = board.get_piece(from);
+    let color = piece.color;
+    let mut text = String::new();
+
+    match tandem_marker(board, from, piece){
+        Some(marker) => {
+            text.push(marker);
+            text.push(piece.chinese_name());
+        }
+        None => {
+            text.push(piece.chinese_name());
+            text.push(number_char(file_number(from.x(), color), color));
+        }
+    }
+
+    let distance = forward_distance(from, to, color);
+    let diagonal = matches!(piece.piece_type, PieceType::Horse | PieceType::Elephant | PieceType::Advisor);
+
+    if distance == 0{
+        text.push('平');
+        text.push(number_char(file_number(to.x(), color), color));
+    }else{
+        text.push(if distance > 0 { '进' } else { '退' });
+        let n = if diagonal {
+            file_number(to.x(), color)
+        } else {
+            distance.unsigned_abs() as usize
+        };
+        text.push(number_char(n, color));
+    }
+
+    text
+}
+
+// WXF 记谱用的棋子字母
+fn wxf_letter(piece_type: PieceType) -> char{
+    match piece_type{
+        PieceType::King => 'K',
+        PieceType::Advisor => 'A',
+        PieceType::Elephant => 'E',
+        PieceType::Horse => 'H',
+        PieceType::Chariot => 'R',
+        PieceType::Cannon => 'C',
+        PieceType::Pawn => 'P',
+        PieceType::None => ' ',
+    }
+}
+
+// WXF 记谱，例如 C2.5、H8+7、+R-1
+// 与中文纵线记谱一一对应：纵线编号相同，进退平记为 + - .，前中后记为 + = -
+// 需要走子之前的棋盘
+pub fn to_wxf(board: &Board, from: Position, to: Position) -> String{
+    let chinese: Vec<char> = to_chinese(board, from, to).chars().collect();
+    let piece = board.get_piece(from);
+
+    let digit = |c: char| -> char{
+        match RED_NUMBERS.iter().position(|&n| n == c){
+            Some(i) => BLACK_NUMBERS[i],
+            None => c,
+        }
+    };
+
+    let mut text = String::new();
+    match chinese[0]{
+        '前' => { text.push('+'); text.push(wxf_letter(piece.piece_type)); }
+        '中' => { text.push('='); text.push(wxf_letter(piece.piece_type)); }
+        '后' => { text.push('-'); text.push(wxf_letter(piece.piece_type)); }
+        c if c.is_ascii_digit() || RED_NUMBERS.contains(&c) => {
+            // 同一纵线上有四五个兵时按序号记
+            text.push(digit(c));
+            text.push(wxf_letter(piece.piece_type));
+        }
+        _ => {
+            text.push(wxf_letter(piece.piece_type));
+            text.push(digit(chinese[1]));
+        }
+    }
+
+    text.push(match chinese[2]{
+        '进' => '+',
+        '退' => '-',
+        _ => '.',
+    });
+    text.push(digit(chinese[3]));
+
+    text
+}
+
+// 解析 WXF 记谱，与 parse_chinese 一样通过比较所有合法走法实现
+pub fn parse_wxf(board: &mut Board, color: Color, text: &str) -> Option<Move>{
+    let wanted: String = text.trim().to_uppercase();
+    if wanted.len() != 4 || !wanted.is_ascii(){
+        return None;
+    }
+
+    board.legal_moves(color)
+        .into_iter()
+        .find(|mv| to_wxf(board, mv.from(), mv.to()) == wanted)
+}
+
+// 把中文记谱的各种写法统一成便于比较的形式
+fn normalize_chinese(text: &str) -> String{
+    text.chars()
+        .filter(|c| !c.is_whitespace())
+        .map(|c| match c{
+            '帅' | '帥' | '将' | '將' => 'K',
+            '仕' | '士' => 'A',
+            '相' | '象' => 'E',
+            '马' | '馬' | '傌' => 'H',
+            '车' | '車' | '俥' => 'R',
+            '炮' | '砲' | '包' => 'C',
+            '兵' | '卒' => 'P',
+            '进' | '進' => '+',
+            '退' => '-',
+            '平' => '.',
+            '前' => 'f',
+            '中' => 'm',
+            '后' | '後' => 'b',
+            '一' | '１' => '1',
+            '二' | '２' => '2',
+            '三' | '３' => '3',
+            '四' | '４' => '4',
+            '五' | '５' => '5',
+            '六' | '６' => '6',
+            '七' | '７' => '7',
+            '八' | '８' => '8',
+            '九' | '９' => '9',
+            other => other,
+        })
+        .collect()
+}
+
+// 解析中文纵线记谱
+// 通过与所有合法走法的记谱比较来解析，天然支持前后标记
+pub fn parse_chinese(board: &mut Board, color: Color, text: &str) -> Option<Move>{
+    let wanted = normalize_chinese(text);
+    if wanted.is_empty(){
+        return None;
+    }
+
+    board.legal_moves(color)
+        .into_iter()
+        .find(|mv| normalize_chinese(&to_chinese(board, mv.from(), mv.to())) == wanted)
+}
+
+// 解析任意支持的记谱：坐标、ICCS、WXF 或中文纵线记谱
+pub fn parse_move(board: &mut Board, color: Color, text: &str) -> Option<Move>{
+    parse_coordinates(text)
+        .or_else(|| parse_iccs(text))
+        .or_else(|| parse_wxf(board, color, text))
+        .or_else(|| parse_chinese(board, color, text))
+}
+
+// 记谱方式
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, serde::Serialize)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum Notation{
+    // ICCS 坐标记谱，例如 h2e2
+    #[default]
+    Iccs,
+    // 中文纵线记谱，例如 炮二平五
+    Chinese,
+    // WXF 记谱，例如 C2.5
+    Wxf,
+    // 引擎命令使用的坐标，例如 (7,2)-(4,2)
+    Coordinate,
+}
+
+impl Notation{
+    pub fn name(&self) -> &'static str{
+        match self{
+            Notation::Iccs => "ICCS",
+            Notation::Chinese => "CHINESE",
+            Notation::Wxf => "WXF",
+            Notation::Coordinate => "COORDINATE",
+        }
+    }
+
+    // 记录一步走法，需要走子之前的棋盘
+    pub fn format(&self, board: &Board, mv: Move) -> String{
+        let (from, to) = (mv.from(), mv.to());
+        match self{
+            Notation::Iccs => to_iccs(from, to),
+            Notation::Chinese => to_chinese(board, from, to),
+            Notation::Wxf => to_wxf(board, from, to),
+            Notation::Coordinate => format!("({},{})-({},{})", from.x(), from.y(), to.x(), to.y()),
+        }
+    }
+}
@@ -0,0 +1,118 @@
+use super::board::Board;
+use super::diagram::FenError;
+use super::moves::Move;
+use super::piece::Color;
+
+// 走法生成的验证：统计指定深度的局面数，与公认的结果比较
+
+impl Board{
+    // color 一方先走，走 depth 步之后的局面数
+    pub fn perft(&mut self, color: Color, depth: u32) -> u64{
+        if depth == 0{
+            return 1;
+        }
+
+        let moves = self.legal_moves(color);
+        // 最后一层只需要走法数
+        if depth == 1{
+            return moves.len() as u64;
+        }
+
+        moves.iter()
+            .map(|&mv|{
+                let undo = self.make_move(mv);
+                let nodes = self.perft(color.opponent(), depth - 1);
+                self.unmake_move(undo);
+                nodes
+            })
+            .sum()
+    }
+
+    // 每个走法之后的 perft，与其他引擎对比时用于定位出错的走法
+    pub fn divide(&mut self, color: Color, depth: u32) -> Vec<(Move, u64)>{
+        if depth == 0{
+            return Vec::new();
+        }
+
+        self.legal_moves(color)
+            .iter()
+            .map(|&mv|{
+                let undo = self.make_move(mv);
+                let nodes = self.perft(color.opponent(), depth - 1);
+                self.unmake_move(undo);
+                (mv, nodes)
+            })
+            .collect()
+    }
+}
+
+// EPD 文件中的一个局面，例如
+// rnbakabnr/9/1c5c1/p1p1p1p1p/9/9/P1P1P1P1P/1C5C1/9/RNBAKABNR w ;D1 44 ;D2 1920
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PerftPosition{
+    // 行号，从 1 开始
+    pub line: usize,
+    pub fen: String,
+    // (深度, 局面数)
+    pub counts: Vec<(u32, u64)>,
+}
+
+// EPD 解析错误，line 为行号
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum EpdError{
+    Fen{ line: usize, error: FenError },
+    // 无法识别的 ;D<深度> <局面数>
+    BadCount{ line: usize, text: String },
+}
+
+impl std::fmt::Display for EpdError{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self{
+            EpdError::Fen{ line, error } => write!(f, "line {}: {}", line, error),
+            EpdError::BadCount{ line, text } => write!(f, "line {}: expected ;D<depth> <nodes>, found '{}'", line, text),
+        }
+    }
+}
+
+impl std::error::Error for EpdError {}
+
+impl PerftPosition{
+    // 解析 EPD 文本，忽略空行和 # 注释
+    pub fn parse_epd(text: &str) -> Result<Vec<PerftPosition>, EpdError>{
+        let mut positions = Vec::new();
+
+        for (index, row) in text.lines().enumerate(){
+            let line = index + 1;
+            let row = row.trim();
+            if row.is_empty() || row.starts_with('#'){
+                continue;
+            }
+
+            let mut fields = row.split(';');
+            let fen = fields.next().unwrap_or("").trim().to_string();
+            Board::from_fen(&fen).map_err(|error| EpdError::Fen{ line, error })?;
+
+            let mut counts = Vec::new();
+            for field in fields{
+                let field = field.trim();
+                let count = field.strip_prefix('D')
+                    .and_then(|rest| rest.split_once(char::is_whitespace))
+                    .and_then(|(depth, nodes)| Some((depth.parse().ok()?, nodes.trim().parse().ok()?)));
+                match count{
+                    Some(count) => counts.push(count),
+                    None => return Err(EpdError::BadCount{ line, text: field.to_string() }),
+                }
+            }
+
+            positions.push(PerftPosition{ line, fen, counts });
+        }
+
+        Ok(positions)
+    }
+
+    // 计算给定深度的局面数，FEN 已在解析时检查过
+    pub fn perft(&self, depth: u32) -> u64{
+        let (mut board, color) = Board::from_fen(&self.fen).expect("FEN checked by parse_epd");
+        board.perft(color, depth)
+    }
+}
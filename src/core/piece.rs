@@ -53,6 +53,22 @@ impl PieceType{
     }
 }
 
+impl PieceType{
+    // 棋子价值，用于局面评估和吃子排序
+    pub fn value(&self) -> i32{
+        match self{
+            PieceType::King => 10_000,
+            PieceType::Chariot => 900,
+            PieceType::Cannon => 450,
+            PieceType::Horse => 400,
+            PieceType::Elephant => 200,
+            PieceType::Advisor => 200,
+            PieceType::Pawn => 100,
+            PieceType::None => 0,
+        }
+    }
+}
+
 impl Color{
     pub fn from_color_to_usize(&self) -> usize{
         match self{
@@ -61,4 +77,109 @@ impl Color{
             Color::None => 0,
         }
     }
-}
\ No newline at end of file
+}
+
+impl Color{
+    // 对方颜色
+    pub fn opponent(&self) -> Color{
+        match self{
+            Color::Red => Color::Black,
+            Color::Black => Color::Red,
+            Color::None => Color::None,
+        }
+    }
+}
+
+impl Piece{
+    pub fn new(piece_type: PieceType, color: Color) -> Self{
+        Piece { piece_type, color }
+    }
+
+    pub fn is_empty(&self) -> bool{
+        self.piece_type == PieceType::None
+    }
+
+    // 棋子的中文字形（与README中的棋盘图一致）
+    pub fn chinese_name(&self) -> char{
+        match (self.piece_type, self.color){
+            (PieceType::King, Color::Red) => '帅',
+            (PieceType::King, _) => '将',
+            (PieceType::Advisor, Color::Red) => '仕',
+            (PieceType::Advisor, _) => '士',
+            (PieceType::Elephant, Color::Red) => '相',
+            (PieceType::Elephant, _) => '象',
+            (PieceType::Horse, _) => '馬',
+            (PieceType::Chariot, _) => '車',
+            (PieceType::Cannon, _) => '炮',
+            (PieceType::Pawn, Color::Red) => '兵',
+            (PieceType::Pawn, _) => '卒',
+            (PieceType::None, _) => ' ',
+        }
+    }
+
+    // 繁体字形，红黑双方的字形互不相同，只看字形就能确定颜色
+    pub fn traditional_name(&self) -> char{
+        match (self.piece_type, self.color){
+            (PieceType::King, Color::Red) => '帥',
+            (PieceType::King, _) => '將',
+            (PieceType::Horse, Color::Red) => '傌',
+            (PieceType::Chariot, Color::Red) => '俥',
+            (PieceType::Cannon, Color::Black) => '砲',
+            _ => self.chinese_name(),
+        }
+    }
+
+    // 从中文字形解析棋子，同时接受简体和繁体字形
+    // 马、车、炮在简体字形和README的棋盘图中红黑同形，此时返回的颜色为 Color::None
+    pub fn from_chinese_name(c: char) -> Option<Piece>{
+        let (piece_type, color) = match c{
+            '帅' | '帥' => (PieceType::King, Color::Red),
+            '将' | '將' => (PieceType::King, Color::Black),
+            '仕' => (PieceType::Advisor, Color::Red),
+            '士' => (PieceType::Advisor, Color::Black),
+            '相' => (PieceType::Elephant, Color::Red),
+            '象' => (PieceType::Elephant, Color::Black),
+            '傌' => (PieceType::Horse, Color::Red),
+            '馬' | '马' => (PieceType::Horse, Color::None),
+            '俥' => (PieceType::Chariot, Color::Red),
+            '車' | '车' => (PieceType::Chariot, Color::None),
+            '炮' => (PieceType::Cannon, Color::None),
+            '砲' | '包' => (PieceType::Cannon, Color::Black),
+            '兵' => (PieceType::Pawn, Color::Red),
+            '卒' => (PieceType::Pawn, Color::Black),
+            _ => return None,
+        };
+        Some(Piece::new(piece_type, color))
+    }
+
+    // FEN 字母，红方大写，黑方小写
+    pub fn to_fen_char(&self) -> char{
+        let c = match self.piece_type{
+            PieceType::King => 'k',
+            PieceType::Advisor => 'a',
+            PieceType::Elephant => 'b',
+            PieceType::Horse => 'n',
+            PieceType::Chariot => 'r',
+            PieceType::Cannon => 'c',
+            PieceType::Pawn => 'p',
+            PieceType::None => return '.',
+        };
+        if self.color == Color::Red { c.to_ascii_uppercase() } else { c }
+    }
+
+    // 从 FEN 字母解析棋子，同时接受 WXF 的 E（象）和 H（马）
+    pub fn from_fen_char(c: char) -> Option<Piece>{
+        let piece_type = match c.to_ascii_lowercase(){
+            'k' => PieceType::King,
+            'a' => PieceType::Advisor,
+            'b' | 'e' => PieceType::Elephant,
+            'n' | 'h' => PieceType::Horse,
+            'r' => PieceType::Chariot,
+            'c' => PieceType::Cannon,
+            'p' => PieceType::Pawn,
+            _ => return None,
+        };
+        let color = if c.is_ascii_uppercase() { Color::Red } else { Color::Black };
+        Some(Piece::new(piece_type, color))
+    }
+}
@@ -6,10 +6,21 @@ pub struct Position{
 }
 
 impl Position{
-    pub fn new(x: u8, y: u8) -> Self{
+    // 不检查坐标，x 超过 15 或 y 超过 15 时会与其他位置混淆，只用于已经检查过的坐标
+    // crate 外部使用 try_new
+    pub(crate) fn new(x: u8, y: u8) -> Self{
         Position { data: (x << 4) | y }
     }
 
+    // 棋盘上的位置，坐标超出 (0,0) 到 (8,9) 时返回 None
+    pub fn try_new(x: u8, y: u8) -> Option<Self>{
+        if x < 9 && y < 10{
+            Some(Position::new(x, y))
+        }else{
+            None
+        }
+    }
+
     pub fn x(&self) -> u8{
         self.data >> 4
     }
@@ -18,6 +29,20 @@ impl Position{
         self.data & 0x0F
     }
 
+    // 位置编号 x + y * 9，与位棋盘一致，范围 0-89
+    pub fn index(&self) -> u8{
+        self.x() + self.y() * 9
+    }
+
+    // 由编号得到位置，超出 0-89 时返回 None
+    pub fn from_index(index: u8) -> Option<Self>{
+        if index < 90{
+            Some(Position::new(index % 9, index / 9))
+        }else{
+            None
+        }
+    }
+
     pub fn is_valid(&self) -> bool{
         self.x() < 9 && self.y() < 10
     }
@@ -0,0 +1,194 @@
+use super::board::Board;
+use super::diagram::FenError;
+use super::notation;
+use super::moves::Move;
+use super::piece::Color;
+use super::ruler::{Game, GameStatus};
+
+// 棋谱导入错误
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PgnError{
+    // 标签格式错误，附带行号
+    BadTag(usize),
+    // 注释没有结束
+    UnterminatedComment,
+    // 无法识别或不合法的走法，附带步数（从1开始）和原文
+    IllegalMove{ ply: usize, text: String },
+    // FEN 标签给出的开始局面无效
+    BadFen(FenError),
+}
+
+impl std::fmt::Display for PgnError{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self{
+            PgnError::BadTag(line) => write!(f, "malformed tag on line {}", line),
+            PgnError::UnterminatedComment => write!(f, "unterminated comment"),
+            PgnError::IllegalMove{ ply, text } => write!(f, "illegal or unrecognized move '{}' at ply {}", text, ply),
+            PgnError::BadFen(error) => write!(f, "invalid FEN tag: {}", error),
+        }
+    }
+}
+
+impl std::error::Error for PgnError {}
+
+// 对局记录：从开始局面起的走法序列，以及注释和结果
+// 可以由对局生成，也可以从 PGN 棋谱导入
+#[derive(Debug, Clone)]
+pub struct GameRecord{
+    // 棋谱标签，例如 ("Red", "张三")
+    pub tags: Vec<(String, String)>,
+    // 开始局面和先走的一方，None 为初始局面红方先走
+    pub start: Option<(Board, Color)>,
+    pub moves: Vec<Move>,
+    // comments[0] 为开局前的注释，comments[i] 为第 i 步之后的注释，长度为 moves.len() + 1
+    pub comments: Vec<String>,
+    pub result: GameStatus,
+}
+
+impl GameRecord{
+    pub fn from_game(game: &Game) -> Self{
+        let mut tags = Vec::new();
+        for color in [Color::Red, Color::Black]{
+            if let Some(player) = game.players.iter().find(|player| player.color == color){
+                let key = if color == Color::Red { "Red" } else { "Black" };
+                tags.push((key.to_string(), player.name.clone()));
+            }
+        }
+
+        let moves: Vec<Move> = game.move_history.iter().map(|record| record.mv).collect();
+
+        GameRecord{
+            tags,
+            start: None,
+            comments: vec![String::new(); moves.len() + 1],
+            moves,
+            result: game.status.clone(),
+        }
+    }
+
+    // 开始局面和先走的一方
+    pub fn start_position(&self) -> (Board, Color){
+        self.start.clone().unwrap_or_else(|| (Board::new(), Color::Red))
+    }
+
+    // 标签的值
+    pub fn tag(&self, key: &str) -> Option<&str>{
+        self.tags.iter()
+            .find(|(name, _)| name.eq_ignore_ascii_case(key))
+            .map(|(_, value)| value.as_str())
+    }
+
+    // 设置第 ply 步之后的注释，0 为开局前
+    pub fn set_comment(&mut self, ply: usize, comment: &str){
+        if let Some(slot) = self.comments.get_mut(ply){
+            if !slot.is_empty(){
+                slot.push('\n');
+            }
+            slot.push_str(comment.trim());
+        }
+    }
+
+    // 从 PGN 棋谱导入
+    // 走法可以是 ICCS、WXF 或中文纵线记谱；{} 和 ; 为注释；FEN 标签给出开始局面
+    pub fn from_pgn(text: &str) -> Result<Self, PgnError>{
+        let mut record = GameRecord{
+            tags: Vec::new(),
+            start: None,
+            moves: Vec::new(),
+            comments: vec![String::new()],
+            result: GameStatus::Playing,
+        };
+        let mut movetext = String::new();
+
+        // 标签只能出现在行首
+        for (index, line) in text.lines().enumerate(){
+            let trimmed = line.trim();
+            if trimmed.starts_with('[') && movetext.trim().is_empty(){
+                let (key, value) = parse_tag(trimmed).ok_or(PgnError::BadTag(index + 1))?;
+                if key.eq_ignore_ascii_case("FEN"){
+                    record.start = Some(Board::from_fen(&value).map_err(PgnError::BadFen)?);
+                }
+                if key.eq_ignore_ascii_case("Result"){
+                    record.result = parse_result(&value).unwrap_or(GameStatus::Playing);
+                }
+                record.tags.push((key, value));
+            }else{
+                movetext.push_str(line);
+                movetext.push('\n');
+            }
+        }
+
+        let (mut board, mut color) = record.start_position();
+        let mut rest = movetext.as_str();
+        while let Some(c) = rest.chars().next(){
+            if c.is_whitespace(){
+                rest = &rest[c.len_utf8()..];
+                continue;
+            }
+
+            if c == '{'{
+                let end = rest.find('}').ok_or(PgnError::UnterminatedComment)?;
+                record.set_comment(record.moves.len(), &rest[1..end]);
+                rest = &rest[end + 1..];
+                continue;
+            }
+            if c == ';'{
+                let end = rest.find('\n').unwrap_or(rest.len());
+                record.set_comment(record.moves.len(), &rest[1..end]);
+                rest = &rest[end..];
+                continue;
+            }
+
+            let end = rest.find(|c: char| c.is_whitespace() || c == '{' || c == ';').unwrap_or(rest.len());
+            let token = &rest[..end];
+            rest = &rest[end..];
+
+            if let Some(result) = parse_result(token){
+                record.result = result;
+                continue;
+            }
+
+            // 去掉步数编号，例如 1. 或 1...
+            let digits = token.len() - token.trim_start_matches(|c: char| c.is_ascii_digit()).len();
+            let token = if digits > 0 && token[digits..].starts_with('.'){
+                token[digits..].trim_start_matches('.')
+            }else{
+                token
+            };
+            if token.is_empty(){
+                continue;
+            }
+
+            let illegal = || PgnError::IllegalMove{ ply: record.moves.len() + 1, text: token.to_string() };
+            let mv = notation::parse_move(&mut board, color, token).ok_or_else(illegal)?;
+            if board.check_move(mv, color).is_err(){
+                return Err(illegal());
+            }
+
+            board.make_move(mv);
+            color = color.opponent();
+            record.moves.push(mv);
+            record.comments.push(String::new());
+        }
+
+        Ok(record)
+    }
+}
+
+// 解析 [Key "Value"] 形式的标签
+fn parse_tag(line: &str) -> Option<(String, String)>{
+    let inner = line.strip_prefix('[')?.strip_suffix(']')?.trim();
+    let (key, value) = inner.split_once(char::is_whitespace)?;
+    let value = value.trim().strip_prefix('"')?.strip_suffix('"')?;
+    Some((key.to_string(), value.replace("\\\"", "\"")))
+}
+
+fn parse_result(text: &str) -> Option<GameStatus>{
+    match text{
+        "1-0" => Some(GameStatus::RedWon),
+        "0-1" => Some(GameStatus::BlackWon),
+        "1/2-1/2" => Some(GameStatus::Draw),
+        "*" => Some(GameStatus::Playing),
+        _ => None,
+    }
+}
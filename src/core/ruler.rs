@@ -1,8 +1,13 @@
 use std::collections::HashMap;
+use std::sync::Arc;
 use super::piece::PieceType;
 
-use super::board::Board;
+use super::board::{Board, UndoInfo};
 use super::piece::{Color, Piece};
+use super::event::{CatchUp, EventLog, EventRecord, GameEvent, GameSnapshot, Subscription};
+use super::listener::{GameListener, ListenerId, Listeners};
+use super::move_error::{MakeMoveError, MoveError};
+use super::moves::Move;
 
 // 游戏状态枚举
 #[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
@@ -10,7 +15,10 @@ pub enum GameStatus{
     Playing,
     RedWon,
     BlackWon,
+    // 困毙：走棋方无子可走，按规则判负
     Stalemate,
+    // 和棋，例如从棋谱导入的 1/2-1/2
+    Draw,
 }
 
 // 玩家结构体
@@ -29,19 +37,44 @@ pub struct Game{
     pub status: GameStatus,
     pub current_turn: Color,
     pub move_history: Vec<MoveRecord>,  // 用于保存历史操作记录
+    pub events: EventLog,   // 对局事件及观众
+}
+
+impl Game{
+    // 生成当前对局快照
+    pub fn snapshot(&self) -> GameSnapshot{
+        GameSnapshot{
+            game_id: self.id,
+            board: self.board.clone(),
+            players: self.players.clone(),
+            status: self.status.clone(),
+            current_turn: self.current_turn,
+            move_count: self.move_history.len(),
+            last_seq: self.events.last_seq(),
+        }
+    }
 }
 
 
 // 移动记录
 #[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
 pub struct MoveRecord {
-    from_x: u8,
-    from_y: u8,
-    to_x: u8,
-    to_y: u8,
-    moved_piece: Piece,
-    captured_piece: Piece,
-    hash_before: u64,
+    pub mv: Move,
+    pub moved_piece: Piece,
+    pub captured_piece: Piece,
+    pub hash_before: u64,
+}
+
+impl MoveRecord {
+    // 撤销这一步所需的信息
+    pub fn undo_info(&self) -> UndoInfo {
+        UndoInfo {
+            mv: self.mv,
+            moved_piece: self.moved_piece,
+            captured_piece: self.captured_piece,
+            hash: self.hash_before,
+        }
+    }
 }
 
 
@@ -50,6 +83,16 @@ pub struct MoveRecord {
 pub struct Ruler{
     games: HashMap<u32, Game>,
     next_game_id: u32,
+
+    // 监听器无法序列化
+    #[serde(skip)]
+    listeners: Listeners,
+}
+
+impl Default for Ruler{
+    fn default() -> Self{
+        Self::new()
+    }
 }
 
 impl Ruler{
@@ -57,6 +100,7 @@ impl Ruler{
         Ruler{
             games: HashMap::new(),
             next_game_id: 1,
+            listeners: Listeners::new(),
         }
     }
 
@@ -72,17 +116,34 @@ impl Ruler{
             status: GameStatus::Playing,
             current_turn: Color::Red, // 红方先行
             move_history: Vec::new(),
+            events: EventLog::new(),
         };
 
         self.games.insert(game_id, game);
+        self.listeners.notify(|listener| listener.on_game_created(game_id));
         game_id
     }
 
+    // 注册监听器
+    pub fn add_listener(&mut self, listener: Arc<dyn GameListener>) -> ListenerId {
+        self.listeners.add(listener)
+    }
+
+    // 移除监听器
+    pub fn remove_listener(&mut self, id: ListenerId) -> bool {
+        self.listeners.remove(id)
+    }
+
     // 获取游戏
     pub fn get_game(&self, game_id: u32) -> Option<&Game> {
         self.games.get(&game_id)
     }
 
+    // 获取可修改的游戏，只在库内用于试走后撤销，棋局状态不会改变
+    pub(crate) fn get_game_mut(&mut self, game_id: u32) -> Option<&mut Game> {
+        self.games.get_mut(&game_id)
+    }
+
     // 加入玩家
     pub fn add_player_to_game(&mut self, game_id: u32, name: String, color: Color) -> bool {
         if let Some(game) = self.games.get_mut(&game_id){
@@ -100,6 +161,11 @@ impl Ruler{
 
             // 添加玩家
             game.players.push(Player{name, color});
+
+            // 状态更新完成后再通知监听器
+            if let Some(player) = game.players.last() {
+                self.listeners.notify(|listener| listener.on_player_joined(game_id, player));
+            }
             true
         }else{
             false
@@ -107,46 +173,55 @@ impl Ruler{
     }
 
 
-    // 执行移动
-    pub fn make_move(&mut self, game_id: u32, from_x: u8, from_y: u8, to_x: u8, to_y: u8) -> bool {
+    // 以指定一方的身份走子，不是该方走棋时返回 NotYourTurn
+    pub fn make_move_as(&mut self, game_id: u32, color: Color, mv: Move) -> Result<(), MakeMoveError> {
+        let game = self.games.get(&game_id).ok_or(MakeMoveError::GameNotFound)?;
+        if game.status == GameStatus::Playing && game.current_turn != color {
+            return Err(MoveError::NotYourTurn.into());
+        }
+        self.make_move(game_id, mv)
+    }
+
+    // 执行移动，游戏不存在或走法不合法时返回原因
+    pub fn make_move(&mut self, game_id: u32, mv: Move) -> Result<(), MakeMoveError> {
         if let Some(game) = self.games.get_mut(&game_id) {
             // 检查游戏是否正在进行
             if game.status != GameStatus::Playing {
-                return false;
-            }
-            
-            // 创建位置
-            let from = super::position::Position::new(from_x, from_y);
-            let to = super::position::Position::new(to_x, to_y);
-            
-            // 检查移动是否合法
-            if !game.board.is_move_valid(from, to, game.current_turn) {
-                return false;
-            }
-            
-            // 检查移动后是否仍然被将军
-            if !game.board.is_move_safe(from, to, game.current_turn) {
-                return false;
+                return Err(MoveError::GameOver.into());
             }
 
-            // 记录移动前的状态
-            let moved_piece = game.board.get_piece(from);
-            let captured_piece = game.board.get_piece(to);
-            let hash_before = game.board.get_hash();
-            
-            // 执行移动
-            game.board.make_move(from, to);
+            // 所有检查都在修改棋盘之前完成
+            let opponent_color = match game.current_turn {
+                Color::Red => Color::Black,
+                Color::Black => Color::Red,
+                _ => return Err(MoveError::NotYourTurn.into()),
+            };
+
+            // 检查移动是否合法，以及移动后是否照面或仍然被将军
+            game.board.check_move(mv, game.current_turn)?;
+            let (from, to) = (mv.from(), mv.to());
+
+            // 执行移动，同时取得移动前的状态
+            let undo = game.board.make_move(mv);
+            let moved_piece = undo.moved_piece;
+            let captured_piece = undo.captured_piece;
+            let hash_before = undo.hash;
+
+            game.events.emit(game_id, GameEvent::Moved {
+                color: game.current_turn,
+                from,
+                to,
+                piece: moved_piece,
+                captured: captured_piece,
+            });
 
             // 记录移动历史
-            game.move_history.push(MoveRecord {
-                from_x,
-                from_y,
-                to_x,
-                to_y,
+            let record = MoveRecord {
+                mv,
                 moved_piece,
                 captured_piece,
                 hash_before,
-            });
+            };
 
             if captured_piece.piece_type == PieceType::King {
                 // 游戏结束，当前玩家获胜
@@ -158,12 +233,6 @@ impl Ruler{
             }
             
             // 检查游戏是否结束
-            let opponent_color = match game.current_turn {
-                Color::Red => Color::Black,
-                Color::Black => Color::Red,
-                _ => return false,
-            };
-            
             if game.board.is_checkmate(opponent_color) {
                 game.status = match game.current_turn {
                     Color::Red => GameStatus::RedWon,
@@ -173,13 +242,31 @@ impl Ruler{
             } else if game.board.is_stalemate(opponent_color) {
                 game.status = GameStatus::Stalemate;
             }
+
+            let check = game.board.is_in_check(opponent_color);
+            if check {
+                game.events.emit(game_id, GameEvent::Check { color: opponent_color });
+            }
+            game.move_history.push(record);
+
+            if game.status != GameStatus::Playing {
+                game.events.emit(game_id, GameEvent::GameOver { status: game.status.clone() });
+            }
             
             // 切换回合
             game.current_turn = opponent_color;
+
+            // 历史、状态和回合都更新完成后再通知监听器，回调中读取到的是走子之后的对局
+            if let Some(record) = game.move_history.last() {
+                self.listeners.notify(|listener| listener.on_move_made(game_id, record, check));
+            }
+            if game.status != GameStatus::Playing {
+                self.listeners.notify(|listener| listener.on_game_finished(game_id, &game.status));
+            }
             
-            true
+            Ok(())
         } else {
-            false
+            Err(MakeMoveError::GameNotFound)
         }
     }
 
@@ -204,16 +291,10 @@ impl Ruler{
             
             // 获取最后一步移动
             if let Some(last_move) = game.move_history.pop() {
-                // 创建位置
-                let from = super::position::Position::new(last_move.from_x, last_move.from_y);
-                let to = super::position::Position::new(last_move.to_x, last_move.to_y);
-                
-                // 恢复棋盘状态
-                // 将移动的棋子放回原来的位置
-                game.board.set_piece(from, last_move.moved_piece);
+                let (from, to) = (last_move.mv.from(), last_move.mv.to());
                 
-                // 恢复被吃掉的棋子
-                game.board.set_piece(to, last_move.captured_piece);
+                // 恢复棋盘状态：放回移动的棋子和被吃掉的棋子
+                game.board.unmake_move(last_move.undo_info());
                 
                 // 切换回合
                 game.current_turn = match game.current_turn {
@@ -226,6 +307,14 @@ impl Ruler{
                 if game.status != GameStatus::Playing {
                     game.status = GameStatus::Playing;
                 }
+
+                game.events.emit(game_id, GameEvent::Undo {
+                    from,
+                    to,
+                    piece: last_move.moved_piece,
+                    captured: last_move.captured_piece,
+                });
+                self.listeners.notify(|listener| listener.on_undo(game_id, &last_move));
                 
                 return true;
             }
@@ -233,5 +322,49 @@ impl Ruler{
         
         false
     }
-}
 
+    // 提和
+    pub fn offer_draw(&mut self, game_id: u32, color: Color) -> bool {
+        if let Some(game) = self.games.get_mut(&game_id) {
+            if game.status != GameStatus::Playing || color == Color::None {
+                return false;
+            }
+
+            game.events.emit(game_id, GameEvent::DrawOffered { color });
+            true
+        } else {
+            false
+        }
+    }
+
+    // 观众订阅对局事件
+    // resume_from 为已收到的最后一个事件序号，用于断线续传：此时只补发错过的事件，不再附带快照
+    pub fn subscribe(&mut self, game_id: u32, name: String, resume_from: Option<u64>) -> Option<Subscription> {
+        let game = self.games.get_mut(&game_id)?;
+
+        let catch_up = match resume_from {
+            Some(seq) => CatchUp::Replay(game.events.since(seq)),
+            None => CatchUp::Snapshot(Box::new(game.snapshot())),
+        };
+        let (id, receiver) = game.events.subscribe(name);
+
+        Some(Subscription {
+            id,
+            catch_up,
+            receiver,
+        })
+    }
+
+    // 按订阅ID取消订阅
+    pub fn unsubscribe(&mut self, game_id: u32, subscription_id: u64) -> bool {
+        match self.games.get_mut(&game_id) {
+            Some(game) => game.events.unsubscribe(subscription_id),
+            None => false,
+        }
+    }
+
+    // 获取序号大于 seq 的事件，供轮询方式的前端使用
+    pub fn get_events_since(&self, game_id: u32, seq: u64) -> Option<Vec<EventRecord>> {
+        self.games.get(&game_id).map(|game| game.events.since(seq))
+    }
+}
@@ -0,0 +1,126 @@
+use super::board::Board;
+use super::moves::Move;
+use super::position::Position;
+use super::piece::{Color, PieceType};
+
+// 将死的分值，减去步数以便优先选择更快的杀棋
+const MATE_SCORE: i32 = 100_000;
+
+// 局面评估，从 color 一方的角度计算
+pub fn evaluate(board: &Board, color: Color) -> i32{
+    let mut score = 0;
+
+    for x in 0..9{
+        for y in 0..10{
+            let piece = board.get_piece(Position::new(x, y));
+            if piece.piece_type == PieceType::None{
+                continue;
+            }
+
+            let mut value = piece.piece_type.value();
+
+            // 过河兵价值翻倍
+            if piece.piece_type == PieceType::Pawn{
+                let crossed = match piece.color{
+                    Color::Red => y >= 5,
+                    _ => y < 5,
+                };
+                if crossed{
+                    value *= 2;
+                }
+            }
+
+            if piece.color == color{
+                score += value;
+            }else{
+                score -= value;
+            }
+        }
+    }
+
+    score
+}
+
+// 搜索器：固定深度的 alpha-beta 搜索
+#[derive(Debug, Clone)]
+pub struct Searcher{
+    depth: u32,
+}
+
+impl Searcher{
+    // level 为搜索深度，至少为1
+    pub fn new(level: u32) -> Self{
+        Searcher{
+            depth: level.max(1),
+        }
+    }
+
+    pub fn depth(&self) -> u32{
+        self.depth
+    }
+
+    // 为 color 一方寻找最佳走法，没有合法走法时返回 None
+    pub fn best_move(&self, board: &Board, color: Color) -> Option<Move>{
+        let mut board = board.clone();
+        let mut moves = board.legal_moves(color);
+        order_moves(&board, &mut moves);
+
+        let mut best = None;
+        let mut alpha = -MATE_SCORE - 1;
+        let beta = MATE_SCORE + 1;
+
+        for mv in moves{
+            let score = -self.search_move(&mut board, mv, color, self.depth - 1, -beta, -alpha, 1);
+            if best.is_none() || score > alpha{
+                alpha = score;
+                best = Some(mv);
+            }
+        }
+
+        best
+    }
+
+    // 执行走法、搜索后恢复棋盘
+    #[allow(clippy::too_many_arguments)]
+    fn search_move(&self, board: &mut Board, mv: Move, color: Color, depth: u32, alpha: i32, beta: i32, ply: i32) -> i32{
+        let undo = board.make_move(mv);
+        let score = if undo.captured_piece.piece_type == PieceType::King{
+            -(MATE_SCORE - ply)
+        }else{
+            self.negamax(board, color.opponent(), depth, alpha, beta, ply)
+        };
+        board.unmake_move(undo);
+
+        score
+    }
+
+    fn negamax(&self, board: &mut Board, color: Color, depth: u32, mut alpha: i32, beta: i32, ply: i32) -> i32{
+        if depth == 0{
+            return evaluate(board, color);
+        }
+
+        let mut moves = board.legal_moves(color);
+        // 无子可走即为输棋（将死或困毙）
+        if moves.is_empty(){
+            return -(MATE_SCORE - ply);
+        }
+        order_moves(board, &mut moves);
+
+        for mv in moves{
+            let score = -self.search_move(board, mv, color, depth - 1, -beta, -alpha, ply + 1);
+            if score >= beta{
+                return beta;
+            }
+            if score > alpha{
+                alpha = score;
+            }
+        }
+
+        alpha
+    }
+}
+
+// 吃子走法优先，吃价值高的棋子优先
+fn order_moves(board: &Board, moves: &mut [Move]){
+    moves.sort_by_key(|mv| -board.get_piece(mv.to()).piece_type.value());
+}
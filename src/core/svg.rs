@@ -0,0 +1,354 @@
+use std::fmt::Write;
+
+use super::board::Board;
+use super::moves::Move;
+use super::position::Position;
+use super::piece::{Color, Piece};
+use super::ruler::Game;
+
+// 棋盘颜色
+const BOARD_FILL: &str = "#eecb8c";
+const LINE_COLOR: &str = "#5a3a1a";
+const PIECE_FILL: &str = "#f8e7c3";
+const RED_COLOR: &str = "#c0141c";
+const BLACK_COLOR: &str = "#1a1a1a";
+const HIGHLIGHT_COLOR: &str = "#3c8dde";
+const ARROW_COLOR: &str = "#2a9d3a";
+
+// SVG 导出选项
+#[derive(Debug, Clone)]
+pub struct SvgOptions{
+    // 位于下方的一方
+    pub perspective: Color,
+    // 上一步走法，绘制为箭头
+    pub last_move: Option<Move>,
+    // 需要高亮的格点
+    pub highlights: Vec<Position>,
+    // 是否绘制坐标
+    pub coordinates: bool,
+    // 格子边长（像素）
+    pub cell_size: u32,
+}
+
+impl Default for SvgOptions{
+    fn default() -> Self{
+        SvgOptions{
+            perspective: Color::Red,
+            last_move: None,
+            highlights: Vec::new(),
+            coordinates: true,
+            cell_size: 50,
+        }
+    }
+}
+
+// 棋盘坐标到画布坐标的换算
+struct Layout{
+    cell: f32,
+    margin: f32,
+    perspective: Color,
+}
+
+impl Layout{
+    fn new(options: &SvgOptions) -> Self{
+        let cell = options.cell_size.max(10) as f32;
+        Layout{
+            cell,
+            margin: cell,
+            perspective: options.perspective,
+        }
+    }
+
+    fn width(&self) -> f32{
+        self.cell * 8.0 + self.margin * 2.0
+    }
+
+    fn height(&self) -> f32{
+        self.cell * 9.0 + self.margin * 2.0
+    }
+
+    // 画布上的列、行（从左上角开始）
+    fn column(&self, x: u8) -> f32{
+        match self.perspective{
+            Color::Black => (8 - x) as f32,
+            _ => x as f32,
+        }
+    }
+
+    fn row(&self, y: u8) -> f32{
+        match self.perspective{
+            Color::Black => y as f32,
+            _ => (9 - y) as f32,
+        }
+    }
+
+    fn point(&self, pos: Position) -> (f32, f32){
+        (
+            self.margin + self.column(pos.x()) * self.cell,
+            self.margin + self.row(pos.y()) * self.cell,
+        )
+    }
+}
+
+fn line(out: &mut String, (x1, y1): (f32, f32), (x2, y2): (f32, f32)){
+    let _ = writeln!(out, r#"<line x1="{:.1}" y1="{:.1}" x2="{:.1}" y2="{:.1}"/>"#, x1, y1, x2, y2);
+}
+
+// 棋盘网格、九宫斜线和河界
+fn draw_grid(out: &mut String, layout: &Layout){
+    let _ = writeln!(out, r#"<g stroke="{}" stroke-width="1.5">"#, LINE_COLOR);
+
+    for y in 0..10{
+        line(out, layout.point(Position::new(0, y)), layout.point(Position::new(8, y)));
+    }
+    for x in 0..9{
+        if x == 0 || x == 8{
+            line(out, layout.point(Position::new(x, 0)), layout.point(Position::new(x, 9)));
+        }else{
+            // 河界处断开
+            line(out, layout.point(Position::new(x, 0)), layout.point(Position::new(x, 4)));
+            line(out, layout.point(Position::new(x, 5)), layout.point(Position::new(x, 9)));
+        }
+    }
+
+    // 九宫斜线
+    for (bottom, top) in [(0, 2), (7, 9)]{
+        line(out, layout.point(Position::new(3, bottom)), layout.point(Position::new(5, top)));
+        line(out, layout.point(Position::new(5, bottom)), layout.point(Position::new(3, top)));
+    }
+
+    // 外框
+    let inset = layout.cell * 0.12;
+    let _ = writeln!(
+        out,
+        r#"<rect x="{:.1}" y="{:.1}" width="{:.1}" height="{:.1}" fill="none" stroke-width="3"/>"#,
+        layout.margin - inset,
+        layout.margin - inset,
+        layout.cell * 8.0 + inset * 2.0,
+        layout.cell * 9.0 + inset * 2.0
+    );
+
+    draw_marks(out, layout);
+    out.push_str("</g>\n");
+
+    // 河界文字
+    let river_y = layout.margin + layout.cell * 4.5;
+    let font_size = layout.cell * 0.5;
+    for (text, column) in [("楚 河", 2.0), ("汉 界", 6.0)]{
+        let _ = writeln!(
+            out,
+            r#"<text x="{:.1}" y="{:.1}" font-size="{:.1}" fill="{}" text-anchor="middle" dominant-baseline="central">{}</text>"#,
+            layout.margin + layout.cell * column,
+            river_y,
+            font_size,
+            LINE_COLOR,
+            text
+        );
+    }
+}
+
+// 炮位和兵位的标记
+fn draw_marks(out: &mut String, layout: &Layout){
+    let mut points = vec![
+        Position::new(1, 2), Position::new(7, 2),
+        Position::new(1, 7), Position::new(7, 7),
+    ];
+    for x in (0..9).step_by(2){
+        points.push(Position::new(x, 3));
+        points.push(Position::new(x, 6));
+    }
+
+    let gap = layout.cell * 0.08;
+    let length = layout.cell * 0.2;
+    for pos in points{
+        let (cx, cy) = layout.point(pos);
+        for dx in [-1.0f32, 1.0]{
+            // 边线上只画朝内的一半
+            let column = layout.column(pos.x());
+            if (column == 0.0 && dx < 0.0) || (column == 8.0 && dx > 0.0){
+                continue;
+            }
+            for dy in [-1.0f32, 1.0]{
+                let corner = (cx + dx * gap, cy + dy * gap);
+                line(out, (corner.0 + dx * length, corner.1), corner);
+                line(out, corner, (corner.0, corner.1 + dy * length));
+            }
+        }
+    }
+}
+
+// 坐标标注，与命令中的坐标一致
+fn draw_coordinates(out: &mut String, layout: &Layout){
+    let font_size = layout.cell * 0.3;
+    let _ = writeln!(
+        out,
+        r#"<g font-size="{:.1}" fill="{}" text-anchor="middle" dominant-baseline="central">"#,
+        font_size, LINE_COLOR
+    );
+
+    let bottom = layout.height() - layout.margin * 0.4;
+    for x in 0..9{
+        let (px, _) = layout.point(Position::new(x, 0));
+        let _ = writeln!(out, r#"<text x="{:.1}" y="{:.1}">{}</text>"#, px, bottom, x);
+    }
+    let left = layout.margin * 0.4;
+    for y in 0..10{
+        let (_, py) = layout.point(Position::new(0, y));
+        let _ = writeln!(out, r#"<text x="{:.1}" y="{:.1}">{}</text>"#, left, py, y);
+    }
+
+    out.push_str("</g>\n");
+}
+
+fn draw_highlights(out: &mut String, layout: &Layout, highlights: &[Position]){
+    let size = layout.cell * 0.96;
+    for &pos in highlights.iter().filter(|pos| pos.is_valid()){
+        let (cx, cy) = layout.point(pos);
+        let _ = writeln!(
+            out,
+            r#"<rect x="{:.1}" y="{:.1}" width="{:.1}" height="{:.1}" rx="{:.1}" fill="{}" fill-opacity="0.35"/>"#,
+            cx - size / 2.0,
+            cy - size / 2.0,
+            size,
+            size,
+            size * 0.15,
+            HIGHLIGHT_COLOR
+        );
+    }
+}
+
+fn draw_piece(out: &mut String, (cx, cy): (f32, f32), radius: f32, piece: Piece){
+    let color = if piece.color == Color::Red { RED_COLOR } else { BLACK_COLOR };
+
+    let _ = writeln!(
+        out,
+        r#"<g><circle cx="{:.1}" cy="{:.1}" r="{:.1}" fill="{}" stroke="{}" stroke-width="2"/><circle cx="{:.1}" cy="{:.1}" r="{:.1}" fill="none" stroke="{}" stroke-width="1"/><text x="{:.1}" y="{:.1}" font-size="{:.1}" fill="{}" text-anchor="middle" dominant-baseline="central">{}</text></g>"#,
+        cx, cy, radius, PIECE_FILL, color,
+        cx, cy, radius * 0.82, color,
+        cx, cy, radius * 1.1, color, piece.chinese_name()
+    );
+}
+
+// 走法箭头，从起点棋子边缘画到终点
+fn draw_arrow(out: &mut String, layout: &Layout, from: Position, to: Position){
+    if !from.is_valid() || !to.is_valid() || from == to{
+        return;
+    }
+
+    let (x1, y1) = layout.point(from);
+    let (x2, y2) = layout.point(to);
+    let (dx, dy) = (x2 - x1, y2 - y1);
+    let distance = (dx * dx + dy * dy).sqrt();
+    let shrink = layout.cell * 0.3 / distance;
+
+    let _ = writeln!(
+        out,
+        r#"<line x1="{:.1}" y1="{:.1}" x2="{:.1}" y2="{:.1}" stroke="{}" stroke-width="{:.1}" stroke-opacity="0.8" stroke-linecap="round" marker-end="url(#arrowhead)"/>"#,
+        x1 + dx * shrink,
+        y1 + dy * shrink,
+        x2 - dx * shrink,
+        y2 - dy * shrink,
+        ARROW_COLOR,
+        layout.cell * 0.1
+    );
+}
+
+// SVG 开头、箭头定义、底色、网格和坐标
+fn open_canvas(out: &mut String, layout: &Layout, options: &SvgOptions, extra_defs: &str){
+    let _ = writeln!(
+        out,
+        r#"<svg xmlns="http://www.w3.org/2000/svg" width="{:.0}" height="{:.0}" viewBox="0 0 {:.0} {:.0}" font-family="KaiTi, STKaiti, SimSun, serif">"#,
+        layout.width(), layout.height(), layout.width(), layout.height()
+    );
+    let _ = writeln!(
+        out,
+        r#"<defs><marker id="arrowhead" viewBox="0 0 10 10" refX="5" refY="5" markerWidth="3" markerHeight="3" orient="auto"><path d="M0,0 L10,5 L0,10 z" fill="{}"/></marker>{}</defs>"#,
+        ARROW_COLOR, extra_defs
+    );
+    let _ = writeln!(
+        out,
+        r#"<rect width="{:.0}" height="{:.0}" fill="{}"/>"#,
+        layout.width(), layout.height(), BOARD_FILL
+    );
+
+    draw_grid(out, layout);
+    if options.coordinates{
+        draw_coordinates(out, layout);
+    }
+    draw_highlights(out, layout, &options.highlights);
+}
+
+// 网页查看器使用的空棋盘：棋子以 piece-<FEN字母> 为 id 定义在 defs 中（中心在原点），
+// 由脚本放入 id 为 pieces 和 arrow 的分组
+pub(crate) fn viewer_svg(options: &SvgOptions) -> String{
+    let layout = Layout::new(options);
+    let mut defs = String::new();
+    for color in [Color::Red, Color::Black]{
+        for c in "KABNRCP".chars(){
+            let c = if color == Color::Red { c } else { c.to_ascii_lowercase() };
+            if let Some(piece) = Piece::from_fen_char(c){
+                let mut symbol = String::new();
+                draw_piece(&mut symbol, (0.0, 0.0), layout.cell * 0.44, piece);
+                let _ = write!(defs, r#"<g id="piece-{}">{}</g>"#, c, symbol.trim_end());
+            }
+        }
+    }
+
+    let mut out = String::new();
+    open_canvas(&mut out, &layout, options, &defs);
+    let _ = writeln!(
+        out,
+        r#"<g id="pieces"></g><g id="arrow" stroke="{}" stroke-width="{:.1}" stroke-opacity="0.8" stroke-linecap="round"></g>"#,
+        ARROW_COLOR,
+        layout.cell * 0.1
+    );
+    out.push_str("</svg>\n");
+    out
+}
+
+impl Board{
+    // 导出为 SVG 图片
+    pub fn to_svg(&self, options: &SvgOptions) -> String{
+        let layout = Layout::new(options);
+        let mut out = String::new();
+        open_canvas(&mut out, &layout, options, "");
+
+        for x in 0..9{
+            for y in 0..10{
+                let pos = Position::new(x, y);
+                let piece = self.get_piece(pos);
+                if !piece.is_empty(){
+                    draw_piece(&mut out, layout.point(pos), layout.cell * 0.44, piece);
+                }
+            }
+        }
+
+        if let Some(mv) = options.last_move{
+            let (from, to) = (mv.from(), mv.to());
+            draw_arrow(&mut out, &layout, from, to);
+        }
+
+        out.push_str("</svg>\n");
+        out
+    }
+}
+
+impl Game{
+    // 按走子顺序导出每一步的 SVG，第一张为初始局面
+    // 每张图的上一步箭头由对局记录决定，options 中的 last_move 会被忽略
+    pub fn to_svg_frames(&self, options: &SvgOptions) -> Vec<String>{
+        let mut board = Board::new();
+        let mut frame_options = options.clone();
+        frame_options.last_move = None;
+
+        let mut frames = vec![board.to_svg(&frame_options)];
+        for record in &self.move_history{
+            board.make_move(record.mv);
+
+            frame_options.last_move = Some(record.mv);
+            frames.push(board.to_svg(&frame_options));
+        }
+
+        frames
+    }
+}
@@ -0,0 +1,101 @@
+use super::position::Position;
+use super::piece::{Color, Piece, PieceType};
+
+// 棋盘数据不一致或不符合规则的一处问题，见 Board::validate
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Inconsistency{
+    // 位棋盘与棋子数组在该位置不一致
+    Bitboard{ pos: Position, piece: Piece },
+    // 九宫等其他位棋盘数据有误
+    BitboardMasks,
+    // 缓存的王的位置与棋盘不一致
+    KingPosition{ color: Color, cached: Option<Position>, actual: Option<Position> },
+    // 保存的哈希值与重新计算的不一致
+    Hash{ stored: u64, computed: u64 },
+    // 缓存中的哈希值不是当前哈希值
+    CachedHash{ cached: u64, current: u64 },
+    // 缓存的将军状态有误
+    CachedCheck{ color: Color, cached: bool, actual: bool },
+    // 缓存的合法走法有误
+    CachedLegalMoves{ pos: Position, color: Color },
+    // 一方某种棋子超过规定数量
+    TooManyPieces{ piece: Piece, count: usize },
+    // 棋子不在该棋子可以到达的位置，例如士不在九宫的五个点上
+    IllegalPlacement{ pos: Position, piece: Piece },
+    // 将帅在同一纵线上且中间没有棋子
+    KingsFacing{ red: Position, black: Position },
+}
+
+impl std::fmt::Display for Inconsistency{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let at = |pos: &Position| format!("({}, {})", pos.x(), pos.y());
+        match self{
+            Inconsistency::Bitboard{ pos, piece } => {
+                write!(f, "bitboard disagrees with {:?} {:?} at {}", piece.color, piece.piece_type, at(pos))
+            }
+            Inconsistency::BitboardMasks => write!(f, "bitboard masks are corrupted"),
+            Inconsistency::KingPosition{ color, cached, actual } => {
+                write!(f, "{:?} king cached at {:?}, found at {:?}", color, cached.as_ref().map(at), actual.as_ref().map(at))
+            }
+            Inconsistency::Hash{ stored, computed } => write!(f, "hash {:016x} should be {:016x}", stored, computed),
+            Inconsistency::CachedHash{ cached, current } => write!(f, "cached hash {:016x} differs from {:016x}", cached, current),
+            Inconsistency::CachedCheck{ color, cached, actual } => {
+                write!(f, "cached check status for {:?} is {}, should be {}", color, cached, actual)
+            }
+            Inconsistency::CachedLegalMoves{ pos, color } => write!(f, "cached legal moves for {:?} at {} are stale", color, at(pos)),
+            Inconsistency::TooManyPieces{ piece, count } => {
+                write!(f, "{} {:?} {:?} pieces, at most {}", count, piece.color, piece.piece_type, max_count(piece.piece_type))
+            }
+            Inconsistency::IllegalPlacement{ pos, piece } => {
+                write!(f, "{:?} {:?} cannot stand at {}", piece.color, piece.piece_type, at(pos))
+            }
+            Inconsistency::KingsFacing{ red, black } => write!(f, "kings face each other at {} and {}", at(red), at(black)),
+        }
+    }
+}
+
+// Board::validate 发现的所有问题
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InconsistencyReport{
+    pub issues: Vec<Inconsistency>,
+}
+
+impl std::fmt::Display for InconsistencyReport{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let issues: Vec<String> = self.issues.iter().map(|issue| issue.to_string()).collect();
+        write!(f, "{}", issues.join("; "))
+    }
+}
+
+impl std::error::Error for InconsistencyReport {}
+
+// 每方每种棋子的最多数量
+pub fn max_count(piece_type: PieceType) -> usize{
+    match piece_type{
+        PieceType::King => 1,
+        PieceType::Pawn => 5,
+        PieceType::None => 0,
+        _ => 2,
+    }
+}
+
+// 棋子是否可能出现在该位置
+// 将和士只能在九宫内的规定点上，象只能在己方的七个点上，未过河的兵只能在原来的列上
+pub fn placement_allowed(pos: Position, piece: Piece) -> bool{
+    let x = pos.x();
+    // 换算成红方视角
+    let y = match piece.color{
+        Color::Red => pos.y(),
+        Color::Black => 9 - pos.y(),
+        Color::None => return piece.piece_type == PieceType::None,
+    };
+
+    match piece.piece_type{
+        PieceType::King => (3..=5).contains(&x) && y <= 2,
+        PieceType::Advisor => matches!((x, y), (3, 0) | (5, 0) | (4, 1) | (3, 2) | (5, 2)),
+        PieceType::Elephant => matches!((x, y), (2, 0) | (6, 0) | (0, 2) | (4, 2) | (8, 2) | (2, 4) | (6, 4)),
+        PieceType::Pawn => y >= 5 || (y >= 3 && x.is_multiple_of(2)),
+        PieceType::Horse | PieceType::Chariot | PieceType::Cannon => true,
+        PieceType::None => false,
+    }
+}
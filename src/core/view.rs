@@ -0,0 +1,301 @@
+use super::board::Board;
+use super::diagram::{fen_side, RenderStyle};
+use super::event::{EventRecord, GameEvent};
+use super::notation::{self, Notation};
+use super::moves::Move;
+use super::position::Position;
+use super::piece::{Color, Piece};
+use super::ruler::{Game, GameStatus, Player};
+
+// 走法的展示形式
+#[derive(Debug, Clone, PartialEq, serde::Serialize)]
+pub struct MoveView{
+    pub from_x: u8,
+    pub from_y: u8,
+    pub to_x: u8,
+    pub to_y: u8,
+    pub piece: Piece,
+    // 被吃掉的棋子，没有吃子时为空
+    pub captured: Piece,
+    // ICCS 坐标记谱，例如 h2e2
+    pub iccs: String,
+    // 中文纵线记谱，例如 炮二平五
+    pub chinese: String,
+}
+
+impl MoveView{
+    // board 为走子之前的棋盘
+    pub fn new(board: &Board, mv: Move) -> Self{
+        let (from, to) = (mv.from(), mv.to());
+        MoveView{
+            from_x: from.x(),
+            from_y: from.y(),
+            to_x: to.x(),
+            to_y: to.y(),
+            piece: board.get_piece(from),
+            captured: board.get_piece(to),
+            iccs: notation::to_iccs(from, to),
+            chinese: notation::to_chinese(board, from, to),
+        }
+    }
+}
+
+// 事件的展示形式，只包含该类事件用到的字段
+#[derive(Debug, Clone, PartialEq, serde::Serialize)]
+pub struct EventView{
+    pub seq: u64,
+    // MOVED、CHECK、UNDO、DRAW_OFFERED 或 GAME_OVER
+    pub kind: &'static str,
+    // 走子或提和的一方，CHECK 时为被将军的一方
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub color: Option<Color>,
+    // 走子或被撤销的一步
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub from: Option<(u8, u8)>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub to: Option<(u8, u8)>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub piece: Option<Piece>,
+    // 没有吃子时省略
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub captured: Option<Piece>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub status: Option<GameStatus>,
+}
+
+impl EventView{
+    pub fn new(record: &EventRecord) -> Self{
+        let mut view = EventView{
+            seq: record.seq,
+            kind: "",
+            color: None,
+            from: None,
+            to: None,
+            piece: None,
+            captured: None,
+            status: None,
+        };
+        let set_move = |view: &mut EventView, from: Position, to: Position, piece: Piece, captured: Piece|{
+            view.from = Some((from.x(), from.y()));
+            view.to = Some((to.x(), to.y()));
+            view.piece = Some(piece);
+            view.captured = (!captured.is_empty()).then_some(captured);
+        };
+
+        match &record.event{
+            GameEvent::Moved{ color, from, to, piece, captured } => {
+                view.kind = "MOVED";
+                view.color = Some(*color);
+                set_move(&mut view, *from, *to, *piece, *captured);
+            }
+            GameEvent::Check{ color } => {
+                view.kind = "CHECK";
+                view.color = Some(*color);
+            }
+            GameEvent::Undo{ from, to, piece, captured } => {
+                view.kind = "UNDO";
+                set_move(&mut view, *from, *to, *piece, *captured);
+            }
+            GameEvent::DrawOffered{ color } => {
+                view.kind = "DRAW_OFFERED";
+                view.color = Some(*color);
+            }
+            GameEvent::GameOver{ status } => {
+                view.kind = "GAME_OVER";
+                view.status = Some(status.clone());
+            }
+        }
+        view
+    }
+}
+
+// 对局的完整状态，客户端无需自己维护棋盘
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct GameView{
+    pub game_id: u32,
+    pub status: GameStatus,
+    pub fen: String,
+    // 红方在下方的字母棋盘图
+    pub board: String,
+    pub current_turn: Color,
+    pub in_check: bool,
+    pub players: Vec<Player>,
+    pub move_count: usize,
+    pub last_move: Option<MoveView>,
+    // 被吃掉的红方棋子
+    pub captured_red: Vec<Piece>,
+    // 被吃掉的黑方棋子
+    pub captured_black: Vec<Piece>,
+}
+
+// 对局列表中的一项
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct GameSummary{
+    pub game_id: u32,
+    pub status: GameStatus,
+    pub current_turn: Color,
+    pub move_count: usize,
+    pub players: Vec<Player>,
+}
+
+impl Game{
+    // 当前局面的 FEN，带有回合数
+    pub fn to_fen(&self) -> String{
+        format!(
+            "{} {} - - 0 {}",
+            self.board.fen_placement(),
+            fen_side(self.current_turn),
+            self.move_history.len() / 2 + 1
+        )
+    }
+
+    // 生成对局的完整状态
+    pub fn view(&self) -> GameView{
+        let last_move = self.move_history.last().map(|record|{
+            // 还原走子前的棋盘用于中文记谱
+            let mut before = self.board.clone();
+            before.unmake_move(record.undo_info());
+
+            MoveView::new(&before, record.mv)
+        });
+
+        let captured = |color: Color| -> Vec<Piece>{
+            self.move_history.iter()
+                .map(|record| record.captured_piece)
+                .filter(|piece| !piece.is_empty() && piece.color == color)
+                .collect()
+        };
+
+        GameView{
+            game_id: self.id,
+            status: self.status.clone(),
+            fen: self.to_fen(),
+            board: self.board.render(RenderStyle::Ascii, Color::Red),
+            current_turn: self.current_turn,
+            in_check: self.board.is_in_check(self.current_turn),
+            players: self.players.clone(),
+            move_count: self.move_history.len(),
+            last_move,
+            captured_red: captured(Color::Red),
+            captured_black: captured(Color::Black),
+        }
+    }
+
+    // 对局列表中的一项
+    pub fn summary(&self) -> GameSummary{
+        GameSummary{
+            game_id: self.id,
+            status: self.status.clone(),
+            current_turn: self.current_turn,
+            move_count: self.move_history.len(),
+            players: self.players.clone(),
+        }
+    }
+
+    // 按指定记谱方式列出已走的每一步，从初始局面重放
+    pub fn history(&self, notation: Notation) -> Vec<String>{
+        let mut board = Board::new();
+        self.move_history.iter()
+            .map(|record|{
+                let text = notation.format(&board, record.mv);
+                board.make_move(record.mv);
+                text
+            })
+            .collect()
+    }
+
+    // 当前走棋方的合法走法，from 不为空时只列出该位置棋子的走法
+    // 对局结束后没有合法走法
+    pub fn legal_moves(&mut self, from: Option<Position>) -> Vec<MoveView>{
+        if self.status != GameStatus::Playing{
+            return Vec::new();
+        }
+        self.board.legal_moves(self.current_turn)
+            .into_iter()
+            .filter(|mv| from.is_none_or(|from| from == mv.from()))
+            .map(|mv| MoveView::new(&self.board, mv))
+            .collect()
+    }
+}
+
+fn color_name(color: Color) -> &'static str{
+    match color{
+        Color::Red => "RED",
+        Color::Black => "BLACK",
+        Color::None => "NONE",
+    }
+}
+
+fn piece_list(pieces: &[Piece]) -> String{
+    if pieces.is_empty(){
+        return "-".to_string();
+    }
+    pieces.iter().map(|piece| piece.to_fen_char().to_string()).collect::<Vec<_>>().join(" ")
+}
+
+// 文本格式：(x,y) TO (x,y) ICCS 中文记谱
+impl std::fmt::Display for MoveView{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "({},{}) TO ({},{}) {} {}",
+            self.from_x, self.from_y, self.to_x, self.to_y, self.iccs, self.chinese
+        )
+    }
+}
+
+// 文本格式：EVENT 序号 类型，之后是该类事件的字段，例如
+// EVENT 1 MOVED RED (7,2) TO (4,2) C、EVENT 2 CHECK BLACK、EVENT 5 GAME_OVER RedWon
+impl std::fmt::Display for EventView{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "EVENT {} {}", self.seq, self.kind)?;
+        if let Some(color) = self.color{
+            write!(f, " {}", color_name(color))?;
+        }
+        if let (Some(from), Some(to)) = (self.from, self.to){
+            write!(f, " ({},{}) TO ({},{})", from.0, from.1, to.0, to.1)?;
+        }
+        if let Some(piece) = self.piece{
+            write!(f, " {}", piece.to_fen_char())?;
+        }
+        if let Some(captured) = self.captured{
+            write!(f, " CAPTURED {}", captured.to_fen_char())?;
+        }
+        if let Some(status) = &self.status{
+            write!(f, " {:?}", status)?;
+        }
+        Ok(())
+    }
+}
+
+// 文本格式：GAME 1 Playing TURN RED MOVES 3
+impl std::fmt::Display for GameSummary{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "GAME {} {:?} TURN {} MOVES {}",
+            self.game_id, self.status, color_name(self.current_turn), self.move_count
+        )
+    }
+}
+
+// 文本格式：第一行与之前的 GAME_STATUS 结果相同，之后每行一项，最后是棋盘图
+impl std::fmt::Display for GameView{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "GAME_STATUS {} {:?}", self.game_id, self.status)?;
+        writeln!(f, "FEN {}", self.fen)?;
+        writeln!(f, "TURN {}", color_name(self.current_turn))?;
+        writeln!(f, "CHECK {}", if self.in_check { "YES" } else { "NO" })?;
+        for player in &self.players{
+            writeln!(f, "PLAYER {} {}", color_name(player.color), player.name)?;
+        }
+        writeln!(f, "MOVES {}", self.move_count)?;
+        match &self.last_move{
+            Some(last) => writeln!(f, "LAST_MOVE {}", last)?,
+            None => writeln!(f, "LAST_MOVE -")?,
+        }
+        writeln!(f, "CAPTURED RED {}", piece_list(&self.captured_red))?;
+        writeln!(f, "CAPTURED BLACK {}", piece_list(&self.captured_black))?;
+        write!(f, "{}", self.board.trim_end())
+    }
+}
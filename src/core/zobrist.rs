@@ -10,6 +10,12 @@ pub struct ZobristHash{
     table: [[[[u64; 10]; 9]; 3]; 8],
 }
 
+impl Default for ZobristHash{
+    fn default() -> Self{
+        Self::new()
+    }
+}
+
 impl ZobristHash{
     // 创建新的Zobrist Hash表
     pub fn new() -> Self {
@@ -17,11 +23,11 @@ impl ZobristHash{
         let mut table = [[[[0u64; 10]; 9]; 3]; 8];
         
         // 为每种棋子类型、颜色、位置生成随机数
-        for pt in 0..8 {
-            for c in 0..3 {
-                for x in 0..9 {
-                    for y in 0..10 {
-                        table[pt][c][x][y] = rng.r#gen();
+        for colors in table.iter_mut() {
+            for files in colors.iter_mut() {
+                for ranks in files.iter_mut() {
+                    for value in ranks.iter_mut() {
+                        *value = rng.r#gen();
                     }
                 }
             }
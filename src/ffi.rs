@@ -0,0 +1,1094 @@
+// C API
+// 所有导出函数都会捕获 panic，失败时返回错误码，并可以通过 cec_last_error() 获取错误信息
+// 引擎句柄内部带锁，可以在多个线程中使用
+
+use std::cell::RefCell;
+use std::ffi::{CStr, CString};
+use std::panic::{self, AssertUnwindSafe};
+use std::sync::{Arc, Mutex, MutexGuard, OnceLock};
+
+use crate::core::ruler::Game;
+use crate::{Color, Engine, EngineResult, EventView, GameId, GameListener, GameStatus, GameView, Language, MoveError, MoveList, MoveRecord, OutputFormat, Piece, Player, Position};
+
+#[repr(C)]
+pub struct CECEngine {
+    _private: [u8; 0],
+}
+
+/// 返回结果
+#[repr(C)]
+pub struct CECEngineResult {
+    _private: [u8; 0],
+}
+
+/// 棋子
+/// piece_type: 0-6 依次为 将 士 象 马 车 炮 兵，7 为空
+/// color: 0 为无，1 为红，2 为黑
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CECPiece {
+    pub piece_type: u8,
+    pub color: u8,
+}
+
+impl From<Piece> for CECPiece {
+    fn from(piece: Piece) -> Self {
+        CECPiece {
+            piece_type: piece.piece_type.from_piece_type_to_usize() as u8,
+            color: piece.color.from_color_to_usize() as u8,
+        }
+    }
+}
+
+/// 棋盘坐标
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CECPosition {
+    pub x: u8,
+    pub y: u8,
+}
+
+/// 对局状态
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CECGameStatus {
+    Playing = 0,
+    RedWon = 1,
+    BlackWon = 2,
+    Stalemate = 3,
+    Draw = 4,
+}
+
+impl From<&GameStatus> for CECGameStatus {
+    fn from(status: &GameStatus) -> Self {
+        match status {
+            GameStatus::Playing => CECGameStatus::Playing,
+            GameStatus::RedWon => CECGameStatus::RedWon,
+            GameStatus::BlackWon => CECGameStatus::BlackWon,
+            GameStatus::Stalemate => CECGameStatus::Stalemate,
+            GameStatus::Draw => CECGameStatus::Draw,
+        }
+    }
+}
+
+/// 结果类型
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CECResultKind {
+    GameCreated = 0,
+    MoveSuccess = 1,
+    JoinSuccess = 2,
+    UndoSuccess = 3,
+    GameStatus = 4,
+    GameNotFound = 5,
+    MoveFailed = 6,
+    JoinFailed = 7,
+    UndoFailed = 8,
+    InvalidCommand = 9,
+    GameList = 10,
+    GameDeleted = 11,
+    GameHistory = 12,
+    GameBoard = 13,
+    LegalMoves = 14,
+    GameWatched = 15,
+    GameEvents = 16,
+    DrawOffered = 17,
+    DrawOfferFailed = 18,
+}
+
+impl From<&EngineResult> for CECResultKind {
+    fn from(result: &EngineResult) -> Self {
+        match result {
+            EngineResult::GameCreated(_) => CECResultKind::GameCreated,
+            EngineResult::MoveSuccess(..) => CECResultKind::MoveSuccess,
+            EngineResult::JoinSuccess(_) => CECResultKind::JoinSuccess,
+            EngineResult::UndoSuccess(_) => CECResultKind::UndoSuccess,
+            EngineResult::GameStatus(..) => CECResultKind::GameStatus,
+            EngineResult::GameNotFound(_) => CECResultKind::GameNotFound,
+            EngineResult::MoveFailed(..) => CECResultKind::MoveFailed,
+            EngineResult::JoinFailed(_) => CECResultKind::JoinFailed,
+            EngineResult::UndoFailed(_) => CECResultKind::UndoFailed,
+            EngineResult::InvalidCommand(_) => CECResultKind::InvalidCommand,
+            EngineResult::GameList(_) => CECResultKind::GameList,
+            EngineResult::GameDeleted(_) => CECResultKind::GameDeleted,
+            EngineResult::GameHistory(..) => CECResultKind::GameHistory,
+            EngineResult::GameBoard(..) => CECResultKind::GameBoard,
+            EngineResult::LegalMoves(..) => CECResultKind::LegalMoves,
+            EngineResult::GameWatched(..) => CECResultKind::GameWatched,
+            EngineResult::GameEvents(..) => CECResultKind::GameEvents,
+            EngineResult::DrawOffered(_) => CECResultKind::DrawOffered,
+            EngineResult::DrawOfferFailed(_) => CECResultKind::DrawOfferFailed,
+        }
+    }
+}
+
+/// 失败原因
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CECErrorReason {
+    None = 0,
+    GameNotFound = 1,
+    MoveRejected = 2,
+    JoinRejected = 3,
+    UndoRejected = 4,
+    InvalidCommand = 5,
+    DrawOfferRejected = 6,
+}
+
+impl From<&EngineResult> for CECErrorReason {
+    fn from(result: &EngineResult) -> Self {
+        match result {
+            EngineResult::GameNotFound(_) => CECErrorReason::GameNotFound,
+            EngineResult::MoveFailed(..) => CECErrorReason::MoveRejected,
+            EngineResult::JoinFailed(_) => CECErrorReason::JoinRejected,
+            EngineResult::UndoFailed(_) => CECErrorReason::UndoRejected,
+            EngineResult::InvalidCommand(_) => CECErrorReason::InvalidCommand,
+            EngineResult::DrawOfferFailed(_) => CECErrorReason::DrawOfferRejected,
+            _ => CECErrorReason::None,
+        }
+    }
+}
+
+/// 走法被拒绝的具体原因，0 表示没有
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CECMoveError {
+    None = 0,
+    GameOver = 1,
+    OutOfBounds = 2,
+    NoPiece = 3,
+    WrongColor = 4,
+    NotYourTurn = 5,
+    OwnPieceOnTarget = 6,
+    InvalidMovePattern = 7,
+    HorseLegBlocked = 8,
+    ElephantEyeBlocked = 9,
+    ElephantCrossRiver = 10,
+    OutOfPalace = 11,
+    CannonNeedsScreen = 12,
+    PathBlocked = 13,
+    PawnBackward = 14,
+    LeavesKingInCheck = 15,
+    KingsFacing = 16,
+}
+
+// 下标加一即为 CECMoveError 的值
+const MOVE_ERRORS: [MoveError; 16] = [
+    MoveError::GameOver,
+    MoveError::OutOfBounds,
+    MoveError::NoPiece,
+    MoveError::WrongColor,
+    MoveError::NotYourTurn,
+    MoveError::OwnPieceOnTarget,
+    MoveError::InvalidMovePattern,
+    MoveError::HorseLegBlocked,
+    MoveError::ElephantEyeBlocked,
+    MoveError::ElephantCrossRiver,
+    MoveError::OutOfPalace,
+    MoveError::CannonNeedsScreen,
+    MoveError::PathBlocked,
+    MoveError::PawnBackward,
+    MoveError::LeavesKingInCheck,
+    MoveError::KingsFacing,
+];
+
+impl From<MoveError> for CECMoveError {
+    fn from(error: MoveError) -> Self {
+        match error {
+            MoveError::GameOver => CECMoveError::GameOver,
+            MoveError::OutOfBounds => CECMoveError::OutOfBounds,
+            MoveError::NoPiece => CECMoveError::NoPiece,
+            MoveError::WrongColor => CECMoveError::WrongColor,
+            MoveError::NotYourTurn => CECMoveError::NotYourTurn,
+            MoveError::OwnPieceOnTarget => CECMoveError::OwnPieceOnTarget,
+            MoveError::InvalidMovePattern => CECMoveError::InvalidMovePattern,
+            MoveError::HorseLegBlocked => CECMoveError::HorseLegBlocked,
+            MoveError::ElephantEyeBlocked => CECMoveError::ElephantEyeBlocked,
+            MoveError::ElephantCrossRiver => CECMoveError::ElephantCrossRiver,
+            MoveError::OutOfPalace => CECMoveError::OutOfPalace,
+            MoveError::CannonNeedsScreen => CECMoveError::CannonNeedsScreen,
+            MoveError::PathBlocked => CECMoveError::PathBlocked,
+            MoveError::PawnBackward => CECMoveError::PawnBackward,
+            MoveError::LeavesKingInCheck => CECMoveError::LeavesKingInCheck,
+            MoveError::KingsFacing => CECMoveError::KingsFacing,
+        }
+    }
+}
+
+/// 事件类型
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CECEventKind {
+    GameCreated = 0,
+    PlayerJoined = 1,
+    MoveMade = 2,
+    Undo = 3,
+    GameFinished = 4,
+}
+
+/// 事件，只在回调期间有效
+#[repr(C)]
+pub struct CECEvent {
+    pub kind: CECEventKind,
+    pub game_id: u32,
+    // PlayerJoined
+    pub player_name: *const libc::c_char,
+    pub player_color: u8,
+    // MoveMade / Undo
+    pub from_x: u8,
+    pub from_y: u8,
+    pub to_x: u8,
+    pub to_y: u8,
+    pub moved_piece: CECPiece,
+    pub captured_piece: CECPiece,
+    pub check: bool,
+    // GameFinished
+    pub status: CECGameStatus,
+}
+
+impl CECEvent {
+    fn new(kind: CECEventKind, game_id: u32) -> Self {
+        CECEvent {
+            kind,
+            game_id,
+            player_name: std::ptr::null(),
+            player_color: 0,
+            from_x: 0,
+            from_y: 0,
+            to_x: 0,
+            to_y: 0,
+            moved_piece: Piece::default().into(),
+            captured_piece: Piece::default().into(),
+            check: false,
+            status: CECGameStatus::Playing,
+        }
+    }
+
+    fn with_move(mut self, record: &MoveRecord) -> Self {
+        (self.from_x, self.from_y, self.to_x, self.to_y) = record.mv.coordinates();
+        self.moved_piece = record.moved_piece.into();
+        self.captured_piece = record.captured_piece.into();
+        self
+    }
+}
+
+/// 事件回调
+/// 声明为 "C-unwind"：用 Rust 等语言编写的回调发生 panic 时，由外层的 guard 捕获并返回 Panic，而不是直接终止进程
+pub type CECEventCallback = extern "C-unwind" fn(event: *const CECEvent, user_data: *mut libc::c_void);
+
+// 把C回调适配为监听器
+struct CallbackListener {
+    callback: CECEventCallback,
+    user_data: *mut libc::c_void,
+}
+
+// user_data 的线程安全由调用者保证
+unsafe impl Send for CallbackListener {}
+unsafe impl Sync for CallbackListener {}
+
+impl CallbackListener {
+    fn emit(&self, event: CECEvent) {
+        (self.callback)(&event, self.user_data);
+    }
+}
+
+impl GameListener for CallbackListener {
+    fn on_game_created(&self, game_id: u32) {
+        self.emit(CECEvent::new(CECEventKind::GameCreated, game_id));
+    }
+
+    fn on_player_joined(&self, game_id: u32, player: &Player) {
+        let name = std::ffi::CString::new(player.name.as_str()).unwrap_or_default();
+        let mut event = CECEvent::new(CECEventKind::PlayerJoined, game_id);
+        event.player_name = name.as_ptr();
+        event.player_color = player.color.from_color_to_usize() as u8;
+        self.emit(event);
+    }
+
+    fn on_move_made(&self, game_id: u32, record: &MoveRecord, check: bool) {
+        let mut event = CECEvent::new(CECEventKind::MoveMade, game_id).with_move(record);
+        event.check = check;
+        self.emit(event);
+    }
+
+    fn on_undo(&self, game_id: u32, record: &MoveRecord) {
+        self.emit(CECEvent::new(CECEventKind::Undo, game_id).with_move(record));
+    }
+
+    fn on_game_finished(&self, game_id: u32, status: &GameStatus) {
+        let mut event = CECEvent::new(CECEventKind::GameFinished, game_id);
+        event.status = status.into();
+        self.emit(event);
+    }
+}
+
+
+/// 结果的输出格式，影响 cec_result_to_string
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CECOutputFormat {
+    Text = 0,
+    Json = 1,
+}
+
+impl From<OutputFormat> for CECOutputFormat {
+    fn from(format: OutputFormat) -> Self {
+        match format {
+            OutputFormat::Text => CECOutputFormat::Text,
+            OutputFormat::Json => CECOutputFormat::Json,
+        }
+    }
+}
+
+/// 结果说明文字的语言，影响 cec_result_to_string 和 cec_result_to_json
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CECLanguage {
+    English = 0,
+    Chinese = 1,
+}
+
+impl From<Language> for CECLanguage {
+    fn from(language: Language) -> Self {
+        match language {
+            Language::English => CECLanguage::English,
+            Language::Chinese => CECLanguage::Chinese,
+        }
+    }
+}
+
+/// 错误码
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CECStatus {
+    Ok = 0,
+    NullPointer = 1,
+    InvalidArgument = 2,
+    InvalidUtf8 = 3,
+    GameNotFound = 4,
+    NotAvailable = 5,
+    BufferTooSmall = 6,
+    Reentrant = 7,
+    Panic = 8,
+    EnginePoisoned = 9,
+}
+
+// 内部错误，包含错误码和错误信息
+struct FfiError {
+    status: CECStatus,
+    message: String,
+}
+
+impl FfiError {
+    fn new(status: CECStatus, message: impl Into<String>) -> Self {
+        FfiError {
+            status,
+            message: message.into(),
+        }
+    }
+
+    fn null(name: &str) -> Self {
+        FfiError::new(CECStatus::NullPointer, format!("{} is null", name))
+    }
+
+    fn game_not_found(game_id: u32) -> Self {
+        FfiError::new(CECStatus::GameNotFound, format!("game {} not found", game_id))
+    }
+}
+
+type FfiResult<T> = Result<T, FfiError>;
+
+thread_local! {
+    // 当前线程最近一次失败的错误信息
+    static LAST_ERROR: RefCell<Option<CString>> = const { RefCell::new(None) };
+
+    // 当前线程已经持有锁的引擎，用于检测回调中的重入
+    static LOCKED_ENGINES: RefCell<Vec<usize>> = const { RefCell::new(Vec::new()) };
+}
+
+fn set_last_error(message: String) {
+    let message = CString::new(message.replace('\0', " ")).unwrap_or_default();
+    LAST_ERROR.with(|last| *last.borrow_mut() = Some(message));
+}
+
+// 从 panic 负载中取出信息
+fn panic_message(payload: &(dyn std::any::Any + Send)) -> String {
+    if let Some(message) = payload.downcast_ref::<&str>() {
+        format!("panic: {}", message)
+    } else if let Some(message) = payload.downcast_ref::<String>() {
+        format!("panic: {}", message)
+    } else {
+        "panic: unknown".to_string()
+    }
+}
+
+// 捕获 panic 并把错误转换成错误码
+fn guard(f: impl FnOnce() -> FfiResult<()>) -> CECStatus {
+    match panic::catch_unwind(AssertUnwindSafe(f)) {
+        Ok(Ok(())) => CECStatus::Ok,
+        Ok(Err(error)) => {
+            set_last_error(error.message);
+            error.status
+        }
+        Err(payload) => {
+            set_last_error(panic_message(payload.as_ref()));
+            CECStatus::Panic
+        }
+    }
+}
+
+// 返回指针的函数无法通过错误码报告 panic，发生 panic 时返回静态的 fallback
+fn guard_str(fallback: &'static CStr, f: impl FnOnce() -> *const libc::c_char) -> *const libc::c_char {
+    panic::catch_unwind(AssertUnwindSafe(f)).unwrap_or(fallback.as_ptr())
+}
+
+// 写入输出参数
+fn write_out<T>(out: *mut T, name: &str, value: T) -> FfiResult<()> {
+    if out.is_null() {
+        return Err(FfiError::null(name));
+    }
+    unsafe { *out = value };
+    Ok(())
+}
+
+// 引擎句柄
+struct EngineHandle {
+    engine: Mutex<Engine>,
+}
+
+// 引擎锁，释放时解除重入标记
+struct EngineLock<'a> {
+    guard: MutexGuard<'a, Engine>,
+    address: usize,
+}
+
+impl Drop for EngineLock<'_> {
+    fn drop(&mut self) {
+        LOCKED_ENGINES.with(|locked| locked.borrow_mut().retain(|&address| address != self.address));
+    }
+}
+
+impl std::ops::Deref for EngineLock<'_> {
+    type Target = Engine;
+
+    fn deref(&self) -> &Engine {
+        &self.guard
+    }
+}
+
+impl std::ops::DerefMut for EngineLock<'_> {
+    fn deref_mut(&mut self) -> &mut Engine {
+        &mut self.guard
+    }
+}
+
+// 锁定引擎
+// 同一线程在回调中再次进入同一个引擎会返回 Reentrant，而不是死锁
+fn lock_engine<'a>(engine: *const CECEngine) -> FfiResult<EngineLock<'a>> {
+    if engine.is_null() {
+        return Err(FfiError::null("engine"));
+    }
+
+    let address = engine as usize;
+    if LOCKED_ENGINES.with(|locked| locked.borrow().contains(&address)) {
+        return Err(FfiError::new(CECStatus::Reentrant, "engine is already in use by this thread (called from a callback?)"));
+    }
+
+    let handle = unsafe { &*(engine as *const EngineHandle) };
+    // 持有锁时发生过 panic，引擎状态可能只修改了一半，不再继续使用
+    let guard = handle.engine.lock().map_err(|_| {
+        FfiError::new(CECStatus::EnginePoisoned, "engine panicked while in use; free it and create a new one")
+    })?;
+    LOCKED_ENGINES.with(|locked| locked.borrow_mut().push(address));
+
+    Ok(EngineLock { guard, address })
+}
+
+// 获取结果引用
+// 结果对象，记录执行时引擎的输出格式和语言
+struct ResultHandle {
+    result: EngineResult,
+    format: OutputFormat,
+    language: Language,
+}
+
+fn handle_ref<'a>(result: *const CECEngineResult) -> FfiResult<&'a ResultHandle> {
+    if result.is_null() {
+        return Err(FfiError::null("result"));
+    }
+    Ok(unsafe { &*(result as *const ResultHandle) })
+}
+
+fn result_ref<'a>(result: *const CECEngineResult) -> FfiResult<&'a EngineResult> {
+    handle_ref(result).map(|handle| &handle.result)
+}
+
+// 在引擎中查找游戏并执行只读操作
+fn with_game<T>(engine: *const CECEngine, game_id: u32, f: impl FnOnce(&Game) -> T) -> FfiResult<T> {
+    let engine = lock_engine(engine)?;
+    engine.get_game_manager()
+        .get_game(game_id)
+        .map(f)
+        .ok_or_else(|| FfiError::game_not_found(game_id))
+}
+
+// 与 with_game 相同，但可以在棋盘上试走，用于生成合法走法
+fn with_game_mut<T>(engine: *const CECEngine, game_id: u32, f: impl FnOnce(&mut Game) -> T) -> FfiResult<T> {
+    let mut engine = lock_engine(engine)?;
+    engine.get_game_manager_mut()
+        .get_game_mut(game_id)
+        .map(f)
+        .ok_or_else(|| FfiError::game_not_found(game_id))
+}
+
+// 检查坐标
+fn checked_position(x: u8, y: u8) -> FfiResult<Position> {
+    if x >= 9 || y >= 10 {
+        return Err(FfiError::new(CECStatus::InvalidArgument, format!("({},{}) is off the board", x, y)));
+    }
+    Ok(Position::new(x, y))
+}
+
+// 写入JSON：length 返回字节长度（不含结尾的'\0'），buffer 为NULL时只查询长度，缓冲区不足时不写入内容
+fn write_json(json: &str, buffer: *mut libc::c_char, buffer_size: libc::size_t, length: *mut libc::size_t) -> FfiResult<()> {
+    write_out(length, "length", json.len())?;
+
+    if buffer.is_null() {
+        return Ok(());
+    }
+    if json.len() >= buffer_size {
+        return Err(FfiError::new(CECStatus::BufferTooSmall, format!("{} bytes required", json.len() + 1)));
+    }
+    write_truncated(json, buffer, buffer_size)
+}
+
+// 把字符串写入缓冲区，必要时截断
+fn write_truncated(text: &str, buffer: *mut libc::c_char, buffer_size: libc::size_t) -> FfiResult<()> {
+    if buffer.is_null() {
+        return Err(FfiError::null("buffer"));
+    }
+    if buffer_size == 0 {
+        return Err(FfiError::new(CECStatus::BufferTooSmall, "buffer_size is 0"));
+    }
+
+    let len = std::cmp::min(text.len(), buffer_size - 1);
+    unsafe {
+        std::ptr::copy_nonoverlapping(text.as_ptr(), buffer as *mut u8, len);
+        *buffer.add(len) = 0;
+    }
+    Ok(())
+}
+
+/// 获取当前线程最近一次失败的错误信息，没有错误时返回NULL
+/// 返回的字符串在当前线程下一次失败之前有效
+#[unsafe(no_mangle)]
+pub extern "C" fn cec_last_error() -> *const libc::c_char {
+    guard_str(c"panic: the last error is unavailable", || {
+        LAST_ERROR.with(|last| {
+            last.borrow()
+                .as_ref()
+                .map_or(std::ptr::null(), |message| message.as_ptr())
+        })
+    })
+}
+
+/// 创建引擎对象，失败时返回NULL
+#[unsafe(no_mangle)]
+pub extern "C" fn cec_engine_new() -> *mut CECEngine {
+    let mut engine = std::ptr::null_mut();
+    guard(|| {
+        let handle = Box::new(EngineHandle {
+            engine: Mutex::new(Engine::new()),
+        });
+        engine = Box::into_raw(handle) as *mut CECEngine;
+        Ok(())
+    });
+    engine
+}
+
+/// 销毁引擎对象
+///
+/// # Safety
+/// - `engine` 必须为 NULL 或 `cec_engine_new` 返回的指针，并且只能释放一次，释放后不能再使用
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn cec_engine_free(engine: *mut CECEngine) {
+    guard(|| {
+        if !engine.is_null() {
+            unsafe {
+                let _ = Box::from_raw(engine as *mut EngineHandle);
+            }
+        }
+        Ok(())
+    });
+}
+
+/// 执行命令，结果需要用 cec_result_free 释放
+///
+/// # Safety
+/// - `engine` 必须为 NULL 或 `cec_engine_new` 返回且尚未释放的指针
+/// - `command` 必须为 NULL 或以 '\0' 结尾的字符串
+/// - `result` 必须为 NULL 或指向可写的 `CECEngineResult*`
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn cec_engine_execute(engine: *mut CECEngine, command: *const libc::c_char, result: *mut *mut CECEngineResult) -> CECStatus {
+    guard(|| {
+        if command.is_null() {
+            return Err(FfiError::null("command"));
+        }
+        if result.is_null() {
+            return Err(FfiError::null("result"));
+        }
+
+        let command = unsafe { CStr::from_ptr(command) }
+            .to_str()
+            .map_err(|_| FfiError::new(CECStatus::InvalidUtf8, "command is not valid UTF-8"))?;
+
+        let mut engine = lock_engine(engine)?;
+        let handle = ResultHandle {
+            result: engine.execute(command),
+            format: engine.output_format(),
+            language: engine.language(),
+        };
+        unsafe { *result = Box::into_raw(Box::new(handle)) as *mut CECEngineResult };
+        Ok(())
+    })
+}
+
+/// 设置引擎的输出格式，之后执行的命令的结果按此格式输出
+///
+/// # Safety
+/// - `engine` 必须为 NULL 或 `cec_engine_new` 返回且尚未释放的指针
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn cec_engine_set_output_format(engine: *mut CECEngine, format: libc::c_int) -> CECStatus {
+    guard(|| {
+        let format = match format {
+            0 => OutputFormat::Text,
+            1 => OutputFormat::Json,
+            other => return Err(FfiError::new(CECStatus::InvalidArgument, format!("unknown output format {}", other))),
+        };
+        lock_engine(engine)?.set_output_format(format);
+        Ok(())
+    })
+}
+
+/// 获取引擎的输出格式
+///
+/// # Safety
+/// - `engine` 必须为 NULL 或 `cec_engine_new` 返回且尚未释放的指针
+/// - `format` 必须为 NULL 或指向有效的可写内存
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn cec_engine_get_output_format(engine: *const CECEngine, format: *mut CECOutputFormat) -> CECStatus {
+    guard(|| {
+        let current = lock_engine(engine)?.output_format();
+        write_out(format, "format", current.into())
+    })
+}
+
+/// 设置结果说明文字的语言，之后执行的命令的结果使用此语言
+///
+/// # Safety
+/// - `engine` 必须为 NULL 或 `cec_engine_new` 返回且尚未释放的指针
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn cec_engine_set_language(engine: *mut CECEngine, language: libc::c_int) -> CECStatus {
+    guard(|| {
+        let language = match language {
+            0 => Language::English,
+            1 => Language::Chinese,
+            other => return Err(FfiError::new(CECStatus::InvalidArgument, format!("unknown language {}", other))),
+        };
+        lock_engine(engine)?.set_language(language);
+        Ok(())
+    })
+}
+
+/// 获取结果说明文字的语言
+///
+/// # Safety
+/// - `engine` 必须为 NULL 或 `cec_engine_new` 返回且尚未释放的指针
+/// - `language` 必须为 NULL 或指向有效的可写内存
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn cec_engine_get_language(engine: *const CECEngine, language: *mut CECLanguage) -> CECStatus {
+    guard(|| {
+        let current = lock_engine(engine)?.language();
+        write_out(language, "language", current.into())
+    })
+}
+
+/// 获取结果并按执行时引擎的输出格式转换成字符串，超出缓冲区的部分会被截断
+///
+/// # Safety
+/// - `result` 必须为 NULL 或 `cec_engine_execute` 返回且尚未释放的指针
+/// - `buffer` 必须为 NULL 或至少可写 `buffer_size` 个字节
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn cec_result_to_string(result: *const CECEngineResult, buffer: *mut libc::c_char, buffer_size: libc::size_t) -> CECStatus {
+    guard(|| {
+        let handle = handle_ref(result)?;
+        let text = handle.result.render_in(handle.format, handle.language);
+        write_truncated(&text, buffer, buffer_size)
+    })
+}
+
+/// 获取结果类型
+///
+/// # Safety
+/// - `result` 必须为 NULL 或 `cec_engine_execute` 返回且尚未释放的指针
+/// - `kind` 必须为 NULL 或指向有效的可写内存
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn cec_result_kind(result: *const CECEngineResult, kind: *mut CECResultKind) -> CECStatus {
+    guard(|| {
+        let result = result_ref(result)?;
+        write_out(kind, "kind", result.into())
+    })
+}
+
+/// 获取结果涉及的游戏ID，结果不包含游戏ID时返回 NotAvailable
+///
+/// # Safety
+/// - `result` 必须为 NULL 或 `cec_engine_execute` 返回且尚未释放的指针
+/// - `game_id` 必须为 NULL 或指向有效的可写内存
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn cec_result_game_id(result: *const CECEngineResult, game_id: *mut u32) -> CECStatus {
+    guard(|| {
+        let id = result_ref(result)?
+            .game_id()
+            .ok_or_else(|| FfiError::new(CECStatus::NotAvailable, "result has no game id"))?;
+        write_out(game_id, "game_id", id)
+    })
+}
+
+/// 获取对局状态，仅 GAME_STATUS 和 GAME_WATCHED 结果可用
+///
+/// # Safety
+/// - `result` 必须为 NULL 或 `cec_engine_execute` 返回且尚未释放的指针
+/// - `status` 必须为 NULL 或指向有效的可写内存
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn cec_result_status(result: *const CECEngineResult, status: *mut CECGameStatus) -> CECStatus {
+    guard(|| match result_ref(result)? {
+        EngineResult::GameStatus(_, view) | EngineResult::GameWatched(_, _, view) => write_out(status, "status", (&view.status).into()),
+        _ => Err(FfiError::new(CECStatus::NotAvailable, "result is not a game status")),
+    })
+}
+
+/// 获取失败原因，成功结果为 CEC_ERROR_NONE
+///
+/// # Safety
+/// - `result` 必须为 NULL 或 `cec_engine_execute` 返回且尚未释放的指针
+/// - `reason` 必须为 NULL 或指向有效的可写内存
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn cec_result_error_reason(result: *const CECEngineResult, reason: *mut CECErrorReason) -> CECStatus {
+    guard(|| {
+        let result = result_ref(result)?;
+        write_out(reason, "reason", result.into())
+    })
+}
+
+/// 获取走子吃掉的棋子，仅 MOVE_SUCCESS 结果可用，没有吃子时棋子类型为空
+///
+/// # Safety
+/// - `result` 必须为 NULL 或 `cec_engine_execute` 返回且尚未释放的指针
+/// - `piece` 必须为 NULL 或指向有效的可写内存
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn cec_result_captured_piece(result: *const CECEngineResult, piece: *mut CECPiece) -> CECStatus {
+    guard(|| match result_ref(result)? {
+        EngineResult::MoveSuccess(_, captured) => write_out(piece, "piece", (*captured).into()),
+        _ => Err(FfiError::new(CECStatus::NotAvailable, "result is not a successful move")),
+    })
+}
+
+/// 获取走法被拒绝的具体原因，仅 MOVE_FAILED 结果可用
+///
+/// # Safety
+/// - `result` 必须为 NULL 或 `cec_engine_execute` 返回且尚未释放的指针
+/// - `error` 必须为 NULL 或指向有效的可写内存
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn cec_result_move_error(result: *const CECEngineResult, error: *mut CECMoveError) -> CECStatus {
+    guard(|| match result_ref(result)? {
+        EngineResult::MoveFailed(_, move_error) => write_out(error, "error", (*move_error).into()),
+        _ => Err(FfiError::new(CECStatus::NotAvailable, "result is not a failed move")),
+    })
+}
+
+/// 获取语法错误在命令中的字节范围 [start, end)，仅 INVALID_COMMAND 结果可用
+/// 命令不完整时 start 与 end 相同，都等于命令的长度
+///
+/// # Safety
+/// - `result` 必须为 NULL 或 `cec_engine_execute` 返回且尚未释放的指针
+/// - `start`、`end` 必须为 NULL 或指向有效的可写内存
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn cec_result_parse_error_span(result: *const CECEngineResult, start: *mut libc::size_t, end: *mut libc::size_t) -> CECStatus {
+    guard(|| match result_ref(result)? {
+        EngineResult::InvalidCommand(parse_error) => {
+            write_out(start, "start", parse_error.span.start)?;
+            write_out(end, "end", parse_error.span.end)
+        }
+        _ => Err(FfiError::new(CECStatus::NotAvailable, "result is not an invalid command")),
+    })
+}
+
+/// 走法错误的说明，language 与 cec_engine_set_language 相同，0 为英文，1 为中文
+/// 字符串为静态分配，不需要释放；未知的值返回 NULL
+#[unsafe(no_mangle)]
+pub extern "C" fn cec_move_error_description(error: libc::c_int, language: libc::c_int) -> *const libc::c_char {
+    guard_str(c"panic: the description is unavailable", || {
+        static ENGLISH: OnceLock<Vec<CString>> = OnceLock::new();
+        static CHINESE: OnceLock<Vec<CString>> = OnceLock::new();
+
+        let descriptions = match language {
+            0 => ENGLISH.get_or_init(|| {
+                MOVE_ERRORS.iter()
+                    .map(|error| CString::new(error.to_string()).unwrap_or_default())
+                    .collect()
+            }),
+            1 => CHINESE.get_or_init(|| {
+                MOVE_ERRORS.iter()
+                    .map(|error| CString::new(error.description()).unwrap_or_default())
+                    .collect()
+            }),
+            _ => return std::ptr::null(),
+        };
+        usize::try_from(error)
+            .ok()
+            .and_then(|index| index.checked_sub(1))
+            .and_then(|index| descriptions.get(index))
+            .map_or(std::ptr::null(), |description| description.as_ptr())
+    })
+}
+
+/// 将结果转换为JSON
+/// length 返回JSON的字节长度（不含结尾的'\0'）；buffer 为NULL时只查询长度，
+/// 缓冲区不足时不写入内容并返回 BufferTooSmall
+///
+/// # Safety
+/// - `result` 必须为 NULL 或 `cec_engine_execute` 返回且尚未释放的指针
+/// - `buffer` 必须为 NULL 或至少可写 `buffer_size` 个字节
+/// - `length` 必须为 NULL 或指向有效的可写内存
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn cec_result_to_json(result: *const CECEngineResult, buffer: *mut libc::c_char, buffer_size: libc::size_t, length: *mut libc::size_t) -> CECStatus {
+    guard(|| {
+        let handle = handle_ref(result)?;
+        write_json(&handle.result.to_json_in(handle.language), buffer, buffer_size, length)
+    })
+}
+
+/// 释放结果对象
+///
+/// # Safety
+/// - `result` 必须为 NULL 或 `cec_engine_execute` 返回的指针，并且只能释放一次，释放后不能再使用
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn cec_result_free(result: *mut CECEngineResult) {
+    guard(|| {
+        if !result.is_null() {
+            unsafe {
+                let _ = Box::from_raw(result as *mut ResultHandle);
+            }
+        }
+        Ok(())
+    });
+}
+
+/// 注册事件回调
+/// 回调在执行命令的线程上、持有引擎锁时调用，回调中不能再调用同一个引擎（会返回 Reentrant）
+///
+/// # Safety
+/// - `engine` 必须为 NULL 或 `cec_engine_new` 返回且尚未释放的指针
+/// - `listener_id` 必须为 NULL 或指向有效的可写内存
+/// - `user_data` 会原样传给回调，在移除回调之前必须保持有效，并且可以在执行命令的线程上使用
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn cec_engine_add_listener(engine: *mut CECEngine, callback: Option<CECEventCallback>, user_data: *mut libc::c_void, listener_id: *mut u64) -> CECStatus {
+    guard(|| {
+        let callback = callback.ok_or_else(|| FfiError::null("callback"))?;
+        if listener_id.is_null() {
+            return Err(FfiError::null("listener_id"));
+        }
+
+        let id = lock_engine(engine)?.add_listener(Arc::new(CallbackListener { callback, user_data }));
+        write_out(listener_id, "listener_id", id)
+    })
+}
+
+/// 移除事件回调
+///
+/// # Safety
+/// - `engine` 必须为 NULL 或 `cec_engine_new` 返回且尚未释放的指针
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn cec_engine_remove_listener(engine: *mut CECEngine, listener_id: u64) -> CECStatus {
+    guard(|| {
+        if lock_engine(engine)?.remove_listener(listener_id) {
+            Ok(())
+        } else {
+            Err(FfiError::new(CECStatus::InvalidArgument, format!("listener {} not found", listener_id)))
+        }
+    })
+}
+
+/// 获取棋盘上指定位置的棋子
+///
+/// # Safety
+/// - `engine` 必须为 NULL 或 `cec_engine_new` 返回且尚未释放的指针
+/// - `piece` 必须为 NULL 或指向有效的可写内存
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn cec_game_get_piece(engine: *const CECEngine, game_id: u32, x: u8, y: u8, piece: *mut CECPiece) -> CECStatus {
+    guard(|| {
+        let pos = checked_position(x, y)?;
+        let found = with_game(engine, game_id, |game| game.board.get_piece(pos))?;
+        write_out(piece, "piece", found.into())
+    })
+}
+
+/// 获取整个棋盘，pieces 必须能容纳90个棋子，下标为 y * 9 + x
+///
+/// # Safety
+/// - `engine` 必须为 NULL 或 `cec_engine_new` 返回且尚未释放的指针
+/// - `pieces` 必须为 NULL 或至少可写 90 个 `CECPiece`
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn cec_game_get_board(engine: *const CECEngine, game_id: u32, pieces: *mut CECPiece) -> CECStatus {
+    guard(|| {
+        if pieces.is_null() {
+            return Err(FfiError::null("pieces"));
+        }
+
+        with_game(engine, game_id, |game| {
+            for y in 0..10 {
+                for x in 0..9 {
+                    let piece = game.board.get_piece(Position::new(x, y));
+                    unsafe { *pieces.add(y as usize * 9 + x as usize) = piece.into() };
+                }
+            }
+        })
+    })
+}
+
+/// 获取当前走棋方，1 为红，2 为黑
+///
+/// # Safety
+/// - `engine` 必须为 NULL 或 `cec_engine_new` 返回且尚未释放的指针
+/// - `color` 必须为 NULL 或指向有效的可写内存
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn cec_game_side_to_move(engine: *const CECEngine, game_id: u32, color: *mut u8) -> CECStatus {
+    guard(|| {
+        let turn = with_game(engine, game_id, |game| game.current_turn)?;
+        write_out(color, "color", turn.from_color_to_usize() as u8)
+    })
+}
+
+/// 获取当前走棋方是否被将军
+///
+/// # Safety
+/// - `engine` 必须为 NULL 或 `cec_engine_new` 返回且尚未释放的指针
+/// - `in_check` 必须为 NULL 或指向有效的可写内存
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn cec_game_is_in_check(engine: *const CECEngine, game_id: u32, in_check: *mut bool) -> CECStatus {
+    guard(|| {
+        let check = with_game(engine, game_id, |game| game.board.is_in_check(game.current_turn))?;
+        write_out(in_check, "in_check", check)
+    })
+}
+
+/// 获取已走的步数
+///
+/// # Safety
+/// - `engine` 必须为 NULL 或 `cec_engine_new` 返回且尚未释放的指针
+/// - `length` 必须为 NULL 或指向有效的可写内存
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn cec_game_history_length(engine: *const CECEngine, game_id: u32, length: *mut libc::size_t) -> CECStatus {
+    guard(|| {
+        let count = with_game(engine, game_id, |game| game.move_history.len())?;
+        write_out(length, "length", count)
+    })
+}
+
+/// 获取指定位置棋子的所有合法目标位置，对局已结束或不是当前走棋方的棋子时没有合法目标
+/// count 返回合法目标位置的总数，最多写入 capacity 个位置，容量不足时返回 BufferTooSmall
+///
+/// # Safety
+/// - `engine` 必须为 NULL 或 `cec_engine_new` 返回且尚未释放的指针
+/// - `targets` 必须为 NULL 或至少可写 `capacity` 个 `CECPosition`
+/// - `count` 必须为 NULL 或指向有效的可写内存
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn cec_game_legal_moves_from(engine: *const CECEngine, game_id: u32, x: u8, y: u8, targets: *mut CECPosition, capacity: libc::size_t, count: *mut libc::size_t) -> CECStatus {
+    guard(|| {
+        let from = checked_position(x, y)?;
+        let moves = with_game_mut(engine, game_id, |game| {
+            // 与类型化接口一致：只有对局进行中、并且是当前走棋方的棋子才有合法走法
+            if game.status != GameStatus::Playing || game.board.get_piece(from).color != game.current_turn {
+                return MoveList::new();
+            }
+            game.board.get_legal_moves(from)
+        })?;
+        write_out(count, "count", moves.len())?;
+
+        if targets.is_null() {
+            return if capacity == 0 { Ok(()) } else { Err(FfiError::null("targets")) };
+        }
+
+        for (i, mv) in moves.iter().take(capacity).enumerate() {
+            unsafe { *targets.add(i) = CECPosition { x: mv.to().x(), y: mv.to().y() } };
+        }
+
+        if moves.len() > capacity {
+            return Err(FfiError::new(CECStatus::BufferTooSmall, format!("{} positions required", moves.len())));
+        }
+        Ok(())
+    })
+}
+
+// cec_game_watch 的JSON，与 WATCH GAME 命令结果的 payload 相同
+#[derive(serde::Serialize)]
+struct WatchJson<'a> {
+    last_seq: u64,
+    game: &'a GameView,
+}
+
+/// 以观众身份开始观战：写入对局状态的JSON `{"last_seq":3,"game":{...}}`，与 `WATCH GAME` 命令的 payload 相同
+/// 之后用 cec_game_events_since 从 last_seq 开始获取新事件；JSON 的长度和缓冲区规则与 cec_result_to_json 相同
+///
+/// # Safety
+/// - `engine` 必须为 NULL 或 `cec_engine_new` 返回且尚未释放的指针
+/// - `buffer` 必须为 NULL 或至少可写 `buffer_size` 个字节
+/// - `length` 必须为 NULL 或指向有效的可写内存
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn cec_game_watch(engine: *const CECEngine, game_id: u32, buffer: *mut libc::c_char, buffer_size: libc::size_t, length: *mut libc::size_t) -> CECStatus {
+    guard(|| {
+        let json = with_game(engine, game_id, |game| {
+            let view = game.view();
+            serde_json::to_string(&WatchJson { last_seq: game.events.last_seq(), game: &view }).unwrap_or_default()
+        })?;
+        write_json(&json, buffer, buffer_size, length)
+    })
+}
+
+/// 获取序号大于 seq 的事件，写入JSON数组，每项与 `GET GAME <id> EVENTS SINCE` 结果中的事件相同
+/// 用于轮询和断线续传；JSON 的长度和缓冲区规则与 cec_result_to_json 相同
+///
+/// # Safety
+/// - `engine` 必须为 NULL 或 `cec_engine_new` 返回且尚未释放的指针
+/// - `buffer` 必须为 NULL 或至少可写 `buffer_size` 个字节
+/// - `length` 必须为 NULL 或指向有效的可写内存
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn cec_game_events_since(engine: *const CECEngine, game_id: u32, seq: u64, buffer: *mut libc::c_char, buffer_size: libc::size_t, length: *mut libc::size_t) -> CECStatus {
+    guard(|| {
+        let json = with_game(engine, game_id, |game| {
+            let events: Vec<EventView> = game.events.since(seq).iter().map(EventView::new).collect();
+            serde_json::to_string(&events).unwrap_or_default()
+        })?;
+        write_json(&json, buffer, buffer_size, length)
+    })
+}
+
+/// 以指定一方的身份提和，color 为 1（红）或 2（黑）；对局已结束时返回 NotAvailable
+///
+/// # Safety
+/// - `engine` 必须为 NULL 或 `cec_engine_new` 返回且尚未释放的指针
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn cec_game_offer_draw(engine: *mut CECEngine, game_id: u32, color: u8) -> CECStatus {
+    guard(|| {
+        let color = match color {
+            1 => Color::Red,
+            2 => Color::Black,
+            other => return Err(FfiError::new(CECStatus::InvalidArgument, format!("unknown color {}", other))),
+        };
+        let mut engine = lock_engine(engine)?;
+        let mut game = engine.game(GameId(game_id)).map_err(|_| FfiError::game_not_found(game_id))?;
+        game.offer_draw(color)
+            .map_err(|error| FfiError::new(CECStatus::NotAvailable, error.to_string()))
+    })
+}
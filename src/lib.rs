@@ -1,45 +1,350 @@
-use libc;
-
 pub mod core;
 pub mod parser;
+pub mod ffi;
+pub mod script;
+pub mod api;
 
 pub use core::ruler::{Ruler, GameStatus};
-pub use core::board::Board;
+pub use core::event::{GameEvent, EventRecord, GameSnapshot, Subscription, CatchUp};
+pub use core::listener::{GameListener, ListenerId};
+pub use core::ruler::{MoveRecord, Player};
+pub use core::move_error::{MakeMoveError, MoveError};
+pub use core::view::{EventView, GameSummary, GameView, MoveView};
+pub use core::notation::Notation;
+pub use core::moves::{Move, MoveList};
+pub use api::{EngineError, GameHandle, GameId};
+
+use std::sync::Arc;
+pub use core::board::{Board, UndoInfo};
+pub use core::diagram::{DiagramError, FenError, RenderStyle};
+pub use core::svg::SvgOptions;
+pub use core::record::{GameRecord, PgnError};
 pub use core::position::Position;
 pub use core::piece::{Color, Piece, PieceType};
 pub use parser::parser::Parser;
-pub use parser::ast::ASTNode;
+pub use parser::ast::{ASTNode, GameFilter};
+pub use parser::error::ParseError;
+pub use script::{ScriptReport, ScriptStep};
+
+/// JSON 结果格式的版本，格式发生不兼容的变化时递增
+pub const JSON_SCHEMA_VERSION: u32 = 1;
+
+/// 结果的输出格式
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum OutputFormat {
+    /// 每行以结果类型开头的文本，例如 `MOVE_SUCCESS 1`
+    #[default]
+    Text,
+    /// 带版本号的 JSON，见 [`EngineResult::to_json`]
+    Json,
+}
+
+/// 结果中说明文字的语言，结果类型和错误码不受影响
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Language {
+    #[default]
+    English,
+    /// 简体中文
+    Chinese,
+}
+
+// JSON 结果的结构，字段顺序即输出顺序
+#[derive(serde::Serialize)]
+struct JsonResponse<'a> {
+    version: u32,
+    ok: bool,
+    kind: &'static str,
+    game_id: Option<u32>,
+    error: Option<JsonError<'a>>,
+    payload: Option<JsonPayload<'a>>,
+}
+
+#[derive(serde::Serialize)]
+struct JsonError<'a> {
+    code: &'static str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    reason: Option<&'static str>,
+    message: String,
+    // 语法错误的字节范围 [start, end) 和期望的标记
+    #[serde(skip_serializing_if = "Option::is_none")]
+    span: Option<[usize; 2]>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    expected: Option<&'a [&'static str]>,
+}
+
+#[derive(serde::Serialize)]
+#[serde(untagged)]
+enum JsonPayload<'a> {
+    Move { captured: Option<&'a Piece> },
+    Game(&'a GameView),
+    List { games: &'a [GameSummary] },
+    History { notation: Notation, moves: &'a [String] },
+    Board { fen: &'a str, board: &'a str },
+    Moves { moves: &'a [MoveView] },
+    Watch { last_seq: u64, game: &'a GameView },
+    Events { events: &'a [EventView] },
+}
 
 #[derive(Debug, Clone)]
 pub enum EngineResult {
     // 成功
     GameCreated(u32),
-    MoveSuccess(u32),
+    MoveSuccess(u32, Piece),    // 被吃掉的棋子，没有吃子时为空
     JoinSuccess(u32),
     UndoSuccess(u32),
-    GameStatus(u32, GameStatus),
+    GameStatus(u32, Box<GameView>),    // 对局的完整状态
+    GameList(Vec<GameSummary>),    // 按游戏ID排序
+    GameDeleted(u32),
+    GameHistory(u32, Notation, Vec<String>),    // 每一步的记谱
+    GameBoard(u32, String, String),    // FEN 和棋盘图
+    LegalMoves(u32, Vec<MoveView>),
+    GameWatched(u32, u64, Box<GameView>),    // 最后一个事件的序号和对局状态
+    GameEvents(u32, Vec<EventView>),    // 按序号排列
+    DrawOffered(u32),
     
     // 错误
     GameNotFound(u32),
-    MoveFailed(u32),
+    MoveFailed(u32, MoveError),    // 走法被拒绝的原因
     JoinFailed(u32),
     UndoFailed(u32),
-    InvalidCommand,
+    DrawOfferFailed(u32),
+    InvalidCommand(ParseError),    // 出错的位置和期望的标记
+}
+
+impl EngineResult {
+    /// 结果涉及的游戏ID
+    pub fn game_id(&self) -> Option<u32> {
+        match self {
+            EngineResult::GameCreated(game_id)
+            | EngineResult::MoveSuccess(game_id, _)
+            | EngineResult::JoinSuccess(game_id)
+            | EngineResult::UndoSuccess(game_id)
+            | EngineResult::GameStatus(game_id, _)
+            | EngineResult::GameDeleted(game_id)
+            | EngineResult::GameHistory(game_id, ..)
+            | EngineResult::GameBoard(game_id, ..)
+            | EngineResult::LegalMoves(game_id, _)
+            | EngineResult::GameWatched(game_id, ..)
+            | EngineResult::GameEvents(game_id, _)
+            | EngineResult::DrawOffered(game_id)
+            | EngineResult::GameNotFound(game_id)
+            | EngineResult::MoveFailed(game_id, _)
+            | EngineResult::JoinFailed(game_id)
+            | EngineResult::UndoFailed(game_id)
+            | EngineResult::DrawOfferFailed(game_id) => Some(*game_id),
+            EngineResult::GameList(_) | EngineResult::InvalidCommand(_) => None,
+        }
+    }
+
+    /// 是否为成功结果
+    pub fn is_ok(&self) -> bool {
+        matches!(
+            self,
+            EngineResult::GameCreated(_)
+                | EngineResult::MoveSuccess(..)
+                | EngineResult::JoinSuccess(_)
+                | EngineResult::UndoSuccess(_)
+                | EngineResult::GameStatus(..)
+                | EngineResult::GameList(_)
+                | EngineResult::GameDeleted(_)
+                | EngineResult::GameHistory(..)
+                | EngineResult::GameBoard(..)
+                | EngineResult::LegalMoves(..)
+                | EngineResult::GameWatched(..)
+                | EngineResult::GameEvents(..)
+                | EngineResult::DrawOffered(_)
+        )
+    }
+
+    /// 结果类型，与文本格式第一个单词相同，例如 `MOVE_SUCCESS`
+    pub fn kind(&self) -> &'static str {
+        match self {
+            EngineResult::GameCreated(_) => "GAME_CREATED",
+            EngineResult::MoveSuccess(..) => "MOVE_SUCCESS",
+            EngineResult::JoinSuccess(_) => "JOIN_SUCCESS",
+            EngineResult::UndoSuccess(_) => "UNDO_SUCCESS",
+            EngineResult::GameStatus(..) => "GAME_STATUS",
+            EngineResult::GameList(_) => "GAME_LIST",
+            EngineResult::GameDeleted(_) => "GAME_DELETED",
+            EngineResult::GameHistory(..) => "GAME_HISTORY",
+            EngineResult::GameBoard(..) => "GAME_BOARD",
+            EngineResult::LegalMoves(..) => "LEGAL_MOVES",
+            EngineResult::GameWatched(..) => "GAME_WATCHED",
+            EngineResult::GameEvents(..) => "GAME_EVENTS",
+            EngineResult::DrawOffered(_) => "DRAW_OFFERED",
+            EngineResult::GameNotFound(_) => "GAME_NOT_FOUND",
+            EngineResult::MoveFailed(..) => "MOVE_FAILED",
+            EngineResult::JoinFailed(_) => "JOIN_FAILED",
+            EngineResult::UndoFailed(_) => "UNDO_FAILED",
+            EngineResult::DrawOfferFailed(_) => "DRAW_OFFER_FAILED",
+            EngineResult::InvalidCommand(_) => "INVALID_COMMAND",
+        }
+    }
+
+    /// 走法被拒绝的原因，仅 `MOVE_FAILED` 结果可用
+    pub fn move_error(&self) -> Option<MoveError> {
+        match self {
+            EngineResult::MoveFailed(_, error) => Some(*error),
+            _ => None,
+        }
+    }
+
+    /// 语法错误，仅 `INVALID_COMMAND` 结果可用
+    pub fn parse_error(&self) -> Option<&ParseError> {
+        match self {
+            EngineResult::InvalidCommand(error) => Some(error),
+            _ => None,
+        }
+    }
+
+    /// 错误说明，成功结果返回 None
+    pub fn error_message(&self) -> Option<String> {
+        let message = match self {
+            EngineResult::GameNotFound(game_id) => format!("game {} does not exist", game_id),
+            EngineResult::MoveFailed(game_id, error) => format!("move rejected in game {}: {}", game_id, error),
+            EngineResult::JoinFailed(game_id) => format!("cannot join game {}", game_id),
+            EngineResult::UndoFailed(game_id) => format!("nothing to undo in game {}", game_id),
+            EngineResult::DrawOfferFailed(game_id) => format!("cannot offer a draw in game {}", game_id),
+            EngineResult::InvalidCommand(error) => error.message(),
+            _ => return None,
+        };
+        Some(message)
+    }
+
+    /// 指定语言的错误说明，成功结果返回 None
+    pub fn error_message_in(&self, language: Language) -> Option<String> {
+        if language == Language::English {
+            return self.error_message();
+        }
+        let message = match self {
+            EngineResult::GameNotFound(game_id) => format!("游戏 {} 不存在", game_id),
+            EngineResult::MoveFailed(game_id, error) => format!("游戏 {} 的走法不合法：{}", game_id, error.description()),
+            EngineResult::JoinFailed(game_id) => format!("无法加入游戏 {}", game_id),
+            EngineResult::UndoFailed(game_id) => format!("游戏 {} 没有可以悔的棋", game_id),
+            EngineResult::DrawOfferFailed(game_id) => format!("游戏 {} 无法提和", game_id),
+            EngineResult::InvalidCommand(error) => error.description(),
+            _ => return None,
+        };
+        Some(message)
+    }
+
+    /// 序列化为JSON，格式为
+    /// `{"version":1,"ok":true,"kind":"MOVE_SUCCESS","game_id":1,"error":null,"payload":{...}}`
+    ///
+    /// 失败时 `error` 为 `{"code":"MOVE_FAILED","message":"..."}`，`code` 与 `kind` 相同；
+    /// `MOVE_FAILED` 还带有 `reason`，为 [`MoveError::code`]，例如 `"HORSE_LEG_BLOCKED"`；
+    /// `INVALID_COMMAND` 还带有 `span`（出错位置的字节范围 `[start, end)`）和 `expected`；
+    /// `payload` 为各类结果的附加数据，没有附加数据时为 null
+    pub fn to_json(&self) -> String {
+        self.to_json_in(Language::English)
+    }
+
+    /// 序列化为JSON，`error.message` 使用指定的语言
+    pub fn to_json_in(&self, language: Language) -> String {
+        let payload = match self {
+            EngineResult::MoveSuccess(_, captured) => Some(JsonPayload::Move {
+                captured: if captured.is_empty() { None } else { Some(captured) },
+            }),
+            EngineResult::GameStatus(_, view) => Some(JsonPayload::Game(view)),
+            EngineResult::GameList(games) => Some(JsonPayload::List { games }),
+            EngineResult::GameHistory(_, notation, moves) => Some(JsonPayload::History { notation: *notation, moves }),
+            EngineResult::GameBoard(_, fen, board) => Some(JsonPayload::Board { fen, board }),
+            EngineResult::LegalMoves(_, moves) => Some(JsonPayload::Moves { moves }),
+            EngineResult::GameWatched(_, last_seq, view) => Some(JsonPayload::Watch { last_seq: *last_seq, game: view }),
+            EngineResult::GameEvents(_, events) => Some(JsonPayload::Events { events }),
+            _ => None,
+        };
+        let response = JsonResponse {
+            version: JSON_SCHEMA_VERSION,
+            ok: self.is_ok(),
+            kind: self.kind(),
+            game_id: self.game_id(),
+            error: self.error_message_in(language).map(|message| JsonError {
+                code: self.kind(),
+                reason: self.move_error().map(|error| error.code()),
+                message,
+                span: self.parse_error().map(|error| [error.span.start, error.span.end]),
+                expected: self.parse_error().map(|error| &error.expected[..]),
+            }),
+            payload,
+        };
+        serde_json::to_string(&response).unwrap_or_default()
+    }
+
+    /// 按指定格式输出
+    pub fn render(&self, format: OutputFormat) -> String {
+        self.render_in(format, Language::English)
+    }
+
+    /// 按指定格式和语言输出
+    ///
+    /// 中文文本在失败结果的第一行末尾附加中文说明，例如 `MOVE_FAILED 1 HORSE_LEG_BLOCKED 蹩马腿`，
+    /// 成功结果与英文相同
+    pub fn render_in(&self, format: OutputFormat, language: Language) -> String {
+        match (format, language) {
+            (OutputFormat::Json, _) => self.to_json_in(language),
+            (OutputFormat::Text, Language::English) => self.to_string(),
+            (OutputFormat::Text, Language::Chinese) => match self {
+                EngineResult::InvalidCommand(error) => {
+                    format!("INVALID_COMMAND {}\n{}", error.description(), error.annotated())
+                }
+                EngineResult::MoveFailed(_, error) => format!("{} {}", self, error.description()),
+                _ => match self.error_message_in(language) {
+                    Some(message) => format!("{} {}", self, message),
+                    None => self.to_string(),
+                },
+            },
+        }
+    }
 }
 
 impl std::fmt::Display for EngineResult {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
             EngineResult::GameCreated(game_id) => write!(f, "GAME_CREATED {}", game_id),
-            EngineResult::MoveSuccess(game_id) => write!(f, "MOVE_SUCCESS {}", game_id),
+            EngineResult::MoveSuccess(game_id, _) => write!(f, "MOVE_SUCCESS {}", game_id),
             EngineResult::JoinSuccess(game_id) => write!(f, "JOIN_SUCCESS {}", game_id),
             EngineResult::UndoSuccess(game_id) => write!(f, "UNDO_SUCCESS {}", game_id),
-            EngineResult::GameStatus(game_id, status) => write!(f, "GAME_STATUS {} {:?}", game_id, status),
+            EngineResult::GameStatus(_, view) => write!(f, "{}", view),
+            EngineResult::GameList(games) => {
+                write!(f, "GAME_LIST {}", games.len())?;
+                for game in games {
+                    write!(f, "\n{}", game)?;
+                }
+                Ok(())
+            }
+            EngineResult::GameDeleted(game_id) => write!(f, "GAME_DELETED {}", game_id),
+            EngineResult::GameHistory(game_id, notation, moves) => {
+                write!(f, "GAME_HISTORY {} {} {}", game_id, notation.name(), moves.len())?;
+                // 每行一个回合，例如 1. h2e2 h9g7
+                for (round, pair) in moves.chunks(2).enumerate() {
+                    write!(f, "\n{}. {}", round + 1, pair.join(" "))?;
+                }
+                Ok(())
+            }
+            EngineResult::GameBoard(game_id, fen, board) => write!(f, "GAME_BOARD {} {}\n{}", game_id, fen, board.trim_end()),
+            EngineResult::LegalMoves(game_id, moves) => {
+                write!(f, "LEGAL_MOVES {} {}", game_id, moves.len())?;
+                for legal_move in moves {
+                    write!(f, "\n{}", legal_move)?;
+                }
+                Ok(())
+            }
+            EngineResult::GameWatched(game_id, last_seq, view) => write!(f, "GAME_WATCHED {} SEQ {}\n{}", game_id, last_seq, view),
+            EngineResult::GameEvents(game_id, events) => {
+                write!(f, "GAME_EVENTS {} {}", game_id, events.len())?;
+                for event in events {
+                    write!(f, "\n{}", event)?;
+                }
+                Ok(())
+            }
+            EngineResult::DrawOffered(game_id) => write!(f, "DRAW_OFFERED {}", game_id),
             EngineResult::GameNotFound(game_id) => write!(f, "GAME_NOT_FOUND {}", game_id),
-            EngineResult::MoveFailed(game_id) => write!(f, "MOVE_FAILED {}", game_id),
+            EngineResult::MoveFailed(game_id, error) => write!(f, "MOVE_FAILED {} {}", game_id, error.code()),
             EngineResult::JoinFailed(game_id) => write!(f, "JOIN_FAILED {}", game_id),
             EngineResult::UndoFailed(game_id) => write!(f, "UNDO_FAILED {}", game_id),
-            EngineResult::InvalidCommand => write!(f, "INVALID_COMMAND"),
+            EngineResult::DrawOfferFailed(game_id) => write!(f, "DRAW_OFFER_FAILED {}", game_id),
+            EngineResult::InvalidCommand(error) => write!(f, "INVALID_COMMAND {}\n{}", error, error.annotated()),
         }
     }
 }
@@ -47,64 +352,175 @@ impl std::fmt::Display for EngineResult {
 #[derive(Debug, Clone)]
 pub struct Engine {
     game_manager: Ruler,
+    output_format: OutputFormat,
+    language: Language,
 }
 
 impl Engine {
     pub fn new() -> Self {
         Engine {
             game_manager: Ruler::new(),
+            output_format: OutputFormat::Text,
+            language: Language::English,
         }
     }
     
-    /// 解析命令位AST节点
-    pub fn parse(&self, command: &str) -> ASTNode {
+    /// 解析命令为AST节点
+    pub fn parse(&self, command: &str) -> Result<ASTNode, ParseError> {
         let mut parser = Parser::new(command);
         parser.parse()
     }
     
     /// 执行语句
     pub fn execute(&mut self, command: &str) -> EngineResult {
-        let ast_node = self.parse(command);
-        self.execute_ast(ast_node)
+        match self.parse(command) {
+            Ok(ast_node) => self.execute_ast(ast_node),
+            Err(error) => EngineResult::InvalidCommand(error),
+        }
     }
     
-    /// 执行AST节点指令并返回结果
+    /// 逐行执行命令脚本并返回执行报告
+    ///
+    /// 除命令外，脚本还支持：
+    /// - `# 注释`，空行会被跳过
+    /// - `EXPECT <结果>`：检查上一条命令的结果，写出结果第一行的前几项即可，例如 `EXPECT MOVE_FAILED 1 HORSE_LEG_BLOCKED`
+    /// - `EXPECT BOARD <fen>`：检查上一条命令所在游戏的局面，比较棋子位置和给出的走子方
+    /// - `LET g = CREATE GAME`：保存命令结果中的游戏ID，之后用 `$g` 引用
+    ///
+    /// 失败的命令如果紧接着有 EXPECT 检查，由 EXPECT 决定是否通过
+    pub fn run_script(&mut self, script: &str) -> ScriptReport {
+        script::run(self, script)
+    }
+
+    /// 把游戏保存为可以用 [`Engine::run_script`] 重放的命令脚本，游戏不存在时返回 None
+    pub fn save_script(&self, game_id: u32) -> Option<String> {
+        self.game_manager.get_game(game_id).map(script::save)
+    }
+
+    /// 执行语句并按引擎的输出格式和语言返回结果
+    pub fn execute_to_string(&mut self, command: &str) -> String {
+        let format = self.output_format;
+        self.execute_with_format(command, format)
+    }
+
+    /// 执行语句并按指定格式返回结果，不影响引擎的输出格式，说明文字使用引擎的语言
+    pub fn execute_with_format(&mut self, command: &str, format: OutputFormat) -> String {
+        let language = self.language;
+        self.execute(command).render_in(format, language)
+    }
+
+    /// 引擎的输出格式
+    pub fn output_format(&self) -> OutputFormat {
+        self.output_format
+    }
+
+    /// 设置引擎的输出格式
+    pub fn set_output_format(&mut self, format: OutputFormat) {
+        self.output_format = format;
+    }
+
+    /// 结果说明文字的语言
+    pub fn language(&self) -> Language {
+        self.language
+    }
+
+    /// 设置结果说明文字的语言
+    pub fn set_language(&mut self, language: Language) {
+        self.language = language;
+    }
+
+    /// 执行AST节点指令并返回结果，通过类型化接口完成操作
     pub fn execute_ast(&mut self, ast_node: ASTNode) -> EngineResult {
         match ast_node {
-            ASTNode::CreateGame => {
-                let game_id = self.game_manager.create_game();
-                EngineResult::GameCreated(game_id)
-            }
+            ASTNode::CreateGame => EngineResult::GameCreated(self.create_game().0),
             ASTNode::JoinGame { game_id, name, color } => {
-                if self.game_manager.add_player_to_game(game_id, name, color) {
-                    EngineResult::JoinSuccess(game_id)
-                } else {
-                    EngineResult::JoinFailed(game_id)
+                match self.game(GameId(game_id)).and_then(|mut game| game.join(&name, color)) {
+                    Ok(()) => EngineResult::JoinSuccess(game_id),
+                    Err(_) => EngineResult::JoinFailed(game_id),
                 }
             }
-            ASTNode::Move { game_id, color: _, from_x, from_y, to_x, to_y } => {
-                if self.game_manager.make_move(game_id, from_x, from_y, to_x, to_y) {
-                    EngineResult::MoveSuccess(game_id)
-                } else {
-                    EngineResult::MoveFailed(game_id)
+            ASTNode::Move { game_id, color, mv } => {
+                let result = self.game(GameId(game_id)).and_then(|mut game| game.play_as(color, mv));
+                match result {
+                    Ok(captured) => EngineResult::MoveSuccess(game_id, captured.unwrap_or_default()),
+                    Err(EngineError::IllegalMove(error)) => EngineResult::MoveFailed(game_id, error),
+                    Err(_) => EngineResult::GameNotFound(game_id),
                 }
             }
             ASTNode::Undo { game_id } => {
-                if self.game_manager.undo_move(game_id) {
-                    EngineResult::UndoSuccess(game_id)
-                } else {
-                    EngineResult::UndoFailed(game_id)
+                match self.game(GameId(game_id)).and_then(|mut game| game.undo()) {
+                    Ok(()) => EngineResult::UndoSuccess(game_id),
+                    Err(EngineError::GameNotFound(_)) => EngineResult::GameNotFound(game_id),
+                    Err(_) => EngineResult::UndoFailed(game_id),
                 }
             }
             ASTNode::GetGame { game_id } => {
-                if let Some(game) = self.game_manager.get_game(game_id) {
-                    EngineResult::GameStatus(game_id, game.status.clone())
-                } else {
-                    EngineResult::GameNotFound(game_id)
+                match self.game(GameId(game_id)) {
+                    Ok(game) => EngineResult::GameStatus(game_id, Box::new(game.state())),
+                    Err(_) => EngineResult::GameNotFound(game_id),
+                }
+            }
+            ASTNode::ListGames { filter } => EngineResult::GameList(self.list_games(filter)),
+            ASTNode::DeleteGame { game_id } => {
+                match self.delete_game(GameId(game_id)) {
+                    Ok(()) => EngineResult::GameDeleted(game_id),
+                    Err(_) => EngineResult::GameNotFound(game_id),
+                }
+            }
+            ASTNode::GetHistory { game_id, notation } => {
+                match self.game(GameId(game_id)) {
+                    Ok(game) => EngineResult::GameHistory(game_id, notation, game.history(notation)),
+                    Err(_) => EngineResult::GameNotFound(game_id),
+                }
+            }
+            ASTNode::GetBoard { game_id } => {
+                match self.game(GameId(game_id)) {
+                    Ok(game) => EngineResult::GameBoard(
+                        game_id,
+                        game.fen(),
+                        game.board().render(RenderStyle::Ascii, Color::Red),
+                    ),
+                    Err(_) => EngineResult::GameNotFound(game_id),
+                }
+            }
+            ASTNode::GetMoves { game_id, from } => {
+                match self.game(GameId(game_id)) {
+                    Ok(mut game) => {
+                        let moves = match from {
+                            None => game.legal_moves(),
+                            Some(from) => game.legal_moves_from(from),
+                        };
+                        let views = moves.into_iter()
+                            .map(|mv| MoveView::new(game.board(), mv))
+                            .collect();
+                        EngineResult::LegalMoves(game_id, views)
+                    }
+                    Err(_) => EngineResult::GameNotFound(game_id),
                 }
             }
-            ASTNode::Invalid => {
-                EngineResult::InvalidCommand
+            // 命令无法建立推送通道：新观众得到对局状态和最后一个事件序号，之后用 GET GAME <id> EVENTS SINCE 轮询；
+            // 断线续传与轮询相同，只返回错过的事件
+            ASTNode::WatchGame { game_id, from } => {
+                match self.game(GameId(game_id)) {
+                    Ok(game) => match from {
+                        None => EngineResult::GameWatched(game_id, game.last_event_seq(), Box::new(game.state())),
+                        Some(seq) => EngineResult::GameEvents(game_id, game.events_since(seq).iter().map(EventView::new).collect()),
+                    },
+                    Err(_) => EngineResult::GameNotFound(game_id),
+                }
+            }
+            ASTNode::GetEvents { game_id, since } => {
+                match self.game(GameId(game_id)) {
+                    Ok(game) => EngineResult::GameEvents(game_id, game.events_since(since).iter().map(EventView::new).collect()),
+                    Err(_) => EngineResult::GameNotFound(game_id),
+                }
+            }
+            ASTNode::OfferDraw { game_id, color } => {
+                match self.game(GameId(game_id)).and_then(|mut game| game.offer_draw(color)) {
+                    Ok(()) => EngineResult::DrawOffered(game_id),
+                    Err(EngineError::GameNotFound(_)) => EngineResult::GameNotFound(game_id),
+                    Err(_) => EngineResult::DrawOfferFailed(game_id),
+                }
             }
         }
     }
@@ -118,6 +534,22 @@ impl Engine {
     pub fn get_game_manager_mut(&mut self) -> &mut Ruler {
         &mut self.game_manager
     }
+
+    /// 注册对局监听器
+    pub fn add_listener(&mut self, listener: Arc<dyn GameListener>) -> ListenerId {
+        self.game_manager.add_listener(listener)
+    }
+
+    /// 移除对局监听器
+    pub fn remove_listener(&mut self, id: ListenerId) -> bool {
+        self.game_manager.remove_listener(id)
+    }
+}
+
+impl Default for Engine {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 /// 创造引擎实例
@@ -136,79 +568,7 @@ pub fn create_board() -> Board {
 }
 
 /// 解析命令
-pub fn parse_command(command: &str) -> ASTNode {
+pub fn parse_command(command: &str) -> Result<ASTNode, ParseError> {
     let mut parser = Parser::new(command);
     parser.parse()
 }
-
-// C API
-
-#[repr(C)]
-pub struct CECEngine {
-    _private: [u8; 0],
-}
-
-/// 返回结果
-#[repr(C)]
-pub struct CECEngineResult {
-    _private: [u8; 0],
-}
-
-/// 创建引擎对象
-#[unsafe(no_mangle)]
-pub extern "C" fn cec_engine_new() -> *mut CECEngine {
-    let engine = Box::new(Engine::new());
-    Box::into_raw(engine) as *mut CECEngine
-}
-
-/// 销毁引擎对象
-#[unsafe(no_mangle)]
-pub extern "C" fn cec_engine_free(engine: *mut CECEngine) {
-    if !engine.is_null() {
-        unsafe {
-            let _ = Box::from_raw(engine as *mut Engine);
-        }
-    }
-}
-
-/// 执行命令
-#[unsafe(no_mangle)]
-pub extern "C" fn cec_engine_execute(engine: *mut CECEngine, command: *const libc::c_char) -> *mut CECEngineResult {
-    if engine.is_null() || command.is_null() {
-        return std::ptr::null_mut();
-    }
-    
-    unsafe {
-        let engine = &mut *(engine as *mut Engine);
-        let command = std::ffi::CStr::from_ptr(command).to_str().unwrap_or("");
-        let result = engine.execute(command);
-        let result_box = Box::new(result);
-        Box::into_raw(result_box) as *mut CECEngineResult
-    }
-}
-
-/// 获取结果并转换成字符串
-#[unsafe(no_mangle)]
-pub extern "C" fn cec_result_to_string(result: *mut CECEngineResult, buffer: *mut libc::c_char, buffer_size: libc::size_t) {
-    if result.is_null() || buffer.is_null() || buffer_size == 0 {
-        return;
-    }
-    
-    unsafe {
-        let result = &*(result as *mut EngineResult);
-        let result_str = format!("{}", result);
-        let result_c_str = std::ffi::CString::new(result_str).unwrap_or(std::ffi::CString::new("").unwrap());
-        libc::strncpy(buffer, result_c_str.as_ptr(), buffer_size - 1);
-        *buffer.offset((buffer_size - 1) as isize) = 0;
-    }
-}
-
-/// 释放引擎对象
-#[unsafe(no_mangle)]
-pub extern "C" fn cec_result_free(result: *mut CECEngineResult) {
-    if !result.is_null() {
-        unsafe {
-            let _ = Box::from_raw(result as *mut EngineResult);
-        }
-    }
-}
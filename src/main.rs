@@ -1,6 +1,6 @@
-pub mod core;
-pub mod parser;
+mod cli;
 
 fn main() {
-    println!("Hello, world!");
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    std::process::exit(cli::run(&args));
 }
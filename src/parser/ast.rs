@@ -1,4 +1,8 @@
+use crate::core::moves::Move;
+use crate::core::notation::Notation;
 use crate::core::piece::Color;
+use crate::core::position::Position;
+use crate::core::ruler::GameStatus;
 
 // 抽象语法树节点
 #[derive(Debug, Clone)]
@@ -17,10 +21,7 @@ pub enum ASTNode {
     Move {
         game_id: u32,
         color: Color,
-        from_x: u8,
-        from_y: u8,
-        to_x: u8,
-        to_y: u8,
+        mv: Move,
     },
     
     // 悔棋
@@ -32,7 +33,77 @@ pub enum ASTNode {
     GetGame {
         game_id: u32,
     },
-    
-    // 无效命令
-    Invalid,
+
+    // 列出游戏
+    ListGames {
+        filter: GameFilter,
+    },
+
+    // 删除游戏
+    DeleteGame {
+        game_id: u32,
+    },
+
+    // 获取棋谱
+    GetHistory {
+        game_id: u32,
+        notation: Notation,
+    },
+
+    // 获取棋盘
+    GetBoard {
+        game_id: u32,
+    },
+
+    // 获取合法走法，可以只列出指定位置棋子的走法
+    GetMoves {
+        game_id: u32,
+        from: Option<Position>,
+    },
+
+    // 观战，from 为已收到的最后一个事件序号，用于断线续传
+    WatchGame {
+        game_id: u32,
+        from: Option<u64>,
+    },
+
+    // 获取序号大于 since 的事件
+    GetEvents {
+        game_id: u32,
+        since: u64,
+    },
+
+    // 提和
+    OfferDraw {
+        game_id: u32,
+        color: Color,
+    },
+}
+
+// LIST GAMES 的状态过滤条件
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum GameFilter {
+    #[default]
+    All,
+    Playing,
+    // 已经结束，包括红胜、黑胜与和棋
+    Finished,
+    RedWon,
+    BlackWon,
+    Stalemate,
+    Draw,
+}
+
+impl GameFilter {
+    pub fn matches(&self, status: &GameStatus) -> bool {
+        match self {
+            GameFilter::All => true,
+            GameFilter::Playing => *status == GameStatus::Playing,
+            GameFilter::Finished => *status != GameStatus::Playing,
+            GameFilter::RedWon => *status == GameStatus::RedWon,
+            GameFilter::BlackWon => *status == GameStatus::BlackWon,
+            GameFilter::Stalemate => *status == GameStatus::Stalemate,
+            GameFilter::Draw => *status == GameStatus::Draw,
+        }
+    }
 }
\ No newline at end of file
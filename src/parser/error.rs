@@ -0,0 +1,117 @@
+use std::ops::Range;
+
+use crate::parser::lexer::Token;
+
+// 语法错误：出错的位置、遇到的标记和期望的标记
+#[derive(Debug, Clone, PartialEq)]
+pub struct ParseError {
+    // 原始命令
+    pub source: String,
+    // 出错标记在原始命令中的字节范围，到达结尾时为空范围
+    pub span: Range<usize>,
+    pub found: Token,
+    // 此处可以接受的标记，例如 "TO"、"game id"
+    pub expected: Box<[&'static str]>,
+    // 出错标记之前的一个标记，位于命令开头时为空
+    pub after: Option<Token>,
+}
+
+impl ParseError {
+    // 单行说明，例如 expected TO after (0,0), found (1,1)
+    // 词法错误直接说明原因，例如 number 300 is out of range
+    pub fn message(&self) -> String {
+        if let Token::Error(error) = &self.found {
+            return error.to_string();
+        }
+
+        let expected = match self.expected.split_last() {
+            Some((last, [])) => last.to_string(),
+            Some((last, rest)) => format!("{} or {}", rest.join(", "), last),
+            None => "nothing".to_string(),
+        };
+        match &self.after {
+            Some(after) => format!("expected {} after {}, found {}", expected, after, self.found),
+            None => format!("expected {}, found {}", expected, self.found),
+        }
+    }
+
+    // 中文说明，例如 (0,0) 之后应为 TO，实际为 (1,1)
+    pub fn description(&self) -> String {
+        let found = match &self.found {
+            Token::Error(error) => return error.description(),
+            Token::Eof => "命令结尾".to_string(),
+            token => token.to_string(),
+        };
+
+        let names: Vec<&str> = self.expected.iter().map(|name| expected_name(name)).collect();
+        let expected = match names.split_last() {
+            Some((last, [])) => last.to_string(),
+            Some((last, rest)) => format!("{} 或 {}", rest.join("、"), last),
+            None => "无".to_string(),
+        };
+        match &self.after {
+            Some(after) => format!("{} 之后应为 {}，实际为 {}", after, expected, found),
+            None => format!("应为 {}，实际为 {}", expected, found),
+        }
+    }
+
+    // 原始命令和指向出错位置的 ^ 标记，共两行
+    pub fn annotated(&self) -> String {
+        // 去掉结尾的换行，到达结尾的错误指向最后一个字符之后
+        let line = self.source.trim_end();
+        let start = self.span.start.min(line.len());
+        let end = self.span.end.clamp(start, line.len());
+
+        // 制表符保持原样，其余字符按显示宽度用空格对齐，中文占两列
+        let mut padding = String::new();
+        for c in line[..start].chars() {
+            if c == '\t' {
+                padding.push('\t');
+            } else {
+                padding.push_str(&" ".repeat(display_width(c)));
+            }
+        }
+        let width = line[start..end].chars().map(display_width).sum::<usize>().max(1);
+
+        format!("{}\n{}{}", line, padding, "^".repeat(width))
+    }
+}
+
+impl std::fmt::Display for ParseError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.message())
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+// 期望内容的中文名称，关键字保持原样
+fn expected_name(name: &'static str) -> &'static str {
+    match name {
+        "game id" => "游戏ID",
+        "player name" => "玩家名称",
+        "coordinate (x,y)" => "坐标 (x,y)",
+        "coordinate on the board" => "棋盘上的坐标",
+        "end of input" => "命令结尾",
+        other => other,
+    }
+}
+
+// 字符在等宽终端中占的列数，全角字符占两列
+fn display_width(c: char) -> usize {
+    match c as u32 {
+        0x1100..=0x115F
+        | 0x2E80..=0x303E
+        | 0x3041..=0x33FF
+        | 0x3400..=0x4DBF
+        | 0x4E00..=0x9FFF
+        | 0xA000..=0xA4CF
+        | 0xAC00..=0xD7A3
+        | 0xF900..=0xFAFF
+        | 0xFE30..=0xFE4F
+        | 0xFF00..=0xFF60
+        | 0xFFE0..=0xFFE6
+        | 0x20000..=0x3FFFD => 2,
+        _ => 1,
+    }
+}
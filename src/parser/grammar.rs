@@ -0,0 +1,415 @@
+use crate::core::notation::Notation;
+use crate::core::moves::Move;
+use crate::core::piece::Color;
+use crate::core::position::Position;
+use crate::parser::ast::{ASTNode, GameFilter};
+use crate::parser::lexer::Keywords;
+
+// 命令语法中的一项
+#[derive(Debug, Clone, Copy)]
+pub enum Pattern {
+    Keyword(Keywords),
+    // 任意一个关键字，例如 GAMES 或 GAME
+    AnyKeyword(&'static [Keywords]),
+    // 数字，附带错误提示中的名称，例如 "game id"
+    Number(&'static str),
+    // RED 或 BLACK
+    Color,
+    // 棋盘上的坐标 (x,y)
+    Coordinate,
+    // 标识符或双引号字符串，附带错误提示中的名称
+    Name(&'static str),
+    // 候选词之一，不区分大小写；aliases 为 (别名, 候选词)
+    Word {
+        names: &'static [&'static str],
+        aliases: &'static [(&'static str, &'static str)],
+    },
+    // 可选子句，第一项出现时其余各项必须完整出现
+    Optional(&'static [Pattern]),
+}
+
+impl Pattern {
+    // 该项产生的取值个数
+    pub(crate) fn value_count(&self) -> usize {
+        match self {
+            Pattern::Keyword(_) | Pattern::AnyKeyword(_) => 0,
+            Pattern::Optional(items) => items.iter().map(Pattern::value_count).sum(),
+            _ => 1,
+        }
+    }
+}
+
+// 命令中的取值，按语法中的顺序排列
+#[derive(Debug, Clone, PartialEq)]
+pub enum Value {
+    Number(u32),
+    Color(Color),
+    Position(Position),
+    Name(String),
+    // 候选词，已经换成标准写法
+    Word(&'static str),
+    // 省略的可选子句中的一项
+    Absent,
+}
+
+// 按顺序读取命令中的取值，类型不符时返回 None
+#[derive(Debug, Clone)]
+pub struct Args {
+    values: std::vec::IntoIter<Value>,
+}
+
+impl Args {
+    pub fn new(values: Vec<Value>) -> Self {
+        Args {
+            values: values.into_iter(),
+        }
+    }
+
+    pub fn number(&mut self) -> Option<u32> {
+        match self.values.next()? {
+            Value::Number(n) => Some(n),
+            _ => None,
+        }
+    }
+
+    pub fn color(&mut self) -> Option<Color> {
+        match self.values.next()? {
+            Value::Color(color) => Some(color),
+            _ => None,
+        }
+    }
+
+    pub fn position(&mut self) -> Option<Position> {
+        match self.values.next()? {
+            Value::Position(position) => Some(position),
+            _ => None,
+        }
+    }
+
+    pub fn name(&mut self) -> Option<String> {
+        match self.values.next()? {
+            Value::Name(name) => Some(name),
+            _ => None,
+        }
+    }
+
+    pub fn word(&mut self) -> Option<&'static str> {
+        match self.values.next()? {
+            Value::Word(word) => Some(word),
+            _ => None,
+        }
+    }
+
+    // 可选子句中的位置，省略时为 Some(None)
+    pub fn optional_position(&mut self) -> Option<Option<Position>> {
+        match self.values.next()? {
+            Value::Position(position) => Some(Some(position)),
+            Value::Absent => Some(None),
+            _ => None,
+        }
+    }
+
+    // 可选子句中的数字，省略时为 Some(None)
+    pub fn optional_number(&mut self) -> Option<Option<u32>> {
+        match self.values.next()? {
+            Value::Number(n) => Some(Some(n)),
+            Value::Absent => Some(None),
+            _ => None,
+        }
+    }
+
+    // 可选子句中的候选词，省略时为 Some(None)
+    pub fn optional_word(&mut self) -> Option<Option<&'static str>> {
+        match self.values.next()? {
+            Value::Word(word) => Some(Some(word)),
+            Value::Absent => Some(None),
+            _ => None,
+        }
+    }
+}
+
+// 命令定义：语法和由取值生成语法树的函数
+#[derive(Debug, Clone, Copy)]
+pub struct CommandSpec {
+    // 用法说明，例如 "GAME <id> UNDO"
+    pub usage: &'static str,
+    pub pattern: &'static [Pattern],
+    pub build: fn(&mut Args) -> Option<ASTNode>,
+}
+
+// 所有命令，新增命令只需要在这里注册
+// 多条命令可以有相同的开头，解析时选择完整匹配的一条
+pub const COMMANDS: &[CommandSpec] = &[
+    CommandSpec {
+        usage: "CREATE GAME",
+        pattern: &[Pattern::Keyword(Keywords::Create), Pattern::Keyword(Keywords::Game)],
+        build: build_create_game,
+    },
+    CommandSpec {
+        usage: "JOIN GAME <id> <name> <color>",
+        pattern: &[
+            Pattern::Keyword(Keywords::Join),
+            Pattern::Keyword(Keywords::Game),
+            Pattern::Number("game id"),
+            Pattern::Name("player name"),
+            Pattern::Color,
+        ],
+        build: build_join_game,
+    },
+    CommandSpec {
+        usage: "GAME <id> <color> MOVE (<x>,<y>) TO (<x>,<y>)",
+        pattern: &[
+            Pattern::Keyword(Keywords::Game),
+            Pattern::Number("game id"),
+            Pattern::Color,
+            Pattern::Keyword(Keywords::Move),
+            Pattern::Coordinate,
+            Pattern::Keyword(Keywords::To),
+            Pattern::Coordinate,
+        ],
+        build: build_move,
+    },
+    CommandSpec {
+        usage: "GAME <id> UNDO",
+        pattern: &[
+            Pattern::Keyword(Keywords::Game),
+            Pattern::Number("game id"),
+            Pattern::Keyword(Keywords::Undo),
+        ],
+        build: build_undo,
+    },
+    CommandSpec {
+        usage: "UNDO GAME <id>",
+        pattern: &[
+            Pattern::Keyword(Keywords::Undo),
+            Pattern::Keyword(Keywords::Game),
+            Pattern::Number("game id"),
+        ],
+        build: build_undo,
+    },
+    CommandSpec {
+        usage: "GET GAME <id> [STATUS]",
+        pattern: &[
+            Pattern::Keyword(Keywords::Get),
+            Pattern::Keyword(Keywords::Game),
+            Pattern::Number("game id"),
+            Pattern::Optional(&[Pattern::Keyword(Keywords::Status)]),
+        ],
+        build: build_get_game,
+    },
+    CommandSpec {
+        usage: "GET GAME <id> HISTORY [ICCS|CHINESE|WXF|COORDINATE]",
+        pattern: &[
+            Pattern::Keyword(Keywords::Get),
+            Pattern::Keyword(Keywords::Game),
+            Pattern::Number("game id"),
+            Pattern::Keyword(Keywords::History),
+            Pattern::Optional(&[NOTATION]),
+        ],
+        build: build_get_history,
+    },
+    CommandSpec {
+        usage: "GET GAME <id> BOARD",
+        pattern: &[
+            Pattern::Keyword(Keywords::Get),
+            Pattern::Keyword(Keywords::Game),
+            Pattern::Number("game id"),
+            Pattern::Keyword(Keywords::Board),
+        ],
+        build: build_get_board,
+    },
+    CommandSpec {
+        usage: "GET GAME <id> MOVES [FROM (<x>,<y>)]",
+        pattern: &[
+            Pattern::Keyword(Keywords::Get),
+            Pattern::Keyword(Keywords::Game),
+            Pattern::Number("game id"),
+            Pattern::Keyword(Keywords::Moves),
+            Pattern::Optional(&[Pattern::Keyword(Keywords::From), Pattern::Coordinate]),
+        ],
+        build: build_get_moves,
+    },
+    CommandSpec {
+        usage: "LIST GAMES [ALL|PLAYING|FINISHED|RED_WON|BLACK_WON|STALEMATE|DRAW]",
+        pattern: &[
+            Pattern::Keyword(Keywords::List),
+            Pattern::AnyKeyword(&[Keywords::Games, Keywords::Game]),
+            Pattern::Optional(&[FILTER]),
+        ],
+        build: build_list_games,
+    },
+    CommandSpec {
+        usage: "DELETE GAME <id>",
+        pattern: &[
+            Pattern::Keyword(Keywords::Delete),
+            Pattern::Keyword(Keywords::Game),
+            Pattern::Number("game id"),
+        ],
+        build: build_delete_game,
+    },
+    CommandSpec {
+        usage: "WATCH GAME <id> [FROM <seq>]",
+        pattern: &[
+            Pattern::Keyword(Keywords::Watch),
+            Pattern::Keyword(Keywords::Game),
+            Pattern::Number("game id"),
+            Pattern::Optional(&[Pattern::Keyword(Keywords::From), Pattern::Number("event seq")]),
+        ],
+        build: build_watch_game,
+    },
+    CommandSpec {
+        usage: "GET GAME <id> EVENTS SINCE <seq>",
+        pattern: &[
+            Pattern::Keyword(Keywords::Get),
+            Pattern::Keyword(Keywords::Game),
+            Pattern::Number("game id"),
+            Pattern::Keyword(Keywords::Events),
+            Pattern::Keyword(Keywords::Since),
+            Pattern::Number("event seq"),
+        ],
+        build: build_get_events,
+    },
+    CommandSpec {
+        usage: "GAME <id> <color> OFFER DRAW",
+        pattern: &[
+            Pattern::Keyword(Keywords::Game),
+            Pattern::Number("game id"),
+            Pattern::Color,
+            Pattern::Keyword(Keywords::Offer),
+            DRAW,
+        ],
+        build: build_offer_draw,
+    },
+];
+
+// 和棋，不作为关键字，以免影响 LIST GAMES DRAW
+const DRAW: Pattern = Pattern::Word {
+    names: &["DRAW"],
+    aliases: &[("和棋", "DRAW")],
+};
+
+// 记谱方式
+const NOTATION: Pattern = Pattern::Word {
+    names: &["ICCS", "CHINESE", "WXF", "COORDINATE"],
+    aliases: &[("中文", "CHINESE"), ("坐标", "COORDINATE")],
+};
+
+// 对局状态过滤条件
+const FILTER: Pattern = Pattern::Word {
+    names: &["ALL", "PLAYING", "FINISHED", "RED_WON", "BLACK_WON", "STALEMATE", "DRAW"],
+    aliases: &[
+        ("全部", "ALL"),
+        ("进行中", "PLAYING"),
+        ("已结束", "FINISHED"),
+        ("红胜", "RED_WON"),
+        ("黑胜", "BLACK_WON"),
+        ("困毙", "STALEMATE"),
+        ("和棋", "DRAW"),
+    ],
+};
+
+fn build_create_game(_: &mut Args) -> Option<ASTNode> {
+    Some(ASTNode::CreateGame)
+}
+
+fn build_join_game(args: &mut Args) -> Option<ASTNode> {
+    Some(ASTNode::JoinGame {
+        game_id: args.number()?,
+        name: args.name()?,
+        color: args.color()?,
+    })
+}
+
+fn build_move(args: &mut Args) -> Option<ASTNode> {
+    Some(ASTNode::Move {
+        game_id: args.number()?,
+        color: args.color()?,
+        mv: Move::new(args.position()?, args.position()?)?,
+    })
+}
+
+fn build_undo(args: &mut Args) -> Option<ASTNode> {
+    Some(ASTNode::Undo {
+        game_id: args.number()?,
+    })
+}
+
+fn build_get_game(args: &mut Args) -> Option<ASTNode> {
+    Some(ASTNode::GetGame {
+        game_id: args.number()?,
+    })
+}
+
+fn build_get_history(args: &mut Args) -> Option<ASTNode> {
+    let game_id = args.number()?;
+    let notation = match args.optional_word()? {
+        None | Some("ICCS") => Notation::Iccs,
+        Some("CHINESE") => Notation::Chinese,
+        Some("WXF") => Notation::Wxf,
+        Some("COORDINATE") => Notation::Coordinate,
+        Some(_) => return None,
+    };
+    Some(ASTNode::GetHistory {
+        game_id,
+        notation,
+    })
+}
+
+fn build_get_board(args: &mut Args) -> Option<ASTNode> {
+    Some(ASTNode::GetBoard {
+        game_id: args.number()?,
+    })
+}
+
+fn build_get_moves(args: &mut Args) -> Option<ASTNode> {
+    Some(ASTNode::GetMoves {
+        game_id: args.number()?,
+        from: args.optional_position()?,
+    })
+}
+
+fn build_list_games(args: &mut Args) -> Option<ASTNode> {
+    let filter = match args.optional_word()? {
+        None | Some("ALL") => GameFilter::All,
+        Some("PLAYING") => GameFilter::Playing,
+        Some("FINISHED") => GameFilter::Finished,
+        Some("RED_WON") => GameFilter::RedWon,
+        Some("BLACK_WON") => GameFilter::BlackWon,
+        Some("STALEMATE") => GameFilter::Stalemate,
+        Some("DRAW") => GameFilter::Draw,
+        Some(_) => return None,
+    };
+    Some(ASTNode::ListGames {
+        filter,
+    })
+}
+
+fn build_delete_game(args: &mut Args) -> Option<ASTNode> {
+    Some(ASTNode::DeleteGame {
+        game_id: args.number()?,
+    })
+}
+
+fn build_watch_game(args: &mut Args) -> Option<ASTNode> {
+    Some(ASTNode::WatchGame {
+        game_id: args.number()?,
+        from: args.optional_number()?.map(u64::from),
+    })
+}
+
+fn build_get_events(args: &mut Args) -> Option<ASTNode> {
+    Some(ASTNode::GetEvents {
+        game_id: args.number()?,
+        since: u64::from(args.number()?),
+    })
+}
+
+fn build_offer_draw(args: &mut Args) -> Option<ASTNode> {
+    let game_id = args.number()?;
+    let color = args.color()?;
+    args.word()?;
+    Some(ASTNode::OfferDraw {
+        game_id,
+        color,
+    })
+}
@@ -1,6 +1,7 @@
+use std::ops::Range;
 
 // 关键字
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, Copy, PartialEq)]
 pub enum Keywords{
     Game,
     Create,
@@ -9,8 +10,62 @@ pub enum Keywords{
     Undo,
     Get,
     To,
+    List,
+    Games,
+    Delete,
+    History,
+    Board,
+    Moves,
+    From,
+    Status,
+    Watch,
+    Events,
+    Since,
+    Offer,
 }
 
+// 词法错误
+#[derive(Debug, Clone, PartialEq)]
+pub enum LexError{
+    // 无法识别的字符
+    UnexpectedChar(char),
+    // 字符串没有结束的引号
+    UnterminatedString,
+    // 字符串中不支持的转义，例如 \q
+    BadEscape(char),
+    // 负数，附带原文
+    NegativeNumber(String),
+    // 数字超出范围，附带原文
+    NumberOutOfRange(String),
+}
+
+impl std::fmt::Display for LexError{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self{
+            LexError::UnexpectedChar(c) => write!(f, "unexpected character '{}'", c),
+            LexError::UnterminatedString => write!(f, "unterminated string"),
+            LexError::BadEscape(c) => write!(f, "unknown escape '\\{}' in string", c),
+            LexError::NegativeNumber(text) => write!(f, "negative number {} is not allowed", text),
+            LexError::NumberOutOfRange(text) => write!(f, "number {} is out of range", text),
+        }
+    }
+}
+
+impl LexError{
+    // 中文说明
+    pub fn description(&self) -> String{
+        match self{
+            LexError::UnexpectedChar(c) => format!("无法识别的字符 '{}'", c),
+            LexError::UnterminatedString => "字符串缺少结尾的引号".to_string(),
+            LexError::BadEscape(c) => format!("字符串中不支持的转义 '\\{}'", c),
+            LexError::NegativeNumber(text) => format!("不能使用负数 {}", text),
+            LexError::NumberOutOfRange(text) => format!("数字 {} 超出范围", text),
+        }
+    }
+}
+
+impl std::error::Error for LexError {}
+
 #[derive(Debug, Clone, PartialEq)]
 pub enum Token{
     Keyword(Keywords),
@@ -32,14 +87,74 @@ pub enum Token{
     // 逗号
     Comma,
 
-    // 标识符
+    // 标识符，保留原来的大小写
     Identifier(String),
 
+    // 双引号字符串，已经处理转义
+    Str(String),
+
+    // 词法错误
+    Error(LexError),
+
     // 结束标记
-    Eof, 
+    Eof,
+}
+
+impl Keywords{
+    // 英文名称
+    pub fn name(&self) -> &'static str{
+        match self{
+            Keywords::Game => "GAME",
+            Keywords::Create => "CREATE",
+            Keywords::Join => "JOIN",
+            Keywords::Move => "MOVE",
+            Keywords::Undo => "UNDO",
+            Keywords::Get => "GET",
+            Keywords::To => "TO",
+            Keywords::List => "LIST",
+            Keywords::Games => "GAMES",
+            Keywords::Delete => "DELETE",
+            Keywords::History => "HISTORY",
+            Keywords::Board => "BOARD",
+            Keywords::Moves => "MOVES",
+            Keywords::From => "FROM",
+            Keywords::Status => "STATUS",
+            Keywords::Watch => "WATCH",
+            Keywords::Events => "EVENTS",
+            Keywords::Since => "SINCE",
+            Keywords::Offer => "OFFER",
+        }
+    }
+}
+
+impl std::fmt::Display for Keywords{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+
+// 用于错误提示的标记文本
+impl std::fmt::Display for Token{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self{
+            Token::Keyword(keyword) => write!(f, "{}", keyword),
+            Token::Red => write!(f, "RED"),
+            Token::Black => write!(f, "BLACK"),
+            Token::Number(n) => write!(f, "{}", n),
+            Token::Coordinate(x, y) => write!(f, "({},{})", x, y),
+            Token::LeftParen => write!(f, "'('"),
+            Token::RightParen => write!(f, "')'"),
+            Token::Comma => write!(f, "','"),
+            Token::Identifier(ident) => write!(f, "{}", ident),
+            Token::Str(text) => write!(f, "{:?}", text),
+            Token::Error(error) => write!(f, "{}", error),
+            Token::Eof => write!(f, "end of input"),
+        }
+    }
 }
 
 // 词法分析器
+// position 和 read_position 为字节偏移，标记的位置可以直接用于切片原始输入
 #[derive(Debug, Clone)]
 pub struct Lexer{
     input: String,
@@ -52,7 +167,7 @@ pub struct Lexer{
 impl Lexer{
     pub fn new(input: &str) -> Self{
         let mut lexer = Lexer {
-            input: input.trim().to_string(),
+            input: input.to_string(),
             position: 0,
             read_position: 0,
             ch: '\0',
@@ -64,91 +179,178 @@ impl Lexer{
 
     // 读取下一个字符
     fn read_char(&mut self) {
-        if self.read_position >= self.input.len(){
-            self.ch = '\0';
-        }else{
-            self.ch = self.input.chars().nth(self.read_position).unwrap();
+        self.position = self.read_position;
+        match self.input[self.read_position..].chars().next(){
+            Some(c) => {
+                self.ch = c;
+                self.read_position += c.len_utf8();
+            }
+            None => self.ch = '\0',
         }
+    }
 
-        self.position = self.read_position;
-        self.read_position += 1;
+    // 是否已经读完输入
+    fn at_end(&self) -> bool{
+        self.position >= self.input.len()
     }
 
-    // 跳过空白字符
+    // 跳过空白字符和 # 开始的注释，注释到行尾为止
     fn skip_whitespace(&mut self){
-        while self.ch.is_whitespace(){
-            self.read_char();
+        loop{
+            if self.ch.is_whitespace(){
+                self.read_char();
+            }else if self.ch == '#'{
+                while self.ch != '\n' && !self.at_end(){
+                    self.read_char();
+                }
+            }else{
+                break;
+            }
         }
     }
 
-    // 读取数字
-    fn read_number(&mut self) -> u32{
+    // 读取数字，前面可以带负号
+    fn read_number<T: std::str::FromStr>(&mut self) -> Result<T, LexError>{
         let start = self.position;
-        while self.ch.is_digit(10) {
+        let negative = self.ch == '-';
+        if negative{
+            self.read_char();
+        }
+        while self.ch.is_ascii_digit() {
             self.read_char();
         }
-        self.input[start..self.position].parse().unwrap()
+        let text = &self.input[start..self.position];
+        if negative{
+            return Err(LexError::NegativeNumber(text.to_string()));
+        }
+        text.parse().map_err(|_| LexError::NumberOutOfRange(text.to_string()))
     }
 
-
     // 读取标识符
     fn read_identifier(&mut self) -> String {
         let start = self.position;
-        while self.ch.is_alphanumeric() {
+        while self.ch.is_alphanumeric() || self.ch == '_' {
             self.read_char();
         }
         self.input[start..self.position].to_string()
     }
-    
+
+    // 读取双引号字符串，支持 \" \\ \n \t \r 转义
+    fn read_string(&mut self) -> Result<String, LexError> {
+        // 跳过开头的引号
+        self.read_char();
+
+        let mut text = String::new();
+        let mut error = None;
+        loop {
+            if self.at_end() {
+                return Err(LexError::UnterminatedString);
+            }
+            match self.ch {
+                '"' => {
+                    self.read_char();
+                    break;
+                }
+                '\\' => {
+                    self.read_char();
+                    if self.at_end() {
+                        return Err(LexError::UnterminatedString);
+                    }
+                    match self.ch {
+                        '"' => text.push('"'),
+                        '\\' => text.push('\\'),
+                        'n' => text.push('\n'),
+                        't' => text.push('\t'),
+                        'r' => text.push('\r'),
+                        // 继续读到结尾的引号，错误标记覆盖整个字符串
+                        c => {
+                            error.get_or_insert(LexError::BadEscape(c));
+                        }
+                    }
+                    self.read_char();
+                }
+                c => {
+                    text.push(c);
+                    self.read_char();
+                }
+            }
+        }
+
+        match error {
+            Some(error) => Err(error),
+            None => Ok(text),
+        }
+    }
+
     // 读取坐标
-    fn read_coordinate(&mut self) -> Option<(u8, u8)> {
+    // 不是完整的坐标时回到 '(' 处，由调用者按单个括号处理
+    fn read_coordinate(&mut self) -> Option<Result<(u8, u8), LexError>> {
+        let saved = (self.position, self.read_position, self.ch);
+        let coordinate = self.read_coordinate_inner();
+        if coordinate.is_none(){
+            (self.position, self.read_position, self.ch) = saved;
+        }
+        coordinate
+    }
+
+    fn read_coordinate_inner(&mut self) -> Option<Result<(u8, u8), LexError>> {
         // 期望 '('
         if self.ch != '(' {
             return None;
         }
         self.read_char();
-        
+
         // 读取 x 坐标
-        let x = if self.ch.is_digit(10) {
-            self.read_number() as u8
-        } else {
+        if !self.starts_number() {
             return None;
-        };
-        
+        }
+        let x = self.read_number::<u8>();
+
         // 期望 ','
         if self.ch != ',' {
             return None;
         }
         self.read_char();
-        
+
         // 读取 y 坐标
-        let y = if self.ch.is_digit(10) {
-            self.read_number() as u8
-        } else {
+        if !self.starts_number() {
             return None;
-        };
-        
+        }
+        let y = self.read_number::<u8>();
+
         // 期望 ')'
         if self.ch != ')' {
             return None;
         }
         self.read_char();
-        
-        Some((x, y))
+
+        Some(x.and_then(|x| y.map(|y| (x, y))))
+    }
+
+    // 当前位置是否为数字或负号加数字
+    fn starts_number(&self) -> bool {
+        self.ch.is_ascii_digit()
+            || (self.ch == '-' && self.input[self.read_position..].starts_with(|c: char| c.is_ascii_digit()))
     }
 
     // 获取下一个标记
     pub fn next_token(&mut self) -> Token {
         self.skip_whitespace();
-        
-        let token = match self.ch {
+
+        if self.at_end() {
+            return Token::Eof;
+        }
+
+        match self.ch {
             '(' => {
                 // 检查是否是坐标
-                if let Some((x, y)) = self.read_coordinate() {
-                    Token::Coordinate(x, y)
-                } else {
-                    self.read_char();
-                    Token::LeftParen
+                match self.read_coordinate() {
+                    Some(Ok((x, y))) => Token::Coordinate(x, y),
+                    Some(Err(error)) => Token::Error(error),
+                    None => {
+                        self.read_char();
+                        Token::LeftParen
+                    }
                 }
             }
             ')' => {
@@ -159,32 +361,55 @@ impl Lexer{
                 self.read_char();
                 Token::Comma
             }
-            '0'..='9' => {
-                Token::Number(self.read_number())
-            }
-            'a'..='z' | 'A'..='Z' => {
-                let ident = self.read_identifier().to_uppercase();
-                match ident.as_str() {
-                    "GAME" => Token::Keyword(Keywords::Game),
-                    "CREATE" => Token::Keyword(Keywords::Create),
-                    "JOIN" => Token::Keyword(Keywords::Join),
-                    "MOVE" => Token::Keyword(Keywords::Move),
-                    "UNDO" => Token::Keyword(Keywords::Undo),
-                    "GET" => Token::Keyword(Keywords::Get),
-                    "TO" => Token::Keyword(Keywords::To),
-                    "RED" => Token::Red,
-                    "BLACK" => Token::Black,
+            '"' => match self.read_string() {
+                Ok(text) => Token::Str(text),
+                Err(error) => Token::Error(error),
+            },
+            _ if self.starts_number() => match self.read_number() {
+                Ok(n) => Token::Number(n),
+                Err(error) => Token::Error(error),
+            },
+            c if c.is_alphabetic() || c == '_' => {
+                let ident = self.read_identifier();
+                // 英文关键字不区分大小写，中文关键字与英文关键字等价
+                match ident.to_uppercase().as_str() {
+                    "GAME" | "游戏" | "对局" => Token::Keyword(Keywords::Game),
+                    "CREATE" | "创建" | "新建" => Token::Keyword(Keywords::Create),
+                    "JOIN" | "加入" => Token::Keyword(Keywords::Join),
+                    "MOVE" | "走" | "移动" => Token::Keyword(Keywords::Move),
+                    "UNDO" | "悔棋" => Token::Keyword(Keywords::Undo),
+                    "GET" | "获取" | "查看" => Token::Keyword(Keywords::Get),
+                    "TO" | "到" => Token::Keyword(Keywords::To),
+                    "LIST" | "列出" => Token::Keyword(Keywords::List),
+                    "GAMES" => Token::Keyword(Keywords::Games),
+                    "DELETE" | "删除" => Token::Keyword(Keywords::Delete),
+                    "HISTORY" | "棋谱" | "历史" => Token::Keyword(Keywords::History),
+                    "BOARD" | "棋盘" => Token::Keyword(Keywords::Board),
+                    "MOVES" | "走法" => Token::Keyword(Keywords::Moves),
+                    "FROM" | "从" => Token::Keyword(Keywords::From),
+                    "STATUS" | "状态" => Token::Keyword(Keywords::Status),
+                    "WATCH" | "观战" => Token::Keyword(Keywords::Watch),
+                    "EVENTS" | "事件" => Token::Keyword(Keywords::Events),
+                    "SINCE" | "自" => Token::Keyword(Keywords::Since),
+                    "OFFER" | "提议" => Token::Keyword(Keywords::Offer),
+                    "RED" | "红" | "红方" => Token::Red,
+                    "BLACK" | "黑" | "黑方" => Token::Black,
                     _ => Token::Identifier(ident),
                 }
             }
-            '\0' => Token::Eof,
-            _ => {
+            c => {
                 self.read_char();
-                Token::Eof
+                Token::Error(LexError::UnexpectedChar(c))
             }
-        };
-        
-        token
+        }
+    }
+
+    // 获取下一个标记及其在输入中的字节范围
+    pub fn next_spanned(&mut self) -> (Token, Range<usize>) {
+        self.skip_whitespace();
+        let start = self.position;
+        let token = self.next_token();
+        (token, start..self.position)
     }
 
     // 生成所有标记
@@ -199,4 +424,4 @@ impl Lexer{
         }
         tokens
     }
-}
\ No newline at end of file
+}
@@ -1,249 +1,184 @@
+use std::ops::Range;
+
 use crate::core::piece::Color;
-use crate::parser::lexer::{Lexer, Token, Keywords};
+use crate::core::position::Position;
+use crate::parser::lexer::{Lexer, Token};
 use crate::parser::ast::ASTNode;
+use crate::parser::error::ParseError;
+use crate::parser::grammar::{Args, CommandSpec, Pattern, Value, COMMANDS};
 
 // 语法分析器
+// 先把整条命令切分为标记，再逐条尝试注册的命令，选择完整匹配的一条
 #[derive(Debug, Clone)]
 pub struct Parser {
-    lexer: Lexer,
-    current_token: Token,
-    peek_token: Token,
+    source: String,
+    // 以 Eof 结尾的所有标记
+    tokens: Vec<(Token, Range<usize>)>,
+    // 走得最远的失败位置及该处可以接受的标记，用于错误提示
+    furthest: usize,
+    expected: Vec<&'static str>,
 }
 
 impl Parser {
     // 创建新的语法分析器
     pub fn new(input: &str) -> Self {
         let mut lexer = Lexer::new(input);
-        let current_token = lexer.next_token();
-        let peek_token = lexer.next_token();
-        
+        let mut tokens = Vec::new();
+        loop {
+            let (token, span) = lexer.next_spanned();
+            let end = token == Token::Eof;
+            tokens.push((token, span));
+            if end {
+                break;
+            }
+        }
+
         Parser {
-            lexer,
-            current_token,
-            peek_token,
+            source: input.to_string(),
+            tokens,
+            furthest: 0,
+            expected: Vec::new(),
         }
     }
-    
-    // 前进到下一个标记
-    fn next_token(&mut self) {
-        self.current_token = self.peek_token.clone();
-        self.peek_token = self.lexer.next_token();
-    }
-    
-    // 期望当前标记是指定类型
-    fn expect_token(&mut self, expected: Token) -> bool {
-        if self.current_token == expected {
-            self.next_token();
-            true
-        } else {
-            false
-        }
+
+    // 指定位置的标记，超出范围时为结尾的 Eof
+    fn token(&self, pos: usize) -> &Token {
+        let index = pos.min(self.tokens.len() - 1);
+        &self.tokens[index].0
     }
-    
-    // 期望当前标记是指定关键字
-    fn expect_keyword(&mut self, expected: Keywords) -> bool {
-        match &self.current_token {
-            Token::Keyword(keyword) => {
-                if keyword == &expected {
-                    self.next_token();
-                    true
-                } else {
-                    false
+
+    // 记录匹配失败，只保留走得最远的位置，相同位置的期望标记合并
+    fn fail(&mut self, pos: usize, expected: &[&'static str]) {
+        if pos > self.furthest {
+            self.furthest = pos;
+            self.expected.clear();
+        }
+        if pos == self.furthest {
+            for name in expected {
+                if !self.expected.contains(name) {
+                    self.expected.push(name);
                 }
             }
-            _ => false,
         }
     }
-    
-    // 解析颜色
-    fn parse_color(&mut self) -> Option<Color> {
-        match self.current_token {
-            Token::Red => {
-                self.next_token();
-                Some(Color::Red)
-            }
-            Token::Black => {
-                self.next_token();
-                Some(Color::Black)
+
+    // 匹配单项，成功时返回下一个位置
+    fn match_pattern(&mut self, pattern: &Pattern, pos: usize, values: &mut Vec<Value>) -> Option<usize> {
+        let value = match (pattern, self.token(pos)) {
+            (Pattern::Keyword(keyword), Token::Keyword(current)) if current == keyword => None,
+            (Pattern::AnyKeyword(keywords), Token::Keyword(current)) if keywords.contains(current) => None,
+            (Pattern::Number(_), Token::Number(n)) => Some(Value::Number(*n)),
+            (Pattern::Color, Token::Red) => Some(Value::Color(Color::Red)),
+            (Pattern::Color, Token::Black) => Some(Value::Color(Color::Black)),
+            (Pattern::Coordinate, Token::Coordinate(x, y)) => match Position::try_new(*x, *y) {
+                Some(position) => Some(Value::Position(position)),
+                None => {
+                    self.fail(pos, &["coordinate on the board"]);
+                    return None;
+                }
+            },
+            (Pattern::Name(_), Token::Identifier(name) | Token::Str(name)) => Some(Value::Name(name.clone())),
+            (Pattern::Word { names, aliases }, Token::Identifier(word)) => {
+                let word = word.to_uppercase();
+                let name = names
+                    .iter()
+                    .find(|name| **name == word)
+                    .or_else(|| aliases.iter().find(|(alias, _)| *alias == word).map(|(_, name)| name));
+                match name {
+                    Some(name) => Some(Value::Word(name)),
+                    None => {
+                        self.fail(pos, names);
+                        return None;
+                    }
+                }
             }
-            _ => None,
-        }
-    }
-    
-    // 解析数字
-    fn parse_number(&mut self) -> Option<u32> {
-        match self.current_token {
-            Token::Number(n) => {
-                let num = n;
-                self.next_token();
-                Some(num)
+            (Pattern::Optional(items), _) => return Some(self.match_optional(items, pos, values)),
+            _ => {
+                self.fail(pos, &expected_names(pattern));
+                return None;
             }
-            _ => None,
-        }
-    }
-    
-    // 解析标识符
-    fn parse_identifier(&mut self) -> Option<String> {
-        match self.current_token {
-            Token::Identifier(ref s) => {
-                let ident = s.clone();
-                self.next_token();
-                Some(ident)
-            }
-            _ => None,
-        }
+        };
+
+        values.extend(value);
+        Some(pos + 1)
     }
-    
-    // 解析坐标
-    fn parse_coordinate(&mut self) -> Option<(u8, u8)> {
-        match self.current_token {
-            Token::Coordinate(x, y) => {
-                let coord = (x, y);
-                self.next_token();
-                Some(coord)
+
+    // 匹配可选子句：第一项不出现时整个子句省略，每个取值记为 Absent
+    fn match_optional(&mut self, items: &'static [Pattern], pos: usize, values: &mut Vec<Value>) -> usize {
+        let start = values.len();
+        match self.match_sequence(items, pos, values) {
+            Some(next) => next,
+            None => {
+                values.truncate(start);
+                let count: usize = items.iter().map(Pattern::value_count).sum();
+                values.extend(std::iter::repeat_n(Value::Absent, count));
+                pos
             }
-            _ => None,
         }
     }
-    
-    // 解析创建游戏命令
-    fn parse_create_game(&mut self) -> Option<ASTNode> {
-        if !self.expect_keyword(Keywords::Create) {
-            return None;
-        }
-        
-        if !self.expect_keyword(Keywords::Game) {
-            return None;
-        }
-        
-        Some(ASTNode::CreateGame)
-    }
-    
-    // 解析加入游戏命令
-    fn parse_join_game(&mut self) -> Option<ASTNode> {
-        if !self.expect_keyword(Keywords::Join) {
-            return None;
-        }
-        
-        if !self.expect_keyword(Keywords::Game) {
-            return None;
+
+    // 依次匹配多项，成功时返回下一个位置
+    fn match_sequence(&mut self, patterns: &'static [Pattern], mut pos: usize, values: &mut Vec<Value>) -> Option<usize> {
+        for pattern in patterns {
+            pos = self.match_pattern(pattern, pos, values)?;
         }
-        
-        let game_id = self.parse_number()?;
-        let name = self.parse_identifier()?;
-        let color = self.parse_color()?;
-        
-        Some(ASTNode::JoinGame {
-            game_id,
-            name,
-            color,
-        })
+        Some(pos)
     }
-    
-    // 解析移动命令
-    fn parse_move(&mut self) -> Option<ASTNode> {
-        if !self.expect_keyword(Keywords::Game) {
-            return None;
-        }
-        
-        let game_id = self.parse_number()?;
-        let color = self.parse_color()?;
-        
-        if !self.expect_keyword(Keywords::Move) {
-            return None;
-        }
-        
-        let (from_x, from_y) = self.parse_coordinate()?;
-        
-        if !self.expect_keyword(Keywords::To) {
+
+    // 匹配一条完整的命令，后面必须是结尾
+    fn match_command(&mut self, spec: &CommandSpec) -> Option<ASTNode> {
+        let mut values = Vec::new();
+        let end = self.match_sequence(spec.pattern, 0, &mut values)?;
+        if *self.token(end) != Token::Eof {
+            self.fail(end, &["end of input"]);
             return None;
         }
-        
-        let (to_x, to_y) = self.parse_coordinate()?;
-        
-        Some(ASTNode::Move {
-            game_id,
-            color,
-            from_x,
-            from_y,
-            to_x,
-            to_y,
-        })
+        (spec.build)(&mut Args::new(values))
     }
-    
-    // 解析悔棋命令
-    fn parse_undo(&mut self) -> Option<ASTNode> {
-        if !self.expect_keyword(Keywords::Undo) {
-            return None;
-        }
-        
-        if !self.expect_keyword(Keywords::Game) {
-            return None;
-        }
-        
-        let game_id = self.parse_number()?;
-        
-        Some(ASTNode::Undo {
-            game_id,
-        })
+
+    // 解析命令，失败时返回出错的位置和期望的标记
+    pub fn parse(&mut self) -> Result<ASTNode, ParseError> {
+        self.parse_with(COMMANDS)
     }
-    
-    // 解析获取游戏状态命令
-    fn parse_get_game(&mut self) -> Option<ASTNode> {
-        if !self.expect_keyword(Keywords::Get) {
-            return None;
+
+    // 按指定的命令表解析
+    pub fn parse_with(&mut self, commands: &[CommandSpec]) -> Result<ASTNode, ParseError> {
+        self.furthest = 0;
+        self.expected.clear();
+
+        for spec in commands {
+            if let Some(node) = self.match_command(spec) {
+                return Ok(node);
+            }
         }
-        
-        if !self.expect_keyword(Keywords::Game) {
-            return None;
+
+        // 命令结尾放在最后
+        if let Some(i) = self.expected.iter().position(|name| *name == "end of input") {
+            let end = self.expected.remove(i);
+            self.expected.push(end);
         }
-        
-        let game_id = self.parse_number()?;
-        
-        Some(ASTNode::GetGame {
-            game_id,
+
+        let index = self.furthest.min(self.tokens.len() - 1);
+        let (found, span) = self.tokens[index].clone();
+        Err(ParseError {
+            source: self.source.clone(),
+            span,
+            found,
+            expected: std::mem::take(&mut self.expected).into_boxed_slice(),
+            after: index.checked_sub(1).map(|previous| self.tokens[previous].0.clone()),
         })
     }
-    
-    // 解析命令
-    pub fn parse(&mut self) -> ASTNode {
-        match self.current_token {
-            Token::Keyword(Keywords::Create) => {
-                if let Some(node) = self.parse_create_game() {
-                    node
-                } else {
-                    ASTNode::Invalid
-                }
-            }
-            Token::Keyword(Keywords::Join) => {
-                if let Some(node) = self.parse_join_game() {
-                    node
-                } else {
-                    ASTNode::Invalid
-                }
-            }
-            Token::Keyword(Keywords::Game) => {
-                if let Some(node) = self.parse_move() {
-                    node
-                } else {
-                    ASTNode::Invalid
-                }
-            }
-            Token::Keyword(Keywords::Undo) => {
-                if let Some(node) = self.parse_undo() {
-                    node
-                } else {
-                    ASTNode::Invalid
-                }
-            }
-            Token::Keyword(Keywords::Get) => {
-                if let Some(node) = self.parse_get_game() {
-                    node
-                } else {
-                    ASTNode::Invalid
-                }
-            }
-            _ => ASTNode::Invalid,
-        }
+}
+
+// 单项在错误提示中的名称
+fn expected_names(pattern: &Pattern) -> Vec<&'static str> {
+    match pattern {
+        Pattern::Keyword(keyword) => vec![keyword.name()],
+        Pattern::AnyKeyword(keywords) => keywords.iter().map(|keyword| keyword.name()).collect(),
+        Pattern::Number(name) | Pattern::Name(name) => vec![name],
+        Pattern::Color => vec!["RED", "BLACK"],
+        Pattern::Coordinate => vec!["coordinate (x,y)"],
+        Pattern::Word { names, .. } => names.to_vec(),
+        Pattern::Optional(items) => items.first().map(expected_names).unwrap_or_default(),
     }
 }
@@ -0,0 +1,291 @@
+use std::collections::HashMap;
+
+use crate::core::ruler::Game;
+use crate::{Color, Engine, EngineResult};
+
+/// 脚本中一条语句的执行结果
+#[derive(Debug, Clone, PartialEq)]
+pub struct ScriptStep {
+    /// 行号，从 1 开始
+    pub line: usize,
+    /// 原文，去掉首尾空白，变量没有替换
+    pub text: String,
+    pub passed: bool,
+    /// 命令为结果的第一行；断言失败时为原因
+    pub message: String,
+}
+
+/// 脚本的执行报告
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ScriptReport {
+    pub steps: Vec<ScriptStep>,
+}
+
+impl ScriptReport {
+    /// 所有语句都通过
+    pub fn passed(&self) -> bool {
+        self.steps.iter().all(|step| step.passed)
+    }
+
+    /// 失败的语句
+    pub fn failures(&self) -> impl Iterator<Item = &ScriptStep> {
+        self.steps.iter().filter(|step| !step.passed)
+    }
+
+    /// 通过和失败的语句数
+    pub fn counts(&self) -> (usize, usize) {
+        let failed = self.failures().count();
+        (self.steps.len() - failed, failed)
+    }
+}
+
+/// 每条语句一行，例如 `PASS 3: EXPECT MOVE_SUCCESS 1`，失败的语句下一行缩进说明原因，最后一行为统计
+impl std::fmt::Display for ScriptReport {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for step in &self.steps {
+            if step.passed {
+                writeln!(f, "PASS {}: {}", step.line, step.text)?;
+            } else {
+                writeln!(f, "FAIL {}: {}", step.line, step.text)?;
+                writeln!(f, "    {}", step.message)?;
+            }
+        }
+        let (passed, failed) = self.counts();
+        write!(f, "{} passed, {} failed", passed, failed)
+    }
+}
+
+// 脚本执行状态
+struct ScriptRunner<'a> {
+    engine: &'a mut Engine,
+    report: ScriptReport,
+    // LET 保存的游戏ID
+    variables: HashMap<String, u32>,
+    // 上一条命令的结果及其在报告中的位置
+    last: Option<(usize, EngineResult)>,
+}
+
+impl ScriptRunner<'_> {
+    fn push(&mut self, line: usize, text: &str, result: Result<String, String>) {
+        let (passed, message) = match result {
+            Ok(message) => (true, message),
+            Err(message) => (false, message),
+        };
+        self.report.steps.push(ScriptStep {
+            line,
+            text: text.to_string(),
+            passed,
+            message,
+        });
+    }
+
+    // 执行一行，空行和注释行不产生结果
+    fn run_line(&mut self, line: usize, text: &str) {
+        let text = text.trim();
+        if text.is_empty() || text.starts_with('#') {
+            return;
+        }
+
+        let (directive, rest) = split_word(text);
+        if directive.eq_ignore_ascii_case("EXPECT") {
+            let result = self.expect(strip_comment(rest));
+            self.push(line, text, result);
+        } else if directive.eq_ignore_ascii_case("LET") {
+            self.let_binding(line, text, rest);
+        } else {
+            self.command(line, text, text);
+        }
+    }
+
+    // 执行命令，失败的命令如果之后没有 EXPECT 检查则记为失败
+    fn command(&mut self, line: usize, text: &str, command: &str) -> Option<u32> {
+        let command = match self.substitute(command) {
+            Ok(command) => command,
+            Err(error) => {
+                self.push(line, text, Err(error));
+                self.last = None;
+                return None;
+            }
+        };
+
+        let result = self.engine.execute(&command);
+        let summary = first_line(&result);
+        let outcome = if result.is_ok() {
+            Ok(summary)
+        } else {
+            Err(format!("command failed: {}", summary))
+        };
+        self.push(line, text, outcome);
+
+        let game_id = result.game_id();
+        self.last = Some((self.report.steps.len() - 1, result));
+        game_id
+    }
+
+    // LET <name> = <command>，保存命令结果中的游戏ID
+    fn let_binding(&mut self, line: usize, text: &str, rest: &str) {
+        let Some((name, command)) = rest.split_once('=') else {
+            self.push(line, text, Err("expected LET <name> = <command>".to_string()));
+            return;
+        };
+        let name = name.trim().trim_start_matches('$');
+        if name.is_empty() || !name.chars().all(is_variable_char) {
+            self.push(line, text, Err(format!("invalid variable name '{}'", name)));
+            return;
+        }
+
+        let index = self.report.steps.len();
+        let Some(game_id) = self.command(line, text, command.trim()) else {
+            return;
+        };
+        self.variables.insert(name.to_string(), game_id);
+        let step = &mut self.report.steps[index];
+        if step.passed {
+            step.message = format!("${} = {}", name, game_id);
+        }
+    }
+
+    // EXPECT <result> 或 EXPECT BOARD <fen>，检查上一条命令
+    fn expect(&mut self, expected: &str) -> Result<String, String> {
+        let expected = self.substitute(expected)?;
+        let (word, fen) = split_word(&expected);
+        if word.eq_ignore_ascii_case("BOARD") {
+            return self.expect_board(fen);
+        }
+
+        let Some((index, result)) = &self.last else {
+            return Err("no command before EXPECT".to_string());
+        };
+        let actual = first_line(result);
+
+        // 期望的内容为实际结果的前几项即可，例如 MOVE_FAILED 可以匹配 MOVE_FAILED 1 HORSE_LEG_BLOCKED
+        let mut actual_words = actual.split_whitespace();
+        if expected.is_empty() || !expected.split_whitespace().all(|word| actual_words.next() == Some(word)) {
+            return Err(format!("expected {}, found {}", expected, actual));
+        }
+
+        // 检查过的失败命令不再记为失败
+        let step = &mut self.report.steps[*index];
+        if !step.passed {
+            step.passed = true;
+            step.message = actual.clone();
+        }
+        Ok(actual)
+    }
+
+    // 比较上一条命令所在游戏的局面，只比较棋子位置和给出的走子方
+    fn expect_board(&self, fen: &str) -> Result<String, String> {
+        let game_id = self.last.as_ref()
+            .and_then(|(_, result)| result.game_id())
+            .ok_or("no game to check before EXPECT BOARD")?;
+        let game = self.engine.get_game_manager().get_game(game_id)
+            .ok_or(format!("game {} not found", game_id))?;
+        let actual = game.to_fen();
+
+        let mut actual_fields = actual.split_whitespace();
+        let mut expected_fields = fen.split_whitespace().take(2);
+        if expected_fields.next().is_none_or(|placement| actual_fields.next() != Some(placement))
+            || expected_fields.next().is_some_and(|side| actual_fields.next() != Some(side))
+        {
+            return Err(format!("expected board {}, found {}", fen, actual));
+        }
+        Ok(actual)
+    }
+
+    // 把 $name 替换为变量的值，字符串中的 $ 保持原样
+    fn substitute(&self, text: &str) -> Result<String, String> {
+        let mut output = String::with_capacity(text.len());
+        let mut rest = text;
+        while let Some(start) = find_unquoted(rest, '$') {
+            output.push_str(&rest[..start]);
+            let after = &rest[start + 1..];
+            let end = after.find(|c: char| !is_variable_char(c)).unwrap_or(after.len());
+            let name = &after[..end];
+            match self.variables.get(name) {
+                Some(value) => output.push_str(&value.to_string()),
+                None => return Err(format!("undefined variable ${}", name)),
+            }
+            rest = &after[end..];
+        }
+        output.push_str(rest);
+        Ok(output)
+    }
+}
+
+/// 逐行执行脚本，见 [`Engine::run_script`]
+pub fn run(engine: &mut Engine, script: &str) -> ScriptReport {
+    let mut runner = ScriptRunner {
+        engine,
+        report: ScriptReport::default(),
+        variables: HashMap::new(),
+        last: None,
+    };
+    for (index, line) in script.lines().enumerate() {
+        runner.run_line(index + 1, line);
+    }
+    runner.report
+}
+
+/// 把对局保存为命令脚本，可以用 [`Engine::run_script`] 重放，也可以导出为 HTML
+///
+/// 命令使用对局自己的ID；在新引擎中重放时 `CREATE GAME` 创建的是游戏1，因此只有第一个游戏能原样重放
+pub fn save(game: &Game) -> String {
+    let mut script = String::from("CREATE GAME\n");
+    for record in &game.move_history {
+        let (from_x, from_y, to_x, to_y) = record.mv.coordinates();
+        script.push_str(&format!(
+            "GAME {} {} MOVE ({},{}) TO ({},{})\n",
+            game.id,
+            if record.moved_piece.color == Color::Red { "RED" } else { "BLACK" },
+            from_x, from_y, to_x, to_y
+        ));
+    }
+    script
+}
+
+// 分出第一个词和其余部分
+fn split_word(text: &str) -> (&str, &str) {
+    match text.split_once(char::is_whitespace) {
+        Some((word, rest)) => (word, rest.trim()),
+        None => (text, ""),
+    }
+}
+
+// 去掉行尾的 # 注释，字符串中的 # 不是注释
+fn strip_comment(text: &str) -> &str {
+    match find_unquoted(text, '#') {
+        Some(index) => text[..index].trim(),
+        None => text.trim(),
+    }
+}
+
+// 查找双引号字符串之外的字符，字符串中的转义与命令的词法规则一致
+fn find_unquoted(text: &str, target: char) -> Option<usize> {
+    let mut quoted = false;
+    let mut escaped = false;
+    for (index, c) in text.char_indices() {
+        if quoted {
+            if escaped {
+                escaped = false;
+            } else if c == '\\' {
+                escaped = true;
+            } else if c == '"' {
+                quoted = false;
+            }
+        } else if c == '"' {
+            quoted = true;
+        } else if c == target {
+            return Some(index);
+        }
+    }
+    None
+}
+
+// 结果文本的第一行，使用英文
+fn first_line(result: &EngineResult) -> String {
+    result.to_string().lines().next().unwrap_or("").to_string()
+}
+
+fn is_variable_char(c: char) -> bool {
+    c.is_alphanumeric() || c == '_'
+}
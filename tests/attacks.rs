@@ -0,0 +1,183 @@
+use chinese_chess_engine::core::attacks;
+use chinese_chess_engine::core::bitboard::Bitboard;
+use chinese_chess_engine::{Board, Color, MoveError, Piece, PieceType, Position};
+
+const PIECE_TYPES: [PieceType; 7] = [
+    PieceType::King,
+    PieceType::Advisor,
+    PieceType::Elephant,
+    PieceType::Horse,
+    PieceType::Chariot,
+    PieceType::Cannon,
+    PieceType::Pawn,
+];
+
+// 固定种子的随机数，使随机摆放可以重现
+struct Rng(u64);
+
+impl Rng {
+    fn next(&mut self, bound: usize) -> usize {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 7;
+        self.0 ^= self.0 << 17;
+        (self.0 % bound as u64) as usize
+    }
+
+    fn color(&mut self) -> Color {
+        if self.next(2) == 0 { Color::Red } else { Color::Black }
+    }
+}
+
+fn position(index: usize) -> Position {
+    Position::from_index(index as u8).unwrap()
+}
+
+// 空棋盘和若干疏密不同的随机摆放，用兵占位
+fn layouts() -> Vec<Vec<(Position, Piece)>> {
+    let mut rng = Rng(0x9e37_79b9_7f4a_7c15);
+    let mut layouts = vec![Vec::new()];
+    for density in [2, 3, 4, 6] {
+        for _ in 0..4 {
+            let mut pieces = Vec::new();
+            for index in 0..90 {
+                if rng.next(density) == 0 {
+                    pieces.push((position(index), Piece { piece_type: PieceType::Pawn, color: rng.color() }));
+                }
+            }
+            layouts.push(pieces);
+        }
+    }
+    layouts
+}
+
+fn board_of(pieces: &[(Position, Piece)]) -> (Board, Bitboard) {
+    let mut board = Board::default();
+    let mut bitboard = Bitboard::new();
+    for &(pos, piece) in pieces {
+        board.set_piece(pos, piece);
+        bitboard.set_piece(pos, piece);
+    }
+    (board, bitboard)
+}
+
+// 由攻击表得到的目标位置
+fn table_targets(piece: Piece, square: usize, bitboard: &Bitboard) -> u128 {
+    let occupied = bitboard.occupied();
+    let targets = match piece.piece_type {
+        PieceType::King => attacks::king_attacks(piece.color, square),
+        PieceType::Advisor => attacks::advisor_attacks(piece.color, square),
+        PieceType::Elephant => attacks::elephant_attacks(piece.color, square, occupied),
+        PieceType::Horse => attacks::horse_attacks(square, occupied),
+        PieceType::Chariot => attacks::chariot_attacks(square, bitboard),
+        PieceType::Cannon => {
+            attacks::chariot_attacks(square, bitboard) & !occupied
+                | attacks::cannon_attacks(square, bitboard) & bitboard.color_mask(piece.color.opponent())
+        }
+        PieceType::Pawn => attacks::pawn_attacks(piece.color, square),
+        PieceType::None => 0,
+    };
+    targets & !bitboard.color_mask(piece.color)
+}
+
+// 逐个目标检查走法得到的目标位置
+fn validated_targets(board: &Board, from: Position, color: Color) -> u128 {
+    (0..90)
+        .filter(|&to| board.validate_move(from, position(to), color).is_ok())
+        .fold(0, |targets, to| targets | 1u128 << to)
+}
+
+#[test]
+fn attack_tables_match_move_validation() {
+    // 统计被挡住的马腿、象眼和隔子吃的炮，确认随机摆放覆盖了这些情况
+    let (mut horse_legs, mut elephant_eyes, mut cannon_screens) = (0, 0, 0);
+
+    for (layout, pieces) in layouts().iter().enumerate() {
+        let (mut board, bitboard) = board_of(pieces);
+        for piece_type in PIECE_TYPES {
+            for color in [Color::Red, Color::Black] {
+                let piece = Piece { piece_type, color };
+                for square in 0..90 {
+                    let from = position(square);
+                    let original = board.get_piece(from);
+                    board.set_piece(from, piece);
+                    let mut with_piece = bitboard.clone();
+                    with_piece.set_piece(from, piece);
+
+                    assert_eq!(
+                        table_targets(piece, square, &with_piece),
+                        validated_targets(&board, from, color),
+                        "{:?} {:?} at {:?}, layout {}", color, piece_type, (from.x(), from.y()), layout
+                    );
+                    for to in 0..90 {
+                        match board.validate_move(from, position(to), color) {
+                            Err(MoveError::HorseLegBlocked) => horse_legs += 1,
+                            Err(MoveError::ElephantEyeBlocked) => elephant_eyes += 1,
+                            Ok(()) if piece_type == PieceType::Cannon && board.get_piece(position(to)).piece_type != PieceType::None => cannon_screens += 1,
+                            _ => {}
+                        }
+                    }
+
+                    board.set_piece(from, original);
+                }
+            }
+        }
+    }
+
+    assert!(horse_legs > 0 && elephant_eyes > 0 && cannon_screens > 0);
+}
+
+#[test]
+fn blocked_legs_eyes_and_screens() {
+    // 马在 (4,4)，(4,5) 的马腿被挡住，不能走到 (3,6) 和 (5,6)
+    let (board, bitboard) = board_of(&[
+        (position(4 + 4 * 9), Piece { piece_type: PieceType::Horse, color: Color::Red }),
+        (position(4 + 5 * 9), Piece { piece_type: PieceType::Pawn, color: Color::Black }),
+    ]);
+    let horse = attacks::horse_attacks(4 + 4 * 9, bitboard.occupied());
+    assert_eq!(horse & (1u128 << (3 + 6 * 9) | 1u128 << (5 + 6 * 9)), 0);
+    assert_eq!(horse.count_ones(), 6);
+    assert_eq!(board.validate_move(position(4 + 4 * 9), position(3 + 6 * 9), Color::Red), Err(MoveError::HorseLegBlocked));
+
+    // 象在 (2,0)，(3,1) 的象眼被挡住，只能走到 (0,2)
+    let (board, bitboard) = board_of(&[
+        (position(2), Piece { piece_type: PieceType::Elephant, color: Color::Red }),
+        (position(3 + 9), Piece { piece_type: PieceType::Advisor, color: Color::Red }),
+    ]);
+    assert_eq!(attacks::elephant_attacks(Color::Red, 2, bitboard.occupied()), 1u128 << (2 * 9));
+    assert_eq!(board.validate_move(position(2), position(4 + 2 * 9), Color::Red), Err(MoveError::ElephantEyeBlocked));
+
+    // 炮在 (1,2)，隔着 (1,5) 吃 (1,7)；(1,9) 前有两个子，吃不到
+    let (board, bitboard) = board_of(&[
+        (position(1 + 2 * 9), Piece { piece_type: PieceType::Cannon, color: Color::Red }),
+        (position(1 + 5 * 9), Piece { piece_type: PieceType::Pawn, color: Color::Red }),
+        (position(1 + 7 * 9), Piece { piece_type: PieceType::Cannon, color: Color::Black }),
+        (position(1 + 9 * 9), Piece { piece_type: PieceType::Horse, color: Color::Black }),
+    ]);
+    assert_eq!(attacks::cannon_attacks(1 + 2 * 9, &bitboard), 1u128 << (1 + 7 * 9));
+    assert!(board.validate_move(position(1 + 2 * 9), position(1 + 7 * 9), Color::Red).is_ok());
+    assert_eq!(board.validate_move(position(1 + 2 * 9), position(1 + 9 * 9), Color::Red), Err(MoveError::PathBlocked));
+}
+
+#[test]
+fn attackers_to_matches_move_validation() {
+    let mut rng = Rng(0x2545_f491_4f6c_dd1d);
+    for layout in 0..16 {
+        // 各种棋子随机摆放，不要求摆放符合规则
+        let mut pieces = Vec::new();
+        for index in 0..90 {
+            if rng.next(3) == 0 {
+                pieces.push((position(index), Piece { piece_type: PIECE_TYPES[rng.next(7)], color: rng.color() }));
+            }
+        }
+        let (board, _) = board_of(&pieces);
+
+        for color in [Color::Red, Color::Black] {
+            for to in 0..90 {
+                let expected = pieces.iter()
+                    .filter(|(from, piece)| piece.color == color && board.validate_move(*from, position(to), color).is_ok())
+                    .fold(0, |attackers, (from, _)| attackers | 1u128 << from.index());
+                assert_eq!(board.attackers_to(position(to), color), expected, "{:?} to {}, layout {}", color, to, layout);
+            }
+        }
+    }
+}
@@ -0,0 +1,71 @@
+use chinese_chess_engine::Engine;
+
+// 执行命令并返回结果的第一行
+fn run(engine: &mut Engine, command: &str) -> String {
+    let output = engine.execute_to_string(command);
+    output.lines().next().unwrap_or("").to_string()
+}
+
+#[test]
+fn move_and_undo_on_missing_game_report_game_not_found() {
+    let mut engine = Engine::new();
+    assert_eq!(run(&mut engine, "GAME 99 RED MOVE (0,0) TO (0,1)"), "GAME_NOT_FOUND 99");
+    assert_eq!(run(&mut engine, "GAME 99 UNDO"), "GAME_NOT_FOUND 99");
+
+    run(&mut engine, "CREATE GAME");
+    assert!(run(&mut engine, "GAME 1 UNDO").starts_with("UNDO_FAILED 1"));
+}
+
+#[test]
+fn query_commands_report_game_state() {
+    let mut engine = Engine::new();
+    run(&mut engine, "CREATE GAME");
+    run(&mut engine, "CREATE GAME");
+    run(&mut engine, "GAME 1 RED MOVE (7,2) TO (4,2)");
+
+    assert_eq!(run(&mut engine, "LIST GAMES"), "GAME_LIST 2");
+    assert_eq!(run(&mut engine, "LIST GAMES FINISHED"), "GAME_LIST 0");
+    assert_eq!(run(&mut engine, "DELETE GAME 2"), "GAME_DELETED 2");
+    assert_eq!(run(&mut engine, "DELETE GAME 2"), "GAME_NOT_FOUND 2");
+    assert_eq!(
+        engine.execute_to_string("LIST GAMES"),
+        "GAME_LIST 1\nGAME 1 Playing TURN BLACK MOVES 1"
+    );
+
+    assert_eq!(engine.execute_to_string("GET GAME 1 HISTORY"), "GAME_HISTORY 1 ICCS 1\n1. h2e2");
+    assert_eq!(engine.execute_to_string("GET GAME 1 HISTORY WXF"), "GAME_HISTORY 1 WXF 1\n1. C2.5");
+    assert_eq!(
+        run(&mut engine, "GET GAME 1 BOARD"),
+        "GAME_BOARD 1 rnbakabnr/9/1c5c1/p1p1p1p1p/9/9/P1P1P1P1P/1C2C4/9/RNBAKABNR b - - 0 1"
+    );
+    assert_eq!(run(&mut engine, "GET GAME 7 BOARD"), "GAME_NOT_FOUND 7");
+}
+
+#[test]
+fn moves_query_lists_only_the_side_to_move() {
+    let mut engine = Engine::new();
+    run(&mut engine, "CREATE GAME");
+    run(&mut engine, "GAME 1 RED MOVE (7,2) TO (4,2)");
+
+    assert_eq!(run(&mut engine, "GET GAME 1 MOVES"), "LEGAL_MOVES 1 45");
+    assert_eq!(
+        engine.execute_to_string("GET GAME 1 MOVES FROM (1,9)"),
+        "LEGAL_MOVES 1 2\n(1,9) TO (0,7) b9a7 馬2进1\n(1,9) TO (2,7) b9c7 馬2进3"
+    );
+    // 红方的炮不是当前走子方，空位没有走法
+    assert_eq!(run(&mut engine, "GET GAME 1 MOVES FROM (4,2)"), "LEGAL_MOVES 1 0");
+    assert_eq!(run(&mut engine, "GET GAME 1 MOVES FROM (4,5)"), "LEGAL_MOVES 1 0");
+}
+
+#[test]
+fn readme_undo_and_status_forms_run() {
+    let mut engine = Engine::new();
+    run(&mut engine, "CREATE GAME");
+    run(&mut engine, "GAME 1 RED MOVE (7,2) TO (4,2)");
+    run(&mut engine, "GAME 1 BLACK MOVE (7,9) TO (6,7)");
+
+    assert_eq!(run(&mut engine, "GAME 1 UNDO"), "UNDO_SUCCESS 1");
+    assert_eq!(run(&mut engine, "UNDO GAME 1"), "UNDO_SUCCESS 1");
+    assert_eq!(engine.execute_to_string("GET GAME 1 STATUS"), engine.execute_to_string("GET GAME 1"));
+    assert_eq!(run(&mut engine, "GET GAME 1 HISTORY"), "GAME_HISTORY 1 ICCS 0");
+}
@@ -0,0 +1,130 @@
+use chinese_chess_engine::{Board, Color, Piece, PieceType, Position, RenderStyle};
+
+// 固定种子的随机数，使随机对局可以重现
+struct Rng(u64);
+
+impl Rng {
+    fn next(&mut self, bound: usize) -> usize {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 7;
+        self.0 ^= self.0 << 17;
+        (self.0 % bound as u64) as usize
+    }
+}
+
+// 从初始局面随机走子，得到过河棋子和残局等各种局面
+fn random_positions() -> Vec<(Board, Color)> {
+    let mut rng = Rng(0x9e37_79b9_7f4a_7c15);
+    let mut positions = Vec::new();
+
+    for _ in 0..10 {
+        let mut board = Board::new();
+        let mut color = Color::Red;
+        for ply in 0..120 {
+            let moves = board.legal_moves(color);
+            if moves.is_empty() {
+                break;
+            }
+            if ply % 8 == 0 {
+                positions.push((board.clone(), color));
+            }
+            board.make_move(moves[rng.next(moves.len())]);
+            color = color.opponent();
+        }
+    }
+    positions
+}
+
+#[test]
+fn render_round_trips_through_from_diagram() {
+    let styles = [RenderStyle::Ascii, RenderStyle::Unicode, RenderStyle::Compact];
+    for (board, _) in random_positions() {
+        for style in styles {
+            for perspective in [Color::Red, Color::Black] {
+                let diagram = board.render(style, perspective);
+                let parsed = Board::from_diagram(&diagram)
+                    .unwrap_or_else(|error| panic!("{}\n{}", error, diagram));
+                assert_eq!(parsed.fen_placement(), board.fen_placement(), "\n{}", diagram);
+            }
+        }
+    }
+}
+
+#[test]
+fn fen_round_trips() {
+    for (board, color) in random_positions() {
+        let fen = board.to_fen(color);
+        let (parsed, side) = Board::from_fen(&fen).unwrap();
+        assert_eq!(side, color);
+        assert_eq!(parsed.to_fen(side), fen);
+    }
+}
+
+#[test]
+fn readme_diagram_with_shared_glyphs_parses() {
+    let diagram = "\
+   0  1  2  3  4  5  6  7  8
+  +--+--+--+--+--+--+--+--+--+
+9 |車|馬|象|士|将|士|象|馬|車|
+  +--+--+--+--+--+--+--+--+--+
+8 |  |  |  |  |  |  |  |  |  |
+  +--+--+--+--+--+--+--+--+--+
+7 |  |炮|  |  |  |  |  |炮|  |
+  +--+--+--+--+--+--+--+--+--+
+6 |卒|  |卒|  |卒|  |卒|  |卒|
+  +--+--+--+--+--+--+--+--+--+
+5 |  |  |  |  |  |  |  |  |  |
+  +--+--+--+--+--+--+--+--+--+
+4 |  |  |  |  |  |  |  |  |  |
+  +--+--+--+--+--+--+--+--+--+
+3 |兵|  |兵|  |兵|  |兵|  |兵|
+  +--+--+--+--+--+--+--+--+--+
+2 |  |炮|  |  |  |  |  |炮|  |
+  +--+--+--+--+--+--+--+--+--+
+1 |  |  |  |  |  |  |  |  |  |
+  +--+--+--+--+--+--+--+--+--+
+0 |車|馬|相|仕|帅|仕|相|馬|車|
+  +--+--+--+--+--+--+--+--+--+
+";
+    let board = Board::from_diagram(diagram).unwrap();
+    assert_eq!(board.fen_placement(), Board::new().fen_placement());
+}
+
+#[test]
+fn simplified_glyphs_parse_for_both_colors() {
+    let diagram = "\
+9 . . . 将 . . . . .
+8 . . . . . . . . .
+7 . . . . . . . . .
+6 . . . . . . . . .
+5 . . . . . . . . 车
+4 . . . . . . . . .
+3 . . . . . . . . .
+2 . 马 . . . . . . .
+1 . . . . . . . . .
+0 . . . . 帅 . . . .
+";
+    let board = Board::from_diagram(diagram).unwrap();
+    assert_eq!(board.get_piece(Position::try_new(1, 2).unwrap()), Piece::new(PieceType::Horse, Color::Red));
+    assert_eq!(board.get_piece(Position::try_new(8, 5).unwrap()), Piece::new(PieceType::Chariot, Color::Black));
+}
+
+#[test]
+fn traditional_glyphs_decide_color_regardless_of_side() {
+    // 繁体字形中車为黑方、炮为红方，即使已经过河
+    let diagram = "\
+9 . . . 將 . . . . .
+8 . . . . . . . . .
+7 . . . . . . . 炮 .
+6 . . . . . . . . .
+5 . . . . . . . . .
+4 . . . . . . . . .
+3 . . . . . . . . .
+2 . 車 . . . . . . .
+1 . . . . . . . . .
+0 . . . . 帥 . . . .
+";
+    let board = Board::from_diagram(diagram).unwrap();
+    assert_eq!(board.get_piece(Position::try_new(7, 7).unwrap()), Piece::new(PieceType::Cannon, Color::Red));
+    assert_eq!(board.get_piece(Position::try_new(1, 2).unwrap()), Piece::new(PieceType::Chariot, Color::Black));
+}
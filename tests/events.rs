@@ -0,0 +1,193 @@
+use chinese_chess_engine::{CatchUp, Color, Engine, EngineResult, GameEvent, Move, Ruler};
+
+fn play(ruler: &mut Ruler, game_id: u32, iccs: &str) {
+    let mv = Move::from_iccs(iccs).unwrap();
+    ruler.make_move(game_id, mv).unwrap();
+}
+
+#[test]
+fn new_subscriber_gets_snapshot_only() {
+    let mut ruler = Ruler::new();
+    let game_id = ruler.create_game();
+    play(&mut ruler, game_id, "h2e2");
+
+    let subscription = ruler.subscribe(game_id, "观众".to_string(), None).unwrap();
+    match subscription.catch_up {
+        CatchUp::Snapshot(snapshot) => {
+            assert_eq!(snapshot.move_count, 1);
+            assert_eq!(snapshot.last_seq, 1);
+        }
+        CatchUp::Replay(_) => panic!("expected a snapshot"),
+    }
+    assert!(subscription.receiver.try_recv().is_err());
+}
+
+#[test]
+fn resumed_subscriber_gets_missed_events_once() {
+    let mut ruler = Ruler::new();
+    let game_id = ruler.create_game();
+    play(&mut ruler, game_id, "h2e2");
+    play(&mut ruler, game_id, "h9g7");
+    play(&mut ruler, game_id, "h0g2");
+
+    let subscription = ruler.subscribe(game_id, "观众".to_string(), Some(1)).unwrap();
+    let missed = match subscription.catch_up {
+        CatchUp::Replay(missed) => missed,
+        CatchUp::Snapshot(_) => panic!("expected a replay"),
+    };
+    let seqs: Vec<u64> = missed.iter().map(|record| record.seq).collect();
+    assert_eq!(seqs, vec![2, 3]);
+
+    play(&mut ruler, game_id, "i9h9");
+    let next = subscription.receiver.try_recv().unwrap();
+    assert_eq!(next.seq, 4);
+    assert!(matches!(next.event, GameEvent::Moved { .. }));
+    assert!(subscription.receiver.try_recv().is_err());
+}
+
+#[test]
+fn events_since_is_looked_up_by_seq() {
+    let mut ruler = Ruler::new();
+    let game_id = ruler.create_game();
+    play(&mut ruler, game_id, "h2e2");
+    play(&mut ruler, game_id, "h9g7");
+
+    assert_eq!(ruler.get_events_since(game_id, 0).unwrap().len(), 2);
+    assert_eq!(ruler.get_events_since(game_id, 1).unwrap()[0].seq, 2);
+    assert!(ruler.get_events_since(game_id, 2).unwrap().is_empty());
+    assert!(ruler.get_events_since(game_id, 100).unwrap().is_empty());
+}
+
+#[test]
+fn unsubscribe_removes_only_that_subscription() {
+    let mut ruler = Ruler::new();
+    let game_id = ruler.create_game();
+    let first = ruler.subscribe(game_id, "观众".to_string(), None).unwrap();
+    let second = ruler.subscribe(game_id, "观众".to_string(), None).unwrap();
+    assert_ne!(first.id, second.id);
+
+    assert!(ruler.unsubscribe(game_id, first.id));
+    assert!(!ruler.unsubscribe(game_id, first.id));
+
+    play(&mut ruler, game_id, "h2e2");
+    assert!(first.receiver.try_recv().is_err());
+    assert_eq!(second.receiver.try_recv().unwrap().seq, 1);
+}
+
+// 复制的对局保留事件记录，但不向原对局的观众推送
+#[test]
+fn cloned_game_drops_spectators() {
+    let mut ruler = Ruler::new();
+    let game_id = ruler.create_game();
+    let subscription = ruler.subscribe(game_id, "观众".to_string(), None).unwrap();
+    play(&mut ruler, game_id, "h2e2");
+    assert_eq!(subscription.receiver.try_recv().unwrap().seq, 1);
+
+    let mut copy = ruler.get_game(game_id).unwrap().clone();
+    assert_eq!(copy.events.last_seq(), 1);
+    assert!(copy.events.spectators().is_empty());
+    assert_eq!(copy.events.emit(game_id, GameEvent::DrawOffered { color: Color::Black }), 2);
+    assert!(subscription.receiver.try_recv().is_err());
+}
+
+#[test]
+fn watch_command_returns_snapshot_or_missed_events() {
+    let mut engine = Engine::new();
+    engine.execute("CREATE GAME");
+    engine.execute("GAME 1 RED MOVE (7,2) TO (4,2)");
+    engine.execute("GAME 1 BLACK MOVE (7,9) TO (6,7)");
+
+    match engine.execute("WATCH GAME 1") {
+        EngineResult::GameWatched(1, last_seq, view) => {
+            assert_eq!(last_seq, 2);
+            assert_eq!(view.move_count, 2);
+        }
+        other => panic!("unexpected result: {other}"),
+    }
+    assert_eq!(
+        engine.execute_to_string("WATCH GAME 1 FROM 1"),
+        "GAME_EVENTS 1 1\nEVENT 2 MOVED BLACK (7,9) TO (6,7) n"
+    );
+    assert_eq!(engine.execute_to_string("观战 游戏 1 从 2"), "GAME_EVENTS 1 0");
+    assert_eq!(engine.execute_to_string("WATCH GAME 9"), "GAME_NOT_FOUND 9");
+}
+
+#[test]
+fn events_since_command_polls_by_seq() {
+    let mut engine = Engine::new();
+    engine.execute("CREATE GAME");
+    engine.execute("GAME 1 RED MOVE (7,2) TO (4,2)");
+    engine.execute("GAME 1 BLACK MOVE (7,9) TO (6,7)");
+    engine.execute("GAME 1 RED MOVE (4,2) TO (4,6)");
+    engine.execute("GAME 1 UNDO");
+
+    assert_eq!(
+        engine.execute_to_string("GET GAME 1 EVENTS SINCE 2"),
+        "GAME_EVENTS 1 2\n\
+         EVENT 3 MOVED RED (4,2) TO (4,6) C CAPTURED p\n\
+         EVENT 4 UNDO (4,2) TO (4,6) C CAPTURED p"
+    );
+    assert_eq!(engine.execute_to_string("GET GAME 1 EVENTS SINCE 4"), "GAME_EVENTS 1 0");
+
+    let json: serde_json::Value =
+        serde_json::from_str(&engine.execute("GET GAME 1 EVENTS SINCE 3").to_json()).unwrap();
+    assert_eq!(json["kind"], "GAME_EVENTS");
+    let event = &json["payload"]["events"][0];
+    assert_eq!(event["seq"], 4);
+    assert_eq!(event["kind"], "UNDO");
+    assert_eq!(event["from"], serde_json::json!([4, 2]));
+    assert_eq!(event["captured"]["piece_type"], "Pawn");
+}
+
+#[test]
+fn offer_draw_command_emits_event() {
+    let mut engine = Engine::new();
+    engine.execute("CREATE GAME");
+    assert!(matches!(engine.execute("GAME 1 BLACK OFFER DRAW"), EngineResult::DrawOffered(1)));
+    assert_eq!(engine.execute_to_string("游戏 1 红 提议 和棋"), "DRAW_OFFERED 1");
+    assert_eq!(
+        engine.execute_to_string("GET GAME 1 EVENTS SINCE 0"),
+        "GAME_EVENTS 1 2\nEVENT 1 DRAW_OFFERED BLACK\nEVENT 2 DRAW_OFFERED RED"
+    );
+    assert_eq!(engine.execute_to_string("GAME 9 RED OFFER DRAW"), "GAME_NOT_FOUND 9");
+    assert!(engine.execute_to_string("GAME 1 RED OFFER").starts_with("INVALID_COMMAND"));
+    // DRAW 不是关键字，按状态过滤不受影响
+    assert_eq!(engine.execute_to_string("LIST GAMES DRAW"), "GAME_LIST 0");
+}
+
+#[test]
+fn draw_offer_is_rejected_after_game_over() {
+    let mut engine = Engine::new();
+    engine.execute("CREATE GAME");
+    // 五步杀：炮打底象将死
+    for command in [
+        "GAME 1 RED MOVE (1,2) TO (2,2)",
+        "GAME 1 BLACK MOVE (1,7) TO (2,7)",
+        "GAME 1 RED MOVE (2,2) TO (2,6)",
+        "GAME 1 BLACK MOVE (5,9) TO (4,8)",
+        "GAME 1 RED MOVE (2,6) TO (2,9)",
+    ] {
+        assert_eq!(engine.execute_to_string(command), "MOVE_SUCCESS 1");
+    }
+    assert_eq!(engine.execute_to_string("GAME 1 BLACK OFFER DRAW"), "DRAW_OFFER_FAILED 1");
+}
+
+#[test]
+fn game_handle_watches_and_polls() {
+    let mut engine = Engine::new();
+    let id = engine.create_game();
+    let mut game = engine.game(id).unwrap();
+    let subscription = game.watch("观众", None);
+    game.play(Move::from_iccs("h2e2").unwrap()).unwrap();
+    game.offer_draw(Color::Black).unwrap();
+
+    assert_eq!(subscription.receiver.try_recv().unwrap().seq, 1);
+    assert!(matches!(
+        subscription.receiver.try_recv().unwrap().event,
+        GameEvent::DrawOffered { color: Color::Black, .. }
+    ));
+    assert_eq!(game.last_event_seq(), 2);
+    assert_eq!(game.events_since(1).len(), 1);
+    assert!(game.unwatch(subscription.id));
+    assert!(!game.unwatch(subscription.id));
+}
@@ -0,0 +1,381 @@
+use std::ffi::{CStr, CString};
+use std::ptr;
+
+use chinese_chess_engine::ffi::*;
+
+fn description(error: i32, language: i32) -> Option<String> {
+    let text = cec_move_error_description(error, language);
+    if text.is_null() {
+        return None;
+    }
+    Some(unsafe { CStr::from_ptr(text) }.to_str().unwrap().to_string())
+}
+
+#[test]
+fn move_error_description_follows_language() {
+    // 8 为 CEC_MOVE_ERROR_HORSE_LEG_BLOCKED
+    assert_eq!(description(8, 0).as_deref(), Some("horse leg is blocked"));
+    assert_eq!(description(8, 1).as_deref(), Some("蹩马腿"));
+    assert_eq!(description(0, 0), None);
+    assert_eq!(description(17, 1), None);
+    assert_eq!(description(8, 2), None);
+}
+
+// 执行命令，结果由调用者释放
+fn run(engine: *mut CECEngine, command: &str) -> *mut CECEngineResult {
+    let command = CString::new(command).unwrap();
+    let mut result = ptr::null_mut();
+    assert_eq!(unsafe { cec_engine_execute(engine, command.as_ptr(), &mut result) }, CECStatus::Ok);
+    result
+}
+
+// 执行命令并丢弃结果
+fn execute(engine: *mut CECEngine, command: &str) {
+    unsafe { cec_result_free(run(engine, command)) };
+}
+
+fn kind(result: *const CECEngineResult) -> CECResultKind {
+    let mut kind = CECResultKind::GameCreated;
+    assert_eq!(unsafe { cec_result_kind(result, &mut kind) }, CECStatus::Ok);
+    kind
+}
+
+fn reason(result: *const CECEngineResult) -> CECErrorReason {
+    let mut reason = CECErrorReason::None;
+    assert_eq!(unsafe { cec_result_error_reason(result, &mut reason) }, CECStatus::Ok);
+    reason
+}
+
+fn last_error() -> String {
+    let message = cec_last_error();
+    assert!(!message.is_null());
+    unsafe { CStr::from_ptr(message) }.to_str().unwrap().to_string()
+}
+
+#[test]
+fn result_accessors_report_fields() {
+    let engine = cec_engine_new();
+    unsafe {
+        let mut game_id = 0;
+        let mut status = CECGameStatus::Draw;
+
+        let result = run(engine, "CREATE GAME");
+        assert_eq!(kind(result), CECResultKind::GameCreated);
+        assert_eq!(reason(result), CECErrorReason::None);
+        assert_eq!(cec_result_game_id(result, &mut game_id), CECStatus::Ok);
+        assert_eq!(game_id, 1);
+        assert_eq!(cec_result_status(result, &mut status), CECStatus::NotAvailable);
+        assert_eq!(last_error(), "result is not a game status");
+        cec_result_free(result);
+
+        // 炮打马
+        let result = run(engine, "GAME 1 RED MOVE (1,2) TO (1,9)");
+        let mut piece = CECPiece { piece_type: 7, color: 0 };
+        assert_eq!(kind(result), CECResultKind::MoveSuccess);
+        assert_eq!(cec_result_captured_piece(result, &mut piece), CECStatus::Ok);
+        assert_eq!((piece.piece_type, piece.color), (3, 2));
+        let mut error = CECMoveError::GameOver;
+        assert_eq!(cec_result_move_error(result, &mut error), CECStatus::NotAvailable);
+        cec_result_free(result);
+
+        let result = run(engine, "GAME 1 RED MOVE (0,0) TO (0,1)");
+        assert_eq!(kind(result), CECResultKind::MoveFailed);
+        assert_eq!(reason(result), CECErrorReason::MoveRejected);
+        assert_eq!(cec_result_move_error(result, &mut error), CECStatus::Ok);
+        assert_eq!(error, CECMoveError::NotYourTurn);
+        assert_eq!(cec_result_captured_piece(result, &mut piece), CECStatus::NotAvailable);
+        cec_result_free(result);
+
+        let result = run(engine, "GET GAME 1");
+        assert_eq!(kind(result), CECResultKind::GameStatus);
+        assert_eq!(cec_result_status(result, &mut status), CECStatus::Ok);
+        assert_eq!(status, CECGameStatus::Playing);
+        assert_eq!(cec_result_status(result, ptr::null_mut()), CECStatus::NullPointer);
+        cec_result_free(result);
+
+        let result = run(engine, "GAME 7 UNDO");
+        assert_eq!(kind(result), CECResultKind::GameNotFound);
+        assert_eq!(reason(result), CECErrorReason::GameNotFound);
+        assert_eq!(cec_result_game_id(result, &mut game_id), CECStatus::Ok);
+        assert_eq!(game_id, 7);
+        cec_result_free(result);
+
+        let result = run(engine, "LIST GAMES");
+        assert_eq!(kind(result), CECResultKind::GameList);
+        assert_eq!(cec_result_game_id(result, &mut game_id), CECStatus::NotAvailable);
+        cec_result_free(result);
+
+        let result = run(engine, "GAME 1 JUMP");
+        let (mut start, mut end) = (0, 0);
+        assert_eq!(kind(result), CECResultKind::InvalidCommand);
+        assert_eq!(reason(result), CECErrorReason::InvalidCommand);
+        assert_eq!(cec_result_parse_error_span(result, &mut start, &mut end), CECStatus::Ok);
+        assert_eq!((start, end), (7, 11));
+        cec_result_free(result);
+
+        let mut kind = CECResultKind::GameCreated;
+        assert_eq!(cec_result_kind(ptr::null(), &mut kind), CECStatus::NullPointer);
+        assert_eq!(last_error(), "result is null");
+        cec_engine_free(engine);
+    }
+}
+
+#[test]
+fn result_json_uses_two_call_length_protocol() {
+    let engine = cec_engine_new();
+    execute(engine, "CREATE GAME");
+    let result = run(engine, "GET GAME 1");
+    unsafe {
+        // 第一次只查询长度
+        let mut length = 0;
+        assert_eq!(cec_result_to_json(result, ptr::null_mut(), 0, &mut length), CECStatus::Ok);
+        assert!(length > 256);
+
+        // 缓冲区放不下结尾的'\0'时不写入内容
+        let mut buffer = vec![b'x' as libc::c_char; length];
+        let mut reported = 0;
+        assert_eq!(cec_result_to_json(result, buffer.as_mut_ptr(), buffer.len(), &mut reported), CECStatus::BufferTooSmall);
+        assert_eq!(reported, length);
+        assert!(buffer.iter().all(|&byte| byte == b'x' as libc::c_char));
+        assert_eq!(last_error(), format!("{} bytes required", length + 1));
+
+        let mut buffer = vec![0 as libc::c_char; length + 1];
+        assert_eq!(cec_result_to_json(result, buffer.as_mut_ptr(), buffer.len(), &mut reported), CECStatus::Ok);
+        let json = CStr::from_ptr(buffer.as_ptr()).to_str().unwrap();
+        assert_eq!(json.len(), length);
+        let json: serde_json::Value = serde_json::from_str(json).unwrap();
+        assert_eq!(json["kind"], "GAME_STATUS");
+        assert_eq!(json["payload"]["game_id"], 1);
+
+        assert_eq!(cec_result_to_json(result, buffer.as_mut_ptr(), buffer.len(), ptr::null_mut()), CECStatus::NullPointer);
+        cec_result_free(result);
+        cec_engine_free(engine);
+    }
+}
+
+#[test]
+fn result_to_string_truncates() {
+    let engine = cec_engine_new();
+    let result = run(engine, "CREATE GAME");
+    unsafe {
+        let mut buffer = [0 as libc::c_char; 8];
+        assert_eq!(cec_result_to_string(result, buffer.as_mut_ptr(), buffer.len()), CECStatus::Ok);
+        assert_eq!(CStr::from_ptr(buffer.as_ptr()).to_str().unwrap(), "GAME_CR");
+        assert_eq!(cec_result_to_string(result, buffer.as_mut_ptr(), 0), CECStatus::BufferTooSmall);
+        cec_result_free(result);
+        cec_engine_free(engine);
+    }
+}
+
+#[test]
+fn watch_events_and_draw_offer() {
+    let engine = cec_engine_new();
+    execute(engine, "CREATE GAME");
+    execute(engine, "GAME 1 RED MOVE (7,2) TO (4,2)");
+
+    unsafe {
+        let mut length = 0;
+        assert_eq!(cec_game_watch(engine, 1, ptr::null_mut(), 0, &mut length), CECStatus::Ok);
+        let mut buffer = vec![0 as libc::c_char; length + 1];
+        assert_eq!(cec_game_watch(engine, 1, buffer.as_mut_ptr(), buffer.len(), &mut length), CECStatus::Ok);
+        let json: serde_json::Value =
+            serde_json::from_str(CStr::from_ptr(buffer.as_ptr()).to_str().unwrap()).unwrap();
+        assert_eq!(json["last_seq"], 1);
+        assert_eq!(json["game"]["move_count"], 1);
+
+        assert_eq!(cec_game_offer_draw(engine, 1, 2), CECStatus::Ok);
+        assert_eq!(cec_game_offer_draw(engine, 1, 3), CECStatus::InvalidArgument);
+        assert_eq!(cec_game_offer_draw(engine, 9, 1), CECStatus::GameNotFound);
+
+        let mut buffer = vec![0 as libc::c_char; 256];
+        assert_eq!(cec_game_events_since(engine, 1, 1, buffer.as_mut_ptr(), buffer.len(), &mut length), CECStatus::Ok);
+        let json = CStr::from_ptr(buffer.as_ptr()).to_str().unwrap();
+        assert_eq!(json, r#"[{"seq":2,"kind":"DRAW_OFFERED","color":"Black"}]"#);
+        assert_eq!(length, json.len());
+        assert_eq!(cec_game_events_since(engine, 9, 0, buffer.as_mut_ptr(), buffer.len(), &mut length), CECStatus::GameNotFound);
+
+        cec_engine_free(engine);
+    }
+}
+
+// 与 CECPiece 的编码一致：piece_type 0-6 依次为 将 士 象 马 车 炮 兵，7 为空
+fn piece(piece_type: u8, color: u8) -> CECPiece {
+    CECPiece { piece_type, color }
+}
+
+#[test]
+fn board_inspection_reads_game_state() {
+    let engine = cec_engine_new();
+    execute(engine, "CREATE GAME");
+    execute(engine, "GAME 1 RED MOVE (1,2) TO (1,9)");
+    unsafe {
+        let mut found = piece(7, 0);
+        assert_eq!(cec_game_get_piece(engine, 1, 1, 9, &mut found), CECStatus::Ok);
+        assert_eq!(found, piece(5, 1));
+        assert_eq!(cec_game_get_piece(engine, 1, 1, 2, &mut found), CECStatus::Ok);
+        assert_eq!(found, piece(7, 0));
+        assert_eq!(cec_game_get_piece(engine, 1, 9, 0, &mut found), CECStatus::InvalidArgument);
+        assert_eq!(last_error(), "(9,0) is off the board");
+        assert_eq!(cec_game_get_piece(engine, 2, 0, 0, &mut found), CECStatus::GameNotFound);
+        assert_eq!(cec_game_get_piece(ptr::null(), 1, 0, 0, &mut found), CECStatus::NullPointer);
+
+        // 下标为 y * 9 + x
+        let mut board = [piece(0, 0); 90];
+        assert_eq!(cec_game_get_board(engine, 1, board.as_mut_ptr()), CECStatus::Ok);
+        assert_eq!(board[4], piece(0, 1));
+        assert_eq!(board[9 * 9 + 4], piece(0, 2));
+        assert_eq!(board[9 * 9 + 1], piece(5, 1));
+        assert_eq!(board[2 * 9 + 1], piece(7, 0));
+        assert_eq!(board.iter().filter(|p| p.piece_type != 7).count(), 31);
+        assert_eq!(cec_game_get_board(engine, 1, ptr::null_mut()), CECStatus::NullPointer);
+
+        let mut color = 0;
+        assert_eq!(cec_game_side_to_move(engine, 1, &mut color), CECStatus::Ok);
+        assert_eq!(color, 2);
+
+        let mut in_check = false;
+        assert_eq!(cec_game_is_in_check(engine, 1, &mut in_check), CECStatus::Ok);
+        assert!(!in_check);
+
+        let mut length = 0;
+        assert_eq!(cec_game_history_length(engine, 1, &mut length), CECStatus::Ok);
+        assert_eq!(length, 1);
+        cec_engine_free(engine);
+    }
+}
+
+#[test]
+fn check_is_reported_for_side_to_move() {
+    let engine = cec_engine_new();
+    execute(engine, "CREATE GAME");
+    // 五步杀，黑方被将死
+    for command in [
+        "GAME 1 RED MOVE (1,2) TO (2,2)",
+        "GAME 1 BLACK MOVE (1,7) TO (2,7)",
+        "GAME 1 RED MOVE (2,2) TO (2,6)",
+        "GAME 1 BLACK MOVE (5,9) TO (4,8)",
+        "GAME 1 RED MOVE (2,6) TO (2,9)",
+    ] {
+        execute(engine, command);
+    }
+    unsafe {
+        let mut in_check = false;
+        assert_eq!(cec_game_is_in_check(engine, 1, &mut in_check), CECStatus::Ok);
+        assert!(in_check);
+
+        // 对局结束后没有合法走法
+        let mut count = 1;
+        assert_eq!(cec_game_legal_moves_from(engine, 1, 4, 9, ptr::null_mut(), 0, &mut count), CECStatus::Ok);
+        assert_eq!(count, 0);
+        cec_engine_free(engine);
+    }
+}
+
+#[test]
+fn legal_moves_from_reports_count_and_capacity() {
+    let engine = cec_engine_new();
+    execute(engine, "CREATE GAME");
+    unsafe {
+        // 只查询数量
+        let mut count = 0;
+        assert_eq!(cec_game_legal_moves_from(engine, 1, 1, 0, ptr::null_mut(), 0, &mut count), CECStatus::Ok);
+        assert_eq!(count, 2);
+
+        let mut targets = [CECPosition { x: 0, y: 0 }; 2];
+        assert_eq!(cec_game_legal_moves_from(engine, 1, 1, 0, targets.as_mut_ptr(), 1, &mut count), CECStatus::BufferTooSmall);
+        assert_eq!(count, 2);
+        assert_eq!(cec_game_legal_moves_from(engine, 1, 1, 0, targets.as_mut_ptr(), 2, &mut count), CECStatus::Ok);
+        let mut targets = targets.to_vec();
+        targets.sort_by_key(|target| target.x);
+        assert_eq!(targets, vec![CECPosition { x: 0, y: 2 }, CECPosition { x: 2, y: 2 }]);
+
+        // 不是当前走棋方的棋子、空位都没有合法目标
+        let mut targets = [CECPosition { x: 0, y: 0 }; 17];
+        assert_eq!(cec_game_legal_moves_from(engine, 1, 1, 9, targets.as_mut_ptr(), 17, &mut count), CECStatus::Ok);
+        assert_eq!(count, 0);
+        assert_eq!(cec_game_legal_moves_from(engine, 1, 4, 4, targets.as_mut_ptr(), 17, &mut count), CECStatus::Ok);
+        assert_eq!(count, 0);
+        assert_eq!(cec_game_legal_moves_from(engine, 1, 0, 10, targets.as_mut_ptr(), 17, &mut count), CECStatus::InvalidArgument);
+        assert_eq!(cec_game_legal_moves_from(engine, 1, 1, 0, ptr::null_mut(), 17, &mut count), CECStatus::NullPointer);
+        cec_engine_free(engine);
+    }
+}
+
+// 走子时 panic 的回调
+extern "C-unwind" fn panicking_callback(event: *const CECEvent, _user_data: *mut libc::c_void) {
+    if unsafe { (*event).kind } == CECEventKind::MoveMade {
+        panic!("listener exploded");
+    }
+}
+
+#[test]
+fn panic_is_caught_and_poisons_the_engine() {
+    let engine = cec_engine_new();
+    execute(engine, "CREATE GAME");
+    unsafe {
+        let mut listener_id = 0;
+        assert_eq!(cec_engine_add_listener(engine, Some(panicking_callback), ptr::null_mut(), &mut listener_id), CECStatus::Ok);
+
+        let command = CString::new("GAME 1 RED MOVE (1,2) TO (2,2)").unwrap();
+        let mut result = ptr::null_mut();
+        assert_eq!(cec_engine_execute(engine, command.as_ptr(), &mut result), CECStatus::Panic);
+        assert!(result.is_null());
+        assert_eq!(last_error(), "panic: listener exploded");
+
+        // panic 发生在持有锁时，之后的调用都返回 EnginePoisoned
+        let mut color = 0;
+        assert_eq!(cec_game_side_to_move(engine, 1, &mut color), CECStatus::EnginePoisoned);
+        assert!(last_error().starts_with("engine panicked while in use"));
+        assert_eq!(cec_engine_remove_listener(engine, listener_id), CECStatus::EnginePoisoned);
+        cec_engine_free(engine);
+    }
+}
+
+// 回调中再次调用引擎，记录返回的错误码
+struct Reentry {
+    engine: *mut CECEngine,
+    statuses: Vec<CECStatus>,
+}
+
+extern "C-unwind" fn reentering_callback(_event: *const CECEvent, user_data: *mut libc::c_void) {
+    let reentry = unsafe { &mut *(user_data as *mut Reentry) };
+    let mut color = 0;
+    let status = unsafe { cec_game_side_to_move(reentry.engine, 1, &mut color) };
+    reentry.statuses.push(status);
+}
+
+#[test]
+fn callback_reentering_the_engine_is_rejected() {
+    let engine = cec_engine_new();
+    let mut reentry = Reentry { engine, statuses: Vec::new() };
+    unsafe {
+        let mut listener_id = 0;
+        let user_data = &mut reentry as *mut Reentry as *mut libc::c_void;
+        assert_eq!(cec_engine_add_listener(engine, Some(reentering_callback), user_data, &mut listener_id), CECStatus::Ok);
+        execute(engine, "CREATE GAME");
+        assert_eq!(reentry.statuses, vec![CECStatus::Reentrant]);
+        assert!(last_error().contains("already in use by this thread"));
+
+        // 回调返回后锁已释放，引擎照常可用
+        let mut color = 0;
+        assert_eq!(cec_game_side_to_move(engine, 1, &mut color), CECStatus::Ok);
+        assert_eq!(color, 1);
+        assert_eq!(cec_engine_remove_listener(engine, listener_id), CECStatus::Ok);
+        execute(engine, "CREATE GAME");
+        assert_eq!(reentry.statuses.len(), 1);
+        cec_engine_free(engine);
+    }
+}
+
+#[test]
+fn last_error_is_null_until_a_call_fails() {
+    // 每个测试在自己的线程中运行，错误信息按线程保存
+    std::thread::spawn(|| {
+        assert!(cec_last_error().is_null());
+        let mut color = 0;
+        assert_eq!(unsafe { cec_game_side_to_move(ptr::null(), 1, &mut color) }, CECStatus::NullPointer);
+        assert_eq!(last_error(), "engine is null");
+    })
+    .join()
+    .unwrap();
+}
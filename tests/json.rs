@@ -0,0 +1,99 @@
+use chinese_chess_engine::{Engine, Language, OutputFormat, JSON_SCHEMA_VERSION};
+
+// 结果按引擎的输出格式和语言输出
+fn json_engine(language: Language) -> Engine {
+    let mut engine = Engine::new();
+    engine.set_output_format(OutputFormat::Json);
+    engine.set_language(language);
+    engine
+}
+
+#[test]
+fn success_envelopes_match_golden() {
+    let mut engine = json_engine(Language::English);
+    assert_eq!(
+        engine.execute_to_string("CREATE GAME"),
+        r#"{"version":1,"ok":true,"kind":"GAME_CREATED","game_id":1,"error":null,"payload":null}"#
+    );
+    assert_eq!(
+        engine.execute_to_string("GAME 1 RED MOVE (1,2) TO (1,9)"),
+        r#"{"version":1,"ok":true,"kind":"MOVE_SUCCESS","game_id":1,"error":null,"payload":{"captured":{"piece_type":"Horse","color":"Black"}}}"#
+    );
+    assert_eq!(
+        engine.execute_to_string("GAME 1 BLACK MOVE (0,9) TO (0,8)"),
+        r#"{"version":1,"ok":true,"kind":"MOVE_SUCCESS","game_id":1,"error":null,"payload":{"captured":null}}"#
+    );
+}
+
+#[test]
+fn error_envelopes_match_golden_in_both_languages() {
+    let mut english = json_engine(Language::English);
+    let mut chinese = json_engine(Language::Chinese);
+    for engine in [&mut english, &mut chinese] {
+        engine.execute("CREATE GAME");
+    }
+
+    assert_eq!(
+        english.execute_to_string("GET GAME 9"),
+        r#"{"version":1,"ok":false,"kind":"GAME_NOT_FOUND","game_id":9,"error":{"code":"GAME_NOT_FOUND","message":"game 9 does not exist"},"payload":null}"#
+    );
+    assert_eq!(
+        chinese.execute_to_string("GET GAME 9"),
+        r#"{"version":1,"ok":false,"kind":"GAME_NOT_FOUND","game_id":9,"error":{"code":"GAME_NOT_FOUND","message":"游戏 9 不存在"},"payload":null}"#
+    );
+
+    // reason 为走法错误码，不随语言变化
+    assert_eq!(
+        english.execute_to_string("GAME 1 BLACK MOVE (1,7) TO (1,3)"),
+        r#"{"version":1,"ok":false,"kind":"MOVE_FAILED","game_id":1,"error":{"code":"MOVE_FAILED","reason":"NOT_YOUR_TURN","message":"move rejected in game 1: it is not this side's turn"},"payload":null}"#
+    );
+    assert_eq!(
+        chinese.execute_to_string("GAME 1 BLACK MOVE (1,7) TO (1,3)"),
+        r#"{"version":1,"ok":false,"kind":"MOVE_FAILED","game_id":1,"error":{"code":"MOVE_FAILED","reason":"NOT_YOUR_TURN","message":"游戏 1 的走法不合法：还没有轮到这一方走棋"},"payload":null}"#
+    );
+
+    assert_eq!(
+        english.execute_to_string("GAME 1 JUMP"),
+        r#"{"version":1,"ok":false,"kind":"INVALID_COMMAND","game_id":null,"error":{"code":"INVALID_COMMAND","message":"expected RED, BLACK or UNDO after 1, found JUMP","span":[7,11],"expected":["RED","BLACK","UNDO"]},"payload":null}"#
+    );
+    assert_eq!(
+        chinese.execute_to_string("GAME 1 JUMP"),
+        r#"{"version":1,"ok":false,"kind":"INVALID_COMMAND","game_id":null,"error":{"code":"INVALID_COMMAND","message":"1 之后应为 RED、BLACK 或 UNDO，实际为 JUMP","span":[7,11],"expected":["RED","BLACK","UNDO"]},"payload":null}"#
+    );
+}
+
+#[test]
+fn every_envelope_carries_the_schema_fields() {
+    let mut engine = Engine::new();
+    let commands = [
+        "CREATE GAME",
+        "JOIN GAME 1 张三 RED",
+        "GAME 1 RED MOVE (7,2) TO (4,2)",
+        "GAME 1 UNDO",
+        "GET GAME 1",
+        "GET GAME 1 HISTORY",
+        "GET GAME 1 BOARD",
+        "GET GAME 1 MOVES",
+        "LIST GAMES",
+        "WATCH GAME 1",
+        "GET GAME 1 EVENTS SINCE 0",
+        "GAME 1 RED OFFER DRAW",
+        "DELETE GAME 1",
+        "GAME 1 UNDO",
+        "NONSENSE",
+    ];
+    for command in commands {
+        let result = engine.execute(command);
+        let json: serde_json::Value = serde_json::from_str(&result.to_json()).unwrap();
+        let keys: Vec<&str> = json.as_object().unwrap().keys().map(String::as_str).collect();
+        assert_eq!(keys.len(), 6, "{}", command);
+        assert_eq!(json["version"], JSON_SCHEMA_VERSION, "{}", command);
+        assert_eq!(json["ok"], result.is_ok(), "{}", command);
+        assert_eq!(json["kind"], result.kind(), "{}", command);
+        assert_eq!(json["game_id"], serde_json::json!(result.game_id()), "{}", command);
+        assert_eq!(json["error"].is_null(), result.is_ok(), "{}", command);
+        if !result.is_ok() {
+            assert_eq!(json["error"]["code"], result.kind(), "{}", command);
+        }
+    }
+}
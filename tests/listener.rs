@@ -0,0 +1,111 @@
+use std::sync::{Arc, Mutex};
+
+use chinese_chess_engine::{Engine, GameListener, GameStatus, MoveRecord, Player};
+
+// 把收到的回调按顺序记成字符串
+#[derive(Default)]
+struct Recorder {
+    calls: Mutex<Vec<String>>,
+}
+
+impl Recorder {
+    fn push(&self, call: String) {
+        self.calls.lock().unwrap().push(call);
+    }
+
+    fn take(&self) -> Vec<String> {
+        std::mem::take(&mut *self.calls.lock().unwrap())
+    }
+}
+
+impl GameListener for Recorder {
+    fn on_game_created(&self, game_id: u32) {
+        self.push(format!("created {game_id}"));
+    }
+
+    fn on_player_joined(&self, game_id: u32, player: &Player) {
+        self.push(format!("joined {game_id} {} {:?}", player.name, player.color));
+    }
+
+    fn on_move_made(&self, game_id: u32, record: &MoveRecord, check: bool) {
+        self.push(format!("moved {game_id} {} {check}", record.mv.to_iccs()));
+    }
+
+    fn on_undo(&self, game_id: u32, record: &MoveRecord) {
+        self.push(format!("undo {game_id} {}", record.mv.to_iccs()));
+    }
+
+    fn on_game_finished(&self, game_id: u32, status: &GameStatus) {
+        self.push(format!("finished {game_id} {status:?}"));
+    }
+}
+
+// 五步杀，最后一步将死黑方
+const MATE: [&str; 5] = [
+    "GAME 1 RED MOVE (1,2) TO (2,2)",
+    "GAME 1 BLACK MOVE (1,7) TO (2,7)",
+    "GAME 1 RED MOVE (2,2) TO (2,6)",
+    "GAME 1 BLACK MOVE (5,9) TO (4,8)",
+    "GAME 1 RED MOVE (2,6) TO (2,9)",
+];
+
+#[test]
+fn callbacks_arrive_in_order() {
+    let mut engine = Engine::new();
+    let recorder = Arc::new(Recorder::default());
+    engine.add_listener(recorder.clone());
+
+    engine.execute("CREATE GAME");
+    engine.execute("JOIN GAME 1 张三 RED");
+    for command in &MATE[..4] {
+        engine.execute(command);
+    }
+    engine.execute("GAME 1 UNDO");
+    engine.execute(MATE[3]);
+    engine.execute(MATE[4]);
+
+    assert_eq!(recorder.take(), vec![
+        "created 1",
+        "joined 1 张三 Red",
+        "moved 1 b2c2 false",
+        "moved 1 b7c7 false",
+        "moved 1 c2c6 false",
+        "moved 1 f9e8 false",
+        "undo 1 f9e8",
+        "moved 1 f9e8 false",
+        "moved 1 c6c9 true",
+        "finished 1 RedWon",
+    ]);
+}
+
+#[test]
+fn rejected_commands_do_not_notify() {
+    let mut engine = Engine::new();
+    let recorder = Arc::new(Recorder::default());
+    engine.add_listener(recorder.clone());
+
+    engine.execute("CREATE GAME");
+    recorder.take();
+    // 黑方抢先走子、空悔棋、不存在的游戏都不触发回调
+    engine.execute("GAME 1 BLACK MOVE (1,7) TO (2,7)");
+    engine.execute("GAME 1 UNDO");
+    engine.execute("GAME 2 RED MOVE (1,2) TO (2,2)");
+    assert!(recorder.take().is_empty());
+}
+
+#[test]
+fn removed_listener_stops_receiving() {
+    let mut engine = Engine::new();
+    let first = Arc::new(Recorder::default());
+    let second = Arc::new(Recorder::default());
+    let first_id = engine.add_listener(first.clone());
+    engine.add_listener(second.clone());
+
+    engine.execute("CREATE GAME");
+    assert!(engine.remove_listener(first_id));
+    assert!(!engine.remove_listener(first_id));
+    engine.execute(MATE[0]);
+
+    assert_eq!(first.take(), vec!["created 1"]);
+    assert_eq!(second.take(), vec!["created 1", "moved 1 b2c2 false"]);
+}
@@ -0,0 +1,114 @@
+use chinese_chess_engine::core::perft::PerftPosition;
+use chinese_chess_engine::{Board, Color, Move, Position};
+
+// 固定种子的随机数，使随机对局可以重现
+struct Rng(u64);
+
+impl Rng {
+    fn next(&mut self, bound: usize) -> usize {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 7;
+        self.0 ^= self.0 << 17;
+        (self.0 % bound as u64) as usize
+    }
+}
+
+fn sorted(moves: &[Move]) -> Vec<(u8, u8)> {
+    let mut squares: Vec<(u8, u8)> = moves.iter().map(|mv| (mv.from_index(), mv.to_index())).collect();
+    squares.sort();
+    squares
+}
+
+// 从 perft.epd 的局面出发随机走子，得到各种局面
+fn random_positions() -> Vec<(Board, Color)> {
+    let mut rng = Rng(0x2545_f491_4f6c_dd1d);
+    let mut positions = Vec::new();
+
+    for start in PerftPosition::parse_epd(include_str!("perft.epd")).unwrap() {
+        for _ in 0..10 {
+            let (mut board, mut color) = Board::from_fen(&start.fen).unwrap();
+            for _ in 0..80 {
+                let moves = board.legal_moves(color);
+                if moves.is_empty() {
+                    break;
+                }
+                positions.push((board.clone(), color));
+                board.make_move(moves[rng.next(moves.len())]);
+                color = color.opponent();
+            }
+        }
+    }
+    positions
+}
+
+#[test]
+fn evasions_match_filtered_legal_moves() {
+    let mut checks = 0;
+    for (mut board, color) in random_positions() {
+        let legal = board.legal_moves(color);
+        let evasions = board.generate_evasions(color);
+        if board.is_in_check(color) {
+            checks += 1;
+            assert_eq!(sorted(&evasions), sorted(&legal), "{}", board.to_fen(color));
+        } else {
+            assert!(evasions.is_empty(), "{}", board.to_fen(color));
+        }
+    }
+    assert!(checks > 20, "only {} positions in check", checks);
+}
+
+#[test]
+fn captures_match_filtered_legal_moves() {
+    for (mut board, color) in random_positions() {
+        let legal = board.legal_moves(color);
+        let expected: Vec<Move> = legal.iter()
+            .copied()
+            .filter(|mv| board.get_piece(mv.to()).color == color.opponent())
+            .collect();
+        let captures = board.generate_captures(color);
+        assert_eq!(sorted(&captures), sorted(&expected), "{}", board.to_fen(color));
+
+        // MVV-LVA：被吃棋子价值不增，相同时吃子棋子价值不减
+        let keys: Vec<(i32, i32)> = captures.iter()
+            .map(|mv| (-board.get_piece(mv.to()).piece_type.value(), board.get_piece(mv.from()).piece_type.value()))
+            .collect();
+        assert!(keys.windows(2).all(|pair| pair[0] <= pair[1]), "{}", board.to_fen(color));
+    }
+}
+
+#[test]
+fn evasions_include_screen_removal_and_leg_block() {
+    // 黑炮隔着红马将军，移开炮架即可应将
+    let (mut board, color) = Board::from_fen("3k5/9/9/4c4/9/9/9/4N4/9/4K4 w").unwrap();
+    let evasions = board.generate_evasions(color);
+    assert!(evasions.contains(&Move::from_iccs("e2d4").unwrap()));
+    assert_eq!(sorted(&evasions), sorted(&board.legal_moves(color)));
+
+    // 黑马将军，红车塞马腿
+    let (mut board, color) = Board::from_fen("3k5/9/9/9/9/9/9/3n5/R8/4K4 w").unwrap();
+    let evasions = board.generate_evasions(color);
+    assert!(evasions.contains(&Move::from_iccs("a1d1").unwrap()));
+    assert_eq!(sorted(&evasions), sorted(&board.legal_moves(color)));
+}
+
+#[test]
+fn legal_moves_from_matches_all_legal_moves_and_leaves_board_unchanged() {
+    for (mut board, color) in random_positions() {
+        let hash = board.get_hash();
+        let fen = board.to_fen(color);
+        let all = board.legal_moves(color);
+
+        let mut from_each = Vec::new();
+        for x in 0..9 {
+            for y in 0..10 {
+                let from = Position::try_new(x, y).unwrap();
+                if board.get_piece(from).color == color {
+                    from_each.extend(board.get_legal_moves(from));
+                }
+            }
+        }
+        assert_eq!(sorted(&from_each), sorted(&all), "{}", fen);
+        assert_eq!(board.get_hash(), hash);
+        assert_eq!(board.to_fen(color), fen);
+    }
+}
@@ -0,0 +1,51 @@
+use chinese_chess_engine::{Move, MoveError, MoveList, Position};
+
+#[test]
+fn positions_outside_the_board_are_rejected() {
+    // 16 * 16 个坐标中只有 90 个在棋盘上，并且互不相同
+    let mut seen = std::collections::HashSet::new();
+    for x in 0..16 {
+        for y in 0..16 {
+            match Position::try_new(x, y) {
+                Some(pos) => {
+                    assert!(x < 9 && y < 10);
+                    assert_eq!((pos.x(), pos.y()), (x, y));
+                    assert_eq!(Position::from_index(pos.index()), Some(pos));
+                    assert!(seen.insert(pos));
+                }
+                None => assert!(x >= 9 || y >= 10),
+            }
+        }
+    }
+    assert_eq!(seen.len(), 90);
+    assert_eq!(Position::try_new(16, 0), None);
+    assert_eq!(Position::try_new(0, 16), None);
+    assert_eq!(Position::from_index(90), None);
+}
+
+#[test]
+fn moves_serialize_as_checked_integers() {
+    // b2e2：起点 1 + 2 * 9 = 19，终点 4 + 2 * 9 = 22
+    let mv = Move::from_iccs("b2e2").unwrap();
+    let data = 19 | 22 << 7;
+    assert_eq!(u16::from(mv), data);
+    assert_eq!(serde_json::to_string(&mv).unwrap(), data.to_string());
+    assert_eq!(serde_json::from_str::<Move>(&data.to_string()).unwrap(), mv);
+
+    // 编号 90 以上或高两位不为 0 都不是合法的走法
+    assert_eq!(Move::try_from(90 | 22 << 7), Err(MoveError::OutOfBounds));
+    assert_eq!(Move::try_from(19 | 127 << 7), Err(MoveError::OutOfBounds));
+    assert_eq!(Move::try_from(data | 1 << 14), Err(MoveError::OutOfBounds));
+    assert!(serde_json::from_str::<Move>(&(data | 1 << 15).to_string()).is_err());
+}
+
+#[test]
+#[should_panic(expected = "move list overflow")]
+fn move_list_overflow_panics() {
+    let mv = Move::from_iccs("b2e2").unwrap();
+    let mut list = MoveList::new();
+    // 容量为 128
+    for _ in 0..=128 {
+        list.push(mv);
+    }
+}
@@ -0,0 +1,134 @@
+use chinese_chess_engine::parser::lexer::{Keywords, LexError, Lexer, Token};
+use chinese_chess_engine::{ASTNode, Color, GameFilter, Move, Notation, ParseError, Parser, Position};
+
+fn parse_error(command: &str) -> ParseError {
+    Parser::new(command).parse().unwrap_err()
+}
+
+#[test]
+fn caret_points_at_token_after_full_width_text() {
+    let error = parse_error("加入 游戏 1 张三 红方 多余");
+    assert_eq!(error.message(), "expected end of input after RED, found 多余");
+    // 中文每个字符占两列
+    assert_eq!(error.annotated(), format!("加入 游戏 1 张三 红方 多余\n{}^^^^", " ".repeat(22)));
+}
+
+#[test]
+fn caret_for_missing_token_points_past_the_end() {
+    let error = parse_error("GAME 1 RED MOVE (0,0) TO");
+    assert_eq!(error.span, 24..24);
+    assert_eq!(error.annotated(), format!("GAME 1 RED MOVE (0,0) TO\n{}^", " ".repeat(24)));
+}
+
+#[test]
+fn caret_keeps_tabs() {
+    let error = parse_error("\tGAME 1 RED MOVE (0,0) (1,1)");
+    assert_eq!(error.annotated(), format!("\tGAME 1 RED MOVE (0,0) (1,1)\n\t{}^^^^^", " ".repeat(22)));
+}
+
+fn tokens(command: &str) -> Vec<Token> {
+    Lexer::new(command).tokenize()
+}
+
+#[test]
+fn unquoted_chinese_name_is_an_identifier() {
+    assert_eq!(tokens("JOIN GAME 1 张三 RED"), vec![
+        Token::Keyword(Keywords::Join),
+        Token::Keyword(Keywords::Game),
+        Token::Number(1),
+        Token::Identifier("张三".to_string()),
+        Token::Red,
+    ]);
+    assert!(matches!(
+        Parser::new("JOIN GAME 1 张三 RED").parse(),
+        Ok(ASTNode::JoinGame { game_id: 1, ref name, color: Color::Red }) if name == "张三"
+    ));
+}
+
+#[test]
+fn quoted_name_handles_escapes() {
+    let command = r#"JOIN GAME 1 "张 \"三\"\\" BLACK"#;
+    assert_eq!(tokens(command)[3], Token::Str("张 \"三\"\\".to_string()));
+    assert!(matches!(
+        Parser::new(command).parse(),
+        Ok(ASTNode::JoinGame { ref name, color: Color::Black, .. }) if name == "张 \"三\"\\"
+    ));
+
+    assert_eq!(tokens(r#""a\qb""#), vec![Token::Error(LexError::BadEscape('q'))]);
+}
+
+#[test]
+fn negative_and_out_of_range_numbers_are_errors() {
+    let error = parse_error("GAME 1 RED MOVE (0,-1) TO (0,1)");
+    assert_eq!(error.found, Token::Error(LexError::NegativeNumber("-1".to_string())));
+    assert_eq!(error.span, 16..22);
+
+    let error = parse_error("GAME 1 RED MOVE (0,300) TO (0,1)");
+    assert_eq!(error.found, Token::Error(LexError::NumberOutOfRange("300".to_string())));
+    assert_eq!(error.span, 16..23);
+
+    let error = parse_error("GET GAME 4294967296");
+    assert_eq!(error.found, Token::Error(LexError::NumberOutOfRange("4294967296".to_string())));
+    assert_eq!(error.message(), "number 4294967296 is out of range");
+    assert!(matches!(Parser::new("GET GAME 4294967295").parse(), Ok(ASTNode::GetGame { game_id: 4294967295 })));
+}
+
+#[test]
+fn unterminated_string_is_reported() {
+    let error = parse_error(r#"JOIN GAME 1 "张三 RED"#);
+    assert_eq!(error.found, Token::Error(LexError::UnterminatedString));
+    assert_eq!(error.span, 12..23);
+    assert_eq!(error.message(), "unterminated string");
+}
+
+#[test]
+fn comments_run_to_end_of_line() {
+    assert_eq!(tokens("CREATE GAME # 创建 GAME 1"), vec![
+        Token::Keyword(Keywords::Create),
+        Token::Keyword(Keywords::Game),
+    ]);
+    assert!(tokens("# 只有注释").is_empty());
+    // 字符串中的 # 不是注释
+    assert_eq!(tokens(r#""a#b""#), vec![Token::Str("a#b".to_string())]);
+}
+
+fn parse(command: &str) -> ASTNode {
+    Parser::new(command).parse().unwrap_or_else(|error| panic!("{}: {}", command, error))
+}
+
+#[test]
+fn query_commands_parse() {
+    assert!(matches!(parse("LIST GAMES"), ASTNode::ListGames { filter: GameFilter::All }));
+    assert!(matches!(parse("LIST GAMES FINISHED"), ASTNode::ListGames { filter: GameFilter::Finished }));
+    assert!(matches!(parse("DELETE GAME 2"), ASTNode::DeleteGame { game_id: 2 }));
+    assert!(matches!(parse("GET GAME 1 HISTORY"), ASTNode::GetHistory { game_id: 1, notation: Notation::Iccs }));
+    assert!(matches!(parse("GET GAME 1 HISTORY WXF"), ASTNode::GetHistory { game_id: 1, notation: Notation::Wxf }));
+    assert!(matches!(parse("GET GAME 1 BOARD"), ASTNode::GetBoard { game_id: 1 }));
+    assert!(matches!(parse("GET GAME 1 MOVES"), ASTNode::GetMoves { game_id: 1, from: None }));
+    assert!(matches!(parse("GET GAME 1 MOVES FROM (1,0)"), ASTNode::GetMoves { game_id: 1, from: Some(from) } if from == Position::try_new(1, 0).unwrap()));
+}
+
+#[test]
+fn move_command_builds_a_move() {
+    let expected = Move::from_iccs("b2e2").unwrap();
+    assert!(matches!(parse("GAME 1 RED MOVE (1,2) TO (4,2)"), ASTNode::Move { game_id: 1, color: Color::Red, mv } if mv == expected));
+}
+
+#[test]
+fn off_board_coordinates_are_parse_errors() {
+    let error = parse_error("GAME 1 RED MOVE (9,0) TO (1,1)");
+    assert_eq!(error.span, 16..21);
+    assert_eq!(error.message(), "expected coordinate on the board after MOVE, found (9,0)");
+    assert_eq!(error.description(), "MOVE 之后应为 棋盘上的坐标，实际为 (9,0)");
+
+    let error = parse_error("GET GAME 1 MOVES FROM (0,10)");
+    assert_eq!(error.message(), "expected coordinate on the board after FROM, found (0,10)");
+}
+
+#[test]
+fn readme_undo_and_status_forms_parse() {
+    assert!(matches!(parse("GAME 1 UNDO"), ASTNode::Undo { game_id: 1 }));
+    assert!(matches!(parse("UNDO GAME 1"), ASTNode::Undo { game_id: 1 }));
+    assert!(matches!(parse("GET GAME 1 STATUS"), ASTNode::GetGame { game_id: 1 }));
+    assert!(matches!(parse("GET GAME 1"), ASTNode::GetGame { game_id: 1 }));
+}
@@ -0,0 +1,43 @@
+use chinese_chess_engine::core::perft::PerftPosition;
+use chinese_chess_engine::{Board, Color};
+
+// 调试构建较慢，只检查局面数不超过此值的深度，完整检查见 perft --epd
+const MAX_NODES: u64 = 400_000;
+
+fn positions() -> Vec<PerftPosition> {
+    PerftPosition::parse_epd(include_str!("perft.epd")).expect("perft.epd parses")
+}
+
+#[test]
+fn epd_positions_match_known_counts() {
+    for position in positions() {
+        for &(depth, expected) in position.counts.iter().filter(|(_, nodes)| *nodes <= MAX_NODES) {
+            assert_eq!(position.perft(depth), expected, "line {} depth {}: {}", position.line, depth, position.fen);
+        }
+    }
+}
+
+#[test]
+fn divide_sums_to_perft() {
+    for position in positions() {
+        let (mut board, color) = Board::from_fen(&position.fen).unwrap();
+        let divide = board.divide(color, 2);
+        assert_eq!(divide.len() as u64, board.perft(color, 1));
+        assert_eq!(divide.iter().map(|(_, nodes)| nodes).sum::<u64>(), board.perft(color, 2));
+    }
+}
+
+#[test]
+fn perft_restores_board() {
+    let mut board = Board::new();
+    let (fen, hash) = (board.fen_placement(), board.get_hash());
+    board.perft(Color::Red, 3);
+    assert_eq!(board.fen_placement(), fen);
+    assert_eq!(board.get_hash(), hash);
+}
+
+#[test]
+fn parse_epd_reports_bad_lines() {
+    assert!(PerftPosition::parse_epd("9/9 w ;D1 1").is_err());
+    assert!(PerftPosition::parse_epd("rnbakabnr/9/1c5c1/p1p1p1p1p/9/9/P1P1P1P1P/1C5C1/9/RNBAKABNR w ;D1 x").is_err());
+}
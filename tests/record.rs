@@ -0,0 +1,49 @@
+use chinese_chess_engine::{Color, GameRecord, GameStatus, Move, PgnError, SvgOptions};
+
+#[test]
+fn draw_result_is_not_stalemate() {
+    let record = GameRecord::from_pgn("[Result \"1/2-1/2\"]\n1. 炮二平五 马8进7 1/2-1/2").unwrap();
+    assert_eq!(record.result, GameStatus::Draw);
+    assert_eq!(record.moves.len(), 2);
+}
+
+#[test]
+fn illegal_move_reports_its_ply() {
+    // 第二步的目标格上是黑方自己的炮
+    let error = GameRecord::from_pgn("1. h2e2 h9h7").unwrap_err();
+    assert_eq!(error, PgnError::IllegalMove { ply: 2, text: "h9h7".to_string() });
+}
+
+// 黑方先走的残局：黑车平中路后红帅无处可走
+const STALEMATE_PGN: &str = "[FEN \"5k3/9/9/r8/9/9/9/9/8r/3K5 b\"]\n1... a6e6";
+
+#[test]
+fn fen_tag_sets_the_start_position() {
+    let record = GameRecord::from_pgn(STALEMATE_PGN).unwrap();
+    let (board, color) = record.start.as_ref().unwrap();
+    assert_eq!(*color, Color::Black);
+    assert_eq!(board.fen_placement(), "5k3/9/9/r8/9/9/9/9/8r/3K5");
+    assert_eq!(record.moves, vec![Move::from_iccs("a6e6").unwrap()]);
+
+    let error = GameRecord::from_pgn("[FEN \"5k3/9 b\"]\n").unwrap_err();
+    assert!(matches!(error, PgnError::BadFen(_)));
+}
+
+#[test]
+fn stalemate_loser_is_the_side_to_move() {
+    // 只走了一步，但先走的是黑方，困毙的是红方
+    let mut record = GameRecord::from_pgn(STALEMATE_PGN).unwrap();
+    let (mut board, _) = record.start_position();
+    board.make_move(record.moves[0]);
+    assert!(board.is_stalemate(Color::Red));
+
+    record.result = GameStatus::Stalemate;
+    let html = record.to_html(&SvgOptions::default());
+    assert!(html.contains("<div class=\"result\">红方困毙 黑胜 0-1</div>"));
+
+    // 从初始局面开始时同样按轮到走棋的一方判断
+    let mut record = GameRecord::from_pgn("1. h2e2").unwrap();
+    record.result = GameStatus::Stalemate;
+    let html = record.to_html(&SvgOptions::default());
+    assert!(html.contains("<div class=\"result\">黑方困毙 红胜 1-0</div>"));
+}
@@ -0,0 +1,73 @@
+use chinese_chess_engine::Engine;
+
+#[test]
+fn rules_script_passes() {
+    let mut engine = Engine::new();
+    let report = engine.run_script(include_str!("../scripts/rules.txt"));
+    assert!(report.passed(), "{}", report);
+    assert_eq!(report.counts(), (13, 0));
+}
+
+#[test]
+fn failed_expect_reports_its_line() {
+    let script = "\
+LET g = CREATE GAME
+
+GAME $g RED MOVE (1,0) TO (3,1)
+EXPECT MOVE_SUCCESS $g
+";
+    let mut engine = Engine::new();
+    let report = engine.run_script(script);
+    // 被拒绝的走法没有通过检查，同样记为失败
+    let lines: Vec<usize> = report.failures().map(|step| step.line).collect();
+    assert_eq!(lines, vec![3, 4]);
+    let expect = report.failures().last().unwrap();
+    assert_eq!(expect.message, "expected MOVE_SUCCESS 1, found MOVE_FAILED 1 HORSE_LEG_BLOCKED");
+    assert!(report.to_string().contains("FAIL 4: EXPECT MOVE_SUCCESS $g"));
+}
+
+#[test]
+fn dollar_and_hash_inside_strings_are_literal() {
+    let script = "\
+LET g = CREATE GAME
+JOIN GAME $g \"$price #1 \\\"x\\\"\" RED
+EXPECT JOIN_SUCCESS $g # 字符串之外的 # 才是注释
+";
+    let mut engine = Engine::new();
+    let report = engine.run_script(script);
+    assert!(report.passed(), "{}", report);
+
+    let game = engine.get_game_manager().get_game(1).unwrap();
+    assert_eq!(game.players[0].name, "$price #1 \"x\"");
+}
+
+#[test]
+fn saved_game_replays_through_run_script() {
+    let mut engine = Engine::new();
+    engine.execute("CREATE GAME");
+    engine.execute("GAME 1 RED MOVE (1,2) TO (1,9)");
+    engine.execute("GAME 1 BLACK MOVE (0,9) TO (1,9)");
+    engine.execute("GAME 1 RED MOVE (7,2) TO (4,2)");
+    engine.execute("CREATE GAME");
+    engine.execute("GAME 2 RED MOVE (7,2) TO (4,2)");
+
+    let script = engine.save_script(1).unwrap();
+    assert_eq!(script, "\
+CREATE GAME
+GAME 1 RED MOVE (1,2) TO (1,9)
+GAME 1 BLACK MOVE (0,9) TO (1,9)
+GAME 1 RED MOVE (7,2) TO (4,2)
+");
+    // 使用对局自己的ID
+    assert_eq!(engine.save_script(2).unwrap(), "CREATE GAME\nGAME 2 RED MOVE (7,2) TO (4,2)\n");
+    assert!(engine.save_script(3).is_none());
+
+    // 在新引擎中重放并检查局面
+    let board = engine.execute_to_string("GET GAME 1 BOARD");
+    let fen = board.lines().next().unwrap().trim_start_matches("GAME_BOARD 1 ");
+    let mut replay = Engine::new();
+    let report = replay.run_script(&format!("{}EXPECT BOARD {}\n", script, fen));
+    assert!(report.passed(), "{}", report);
+    assert_eq!(report.counts(), (5, 0));
+    assert_eq!(replay.execute_to_string("GET GAME 1 HISTORY"), engine.execute_to_string("GET GAME 1 HISTORY"));
+}
@@ -0,0 +1,102 @@
+use chinese_chess_engine::{Board, Color, Engine, Move, Position, SvgOptions};
+
+// 检查标签成对出现、属性值都带引号、文本中没有未转义的 < 和 &
+fn assert_well_formed(svg: &str) {
+    let mut open: Vec<&str> = Vec::new();
+    let mut rest = svg;
+    while let Some(start) = rest.find('<') {
+        let text = &rest[..start];
+        assert!(!text.contains('&') && !text.contains('>'), "bad text {:?}", text);
+
+        let end = rest[start..].find('>').expect("unclosed tag") + start;
+        let tag = &rest[start + 1..end];
+        assert_eq!(tag.matches('"').count() % 2, 0, "unbalanced quotes in <{}>", tag);
+        if let Some(name) = tag.strip_prefix('/') {
+            assert_eq!(open.pop(), Some(name), "unexpected </{}>", name);
+        } else if !tag.ends_with('/') {
+            let name = tag.split_whitespace().next().unwrap();
+            open.push(name);
+        }
+        rest = &rest[end + 1..];
+    }
+    assert!(rest.trim().is_empty());
+    assert!(open.is_empty(), "unclosed {:?}", open);
+}
+
+fn piece_count(svg: &str) -> usize {
+    svg.matches(r##"r="22.0" fill="#f8e7c3""##).count()
+}
+
+#[test]
+fn initial_board_is_well_formed() {
+    let svg = Board::new().to_svg(&SvgOptions::default());
+    assert_well_formed(&svg);
+    assert!(svg.starts_with(r#"<svg xmlns="http://www.w3.org/2000/svg" width="500" height="550" viewBox="0 0 500 550""#));
+    assert_eq!(piece_count(&svg), 32);
+    assert_eq!(svg.matches(">帅</text>").count(), 1);
+    assert_eq!(svg.matches(">将</text>").count(), 1);
+    // 没有上一步时不画箭头
+    assert!(!svg.contains("marker-end"));
+}
+
+#[test]
+fn pieces_and_last_move_are_drawn_at_their_points() {
+    // 炮二平五
+    let mv = Move::from_iccs("h2e2").unwrap();
+    let mut board = Board::new();
+    board.make_move(mv);
+    let options = SvgOptions {
+        last_move: Some(mv),
+        highlights: vec![Position::try_new(4, 2).unwrap()],
+        ..SvgOptions::default()
+    };
+    let svg = board.to_svg(&options);
+    assert_well_formed(&svg);
+    assert_eq!(piece_count(&svg), 32);
+
+    // (4,2) 的红炮，画布坐标为 (50 + 4 * 50, 50 + 7 * 50)
+    assert!(svg.contains(r##"<circle cx="250.0" cy="400.0" r="22.0" fill="#f8e7c3" stroke="#c0141c" stroke-width="2"/>"##));
+    assert!(svg.contains(r##"<text x="250.0" y="400.0" font-size="24.2" fill="#c0141c" text-anchor="middle" dominant-baseline="central">炮</text>"##));
+    assert!(!svg.contains(r#"<circle cx="400.0" cy="400.0""#));
+
+    // 箭头两端各缩进 0.3 格
+    assert!(svg.contains(r##"<line x1="385.0" y1="400.0" x2="265.0" y2="400.0" stroke="#2a9d3a" stroke-width="5.0" stroke-opacity="0.8" stroke-linecap="round" marker-end="url(#arrowhead)"/>"##));
+    assert!(svg.contains(r##"<rect x="226.0" y="376.0" width="48.0" height="48.0" rx="7.2" fill="#3c8dde" fill-opacity="0.35"/>"##));
+}
+
+#[test]
+fn black_perspective_flips_the_board() {
+    let mut board = Board::new();
+    board.make_move(Move::from_iccs("h2e2").unwrap());
+    let options = SvgOptions {
+        perspective: Color::Black,
+        coordinates: false,
+        ..SvgOptions::default()
+    };
+    let svg = board.to_svg(&options);
+    assert_well_formed(&svg);
+    // (4,2) 在上方第 2 行
+    assert!(svg.contains(r#"<circle cx="250.0" cy="150.0" r="22.0""#));
+    // 黑将在下方
+    assert!(svg.contains(r##"<text x="250.0" y="500.0" font-size="24.2" fill="#1a1a1a" text-anchor="middle" dominant-baseline="central">将</text>"##));
+    assert!(!svg.contains(">0</text>"));
+}
+
+#[test]
+fn game_frames_follow_the_moves() {
+    let mut engine = Engine::new();
+    engine.execute("CREATE GAME");
+    engine.execute("GAME 1 RED MOVE (1,2) TO (1,9)");
+    engine.execute("GAME 1 BLACK MOVE (0,9) TO (1,9)");
+    let game = engine.get_game_manager().get_game(1).unwrap();
+
+    let frames = game.to_svg_frames(&SvgOptions::default());
+    assert_eq!(frames.len(), 3);
+    let counts: Vec<usize> = frames.iter().map(|frame| piece_count(frame)).collect();
+    assert_eq!(counts, vec![32, 31, 30]);
+    for frame in &frames {
+        assert_well_formed(frame);
+    }
+    assert!(!frames[0].contains("marker-end"));
+    assert_eq!(frames[2].matches("marker-end").count(), 1);
+}
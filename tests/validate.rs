@@ -0,0 +1,62 @@
+use chinese_chess_engine::core::validate::Inconsistency;
+use chinese_chess_engine::{Board, Color, FenError, Piece, PieceType, Position};
+
+// 导入 FEN 时发现的所有问题
+fn issues(fen: &str) -> Vec<Inconsistency> {
+    match Board::from_fen(fen) {
+        Err(FenError::Inconsistent(report)) => report.issues,
+        other => panic!("expected an inconsistent position, got {:?}", other.map(|_| ())),
+    }
+}
+
+#[test]
+fn too_many_advisors() {
+    assert_eq!(issues("3k5/9/9/9/9/9/9/9/4A4/3AKA3 w"), vec![Inconsistency::TooManyPieces {
+        piece: Piece::new(PieceType::Advisor, Color::Red),
+        count: 3,
+    }]);
+}
+
+#[test]
+fn elephant_off_its_points() {
+    assert_eq!(issues("3k5/9/9/9/9/9/9/9/9/3BK4 w"), vec![Inconsistency::IllegalPlacement {
+        pos: Position::try_new(3, 0).unwrap(),
+        piece: Piece::new(PieceType::Elephant, Color::Red),
+    }]);
+}
+
+#[test]
+fn unadvanced_pawn_on_odd_file() {
+    assert_eq!(issues("3k5/9/9/9/9/9/1P7/9/9/4K4 w"), vec![Inconsistency::IllegalPlacement {
+        pos: Position::try_new(1, 3).unwrap(),
+        piece: Piece::new(PieceType::Pawn, Color::Red),
+    }]);
+    // 过河后可以在任意列
+    assert!(Board::from_fen("3k5/9/9/1P7/9/9/9/9/9/4K4 w").is_ok());
+}
+
+#[test]
+fn facing_kings_are_rejected_on_import() {
+    assert_eq!(issues("4k4/9/9/9/9/9/9/9/9/4K4 w"), vec![Inconsistency::KingsFacing {
+        red: Position::try_new(4, 0).unwrap(),
+        black: Position::try_new(4, 9).unwrap(),
+    }]);
+    // 中间有棋子时不算照面
+    assert!(Board::from_fen("4k4/9/9/9/4p4/9/9/9/9/4K4 w").is_ok());
+}
+
+#[test]
+fn desynced_hash_after_set_piece() {
+    let mut board = Board::new();
+    board.set_piece(Position::try_new(0, 3).unwrap(), Piece::default());
+    assert!(board.validate().is_ok());
+
+    // 保存的对局中哈希值被改动，读回后与棋子不一致
+    let json = serde_json::to_string(&board).unwrap();
+    let tampered = json.replace(&format!("\"current_hash\":{}", board.get_hash()), "\"current_hash\":1");
+    assert_ne!(tampered, json);
+    let loaded: Board = serde_json::from_str(&tampered).unwrap();
+
+    let report = loaded.validate().unwrap_err();
+    assert_eq!(report.issues, vec![Inconsistency::Hash { stored: 1, computed: board.get_hash() }]);
+}
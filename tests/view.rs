@@ -0,0 +1,87 @@
+use chinese_chess_engine::{Engine, Language, OutputFormat};
+
+// 双方入座，炮五进四吃中卒
+fn played_engine() -> Engine {
+    let mut engine = Engine::new();
+    for command in [
+        "CREATE GAME",
+        "JOIN GAME 1 张三 RED",
+        "JOIN GAME 1 李四 BLACK",
+        "GAME 1 RED MOVE (7,2) TO (4,2)",
+        "GAME 1 BLACK MOVE (7,9) TO (6,7)",
+        "GAME 1 RED MOVE (4,2) TO (4,6)",
+    ] {
+        assert!(engine.execute(command).is_ok(), "{}", command);
+    }
+    engine
+}
+
+#[test]
+fn game_status_text_matches_golden() {
+    let mut engine = played_engine();
+    let golden = include_str!("golden/game_status.txt").trim_end();
+    let result = engine.execute("GET GAME 1");
+    assert_eq!(result.render_in(OutputFormat::Text, Language::English), golden);
+    // 成功结果的中文文本与英文相同，记谱和玩家名按原样输出
+    assert_eq!(result.render_in(OutputFormat::Text, Language::Chinese), golden);
+    assert_eq!(engine.execute_to_string("查看 游戏 1 状态"), golden);
+}
+
+#[test]
+fn game_status_json_matches_golden() {
+    let mut engine = played_engine();
+    let golden: serde_json::Value = serde_json::from_str(include_str!("golden/game_status.json")).unwrap();
+    let result = engine.execute("GET GAME 1");
+    for language in [Language::English, Language::Chinese] {
+        let json: serde_json::Value = serde_json::from_str(&result.render_in(OutputFormat::Json, language)).unwrap();
+        assert_eq!(json, golden);
+    }
+
+    let payload = &golden["payload"];
+    assert_eq!(payload["last_move"]["chinese"], "炮五进四");
+    assert_eq!(payload["players"][0]["name"], "张三");
+    // JSON 中的棋盘图与文本结果中的相同
+    let text = engine.execute_to_string("GET GAME 1");
+    let grid = text.find("   0  1").unwrap();
+    assert_eq!(payload["board"].as_str().unwrap(), format!("{}\n", &text[grid..]));
+}
+
+#[test]
+fn finished_game_reports_result_and_check() {
+    let mut engine = Engine::new();
+    engine.set_language(Language::Chinese);
+    engine.execute("CREATE GAME");
+    // 五步杀
+    for command in [
+        "GAME 1 RED MOVE (1,2) TO (2,2)",
+        "GAME 1 BLACK MOVE (1,7) TO (2,7)",
+        "GAME 1 RED MOVE (2,2) TO (2,6)",
+        "GAME 1 BLACK MOVE (5,9) TO (4,8)",
+        "GAME 1 RED MOVE (2,6) TO (2,9)",
+    ] {
+        engine.execute(command);
+    }
+
+    let text = engine.execute_to_string("GET GAME 1");
+    let head: Vec<&str> = text.lines().take(8).collect();
+    assert_eq!(head, vec![
+        "GAME_STATUS 1 RedWon",
+        "FEN rnCak1bnr/4a4/2c4c1/p3p1p1p/9/9/P1P1P1P1P/7C1/9/RNBAKABNR b - - 0 3",
+        "TURN BLACK",
+        "CHECK YES",
+        "MOVES 5",
+        "LAST_MOVE (2,6) TO (2,9) c6c9 炮七进三",
+        "CAPTURED RED -",
+        "CAPTURED BLACK p b",
+    ]);
+
+    engine.set_output_format(OutputFormat::Json);
+    let json: serde_json::Value = serde_json::from_str(&engine.execute_to_string("GET GAME 1")).unwrap();
+    assert_eq!(json["payload"]["status"], "RedWon");
+    assert_eq!(json["payload"]["in_check"], true);
+    assert_eq!(json["payload"]["players"], serde_json::json!([]));
+    assert_eq!(json["payload"]["captured_black"], serde_json::json!([
+        {"piece_type": "Pawn", "color": "Black"},
+        {"piece_type": "Elephant", "color": "Black"},
+    ]));
+}